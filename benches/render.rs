@@ -0,0 +1,56 @@
+// A dependency-free benchmark, run with `cargo bench`. Prints the mean time
+// per render of a few document shapes.
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+use tiptap_to_html::ProseMirror;
+
+fn article(blocks: usize) -> Value {
+    let content: Vec<Value> = (0..blocks)
+        .map(|index| match index % 4 {
+            0 => json!({"type":"heading","attrs":{"level":2},"content":[{"type":"text","text":format!("Period {}", index)}]}),
+            1 => json!({"type":"paragraph","content":[
+                {"type":"text","text":"The Leafs "},
+                {"type":"text","text":"tied it up","marks":[{"type":"bold"},{"type":"italic"}]},
+                {"type":"text","text":" with "},
+                {"type":"text","text":"a goal","marks":[{"type":"link","attrs":{"href":"https://nhl.com","target":"_blank"}}]},
+                {"type":"text","text":" & seconds <left> on the clock."}
+            ]}),
+            2 => json!({"type":"bulletList","content":[
+                {"type":"listItem","content":[{"type":"paragraph","content":[{"type":"text","text":"Matthews"}]}]},
+                {"type":"listItem","content":[{"type":"paragraph","content":[{"type":"text","text":"Marner"}]}]}
+            ]}),
+            _ => json!({"type":"codeBlock","attrs":{"language":"rust"},"content":[{"type":"text","text":"let score = (3, 2);"}]}),
+        })
+        .collect();
+    json!({"type":"doc","content":content})
+}
+
+fn bench(name: &str, prose_mirror: &ProseMirror, content: &Value) {
+    let budget = Duration::from_secs(2);
+    let started = Instant::now();
+    let mut iterations = 0u32;
+    while started.elapsed() < budget {
+        black_box(prose_mirror.render(black_box(content)).unwrap());
+        iterations += 1;
+    }
+    let mean = started.elapsed() / iterations;
+    println!(
+        "{:<24} {:>12?}/render ({} iterations)",
+        name, mean, iterations
+    );
+}
+
+fn main() {
+    let prose_mirror = ProseMirror::with_default_plugins();
+    bench("article/10", &prose_mirror, &article(10));
+    bench("article/1000", &prose_mirror, &article(1000));
+    let long_text = json!({"type":"doc","content":[
+        {"type":"paragraph","content":[{"type":"text","text":"Go Leafs go! ".repeat(50_000)}]}
+    ]});
+    bench("long-text", &prose_mirror, &long_text);
+
+    let static_plugins = ProseMirror::with_static_plugins();
+    bench("static/article/1000", &static_plugins, &article(1000));
+}
@@ -0,0 +1,36 @@
+#ifndef TIPTAP_TO_HTML_H
+#define TIPTAP_TO_HTML_H
+
+/* Build the shared library with `cargo build --release --features ffi` */
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define TIPTAP_OK 0
+#define TIPTAP_NULL_ARGUMENT 1
+#define TIPTAP_INVALID_UTF8 2
+#define TIPTAP_INVALID_JSON 3
+#define TIPTAP_INVALID_OPTIONS 4
+#define TIPTAP_RENDER_FAILED 5
+
+/*
+ * Renders Tiptap JSON to HTML. `options_json` is a RenderConfig object such as
+ * {"plugins": ["starter-kit", "tables"]}, or NULL for the defaults.
+ * Returns NULL on failure, otherwise a string to release with tiptap_free.
+ */
+char *tiptap_render(const char *json, const char *options_json);
+
+/* The error code of the last call on this thread, TIPTAP_OK if it succeeded */
+int tiptap_last_error_code(void);
+
+/* Its message, valid until the next call into the library on this thread */
+const char *tiptap_last_error_message(void);
+
+void tiptap_free(char *s);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,41 @@
+use napi::{Error, Result};
+use napi_derive::napi;
+use serde_json::Value;
+
+use tiptap_to_html::{ProseMirror, RenderConfig};
+
+fn renderer(options: Option<Value>) -> Result<ProseMirror> {
+    RenderConfig::from_json(&options.unwrap_or_default())
+        .and_then(|config| config.build())
+        .map_err(|err| Error::from_reason(err.to_string()))
+}
+
+// Same shape as `@tiptap/html`'s `generateHTML(doc, extensions)`, with a
+// `RenderConfig` object naming the plugin sets in place of extensions
+#[napi]
+pub fn render(doc: Value, options: Option<Value>) -> Result<String> {
+    Renderer::new(options)?.render(doc)
+}
+
+// Keeps the configured renderer around, for rendering many documents
+#[napi]
+pub struct Renderer {
+    prosemirror: ProseMirror,
+}
+
+#[napi]
+impl Renderer {
+    #[napi(constructor)]
+    pub fn new(options: Option<Value>) -> Result<Self> {
+        Ok(Self {
+            prosemirror: renderer(options)?,
+        })
+    }
+
+    #[napi]
+    pub fn render(&self, doc: Value) -> Result<String> {
+        self.prosemirror
+            .render(&doc)
+            .map_err(|err| Error::from_reason(err.to_string()))
+    }
+}
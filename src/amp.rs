@@ -0,0 +1,210 @@
+use serde_json::{Map, Value};
+
+use crate::attrs::AttrFilter;
+use crate::error::ProseMirrorError;
+use crate::plugins::Plugin;
+use crate::sanitize::strip_elements;
+use crate::{ProseMirror, RenderContext};
+
+// Attributes the AMP validator rejects on any element
+const DISALLOWED_ATTRS: [&str; 5] = ["contenteditable", "decoding", "loading", "srcdoc", "xmlns"];
+
+// Custom scripts and raw embeds aren't allowed on AMP pages
+const STRIPPED_TAGS: [&str; 6] = ["embed", "frame", "iframe", "object", "script", "style"];
+
+fn dimension(attrs: &Map<String, Value>, key: &str, default: u32) -> Value {
+    let valid = attrs.get(key).filter(|value| match value {
+        Value::Number(number) => number.as_u64().is_some_and(|number| number > 0),
+        Value::String(value) => value.parse::<u32>().is_ok_and(|number| number > 0),
+        _ => false,
+    });
+    valid.cloned().unwrap_or(Value::from(default))
+}
+
+// <amp-img> needs explicit dimensions, images without them get the profile's
+// default size and `layout="responsive"` scales them to the container
+pub struct AmpImagePlugin {
+    width: u32,
+    height: u32,
+}
+
+impl Plugin for AmpImagePlugin {
+    fn render(&self, node: &Value, ctx: &mut RenderContext) -> Result<String, ProseMirrorError> {
+        let node_attrs = ctx.node_attrs("image", node).unwrap_or_default();
+        let mut attrs = Map::new();
+        for key in ["alt", "src", "srcset", "sizes"] {
+            if let Some(value) = node_attrs.get(key) {
+                attrs.insert(key.to_string(), value.clone());
+            }
+        }
+        ctx.sanitize_url_attr(&mut attrs, "src")?;
+        if let (Some(rewriter), Some(Value::String(src))) = (
+            ctx.options().image_src_rewriter.as_ref(),
+            attrs.get_mut("src"),
+        ) {
+            *src = rewriter(src);
+        }
+        if !attrs.contains_key("src") {
+            return Ok(String::new());
+        }
+        attrs.insert(
+            "width".to_string(),
+            dimension(&node_attrs, "width", self.width),
+        );
+        attrs.insert(
+            "height".to_string(),
+            dimension(&node_attrs, "height", self.height),
+        );
+        attrs.insert("layout".to_string(), Value::from("responsive"));
+
+        let img = ctx
+            .tag("amp-img", false)
+            .render_with_attrs(String::new(), Some(&attrs));
+        let caption = ["caption", "title"]
+            .iter()
+            .filter_map(|key| node_attrs.get(*key).and_then(|caption| caption.as_str()))
+            .find(|caption| !caption.is_empty());
+        Ok(match caption {
+            Some(caption) => {
+                let figcaption = ctx
+                    .tag("figcaption", false)
+                    .render_with_attrs(ctx.escape_text(caption), None);
+                ctx.tag("figure", false)
+                    .render_with_attrs(img + &figcaption, None)
+            }
+            None => img,
+        })
+    }
+}
+
+impl AmpImagePlugin {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self { width, height }
+    }
+}
+
+// AMP only allows https iframes, anything else is left out
+pub struct AmpIframePlugin {
+    width: u32,
+    height: u32,
+}
+
+impl Plugin for AmpIframePlugin {
+    fn render(&self, node: &Value, ctx: &mut RenderContext) -> Result<String, ProseMirrorError> {
+        let node_attrs = ctx.node_attrs("iframe", node).unwrap_or_default();
+        let mut attrs = Map::new();
+        for key in ["src", "title", "allow", "allowfullscreen", "referrerpolicy"] {
+            if let Some(value) = node_attrs.get(key) {
+                attrs.insert(key.to_string(), value.clone());
+            }
+        }
+        ctx.sanitize_url_attr(&mut attrs, "src")?;
+        let is_https = attrs
+            .get("src")
+            .and_then(|src| src.as_str())
+            .is_some_and(|src| src.trim().to_ascii_lowercase().starts_with("https://"));
+        if !is_https {
+            return Ok(String::new());
+        }
+        attrs.insert(
+            "width".to_string(),
+            dimension(&node_attrs, "width", self.width),
+        );
+        attrs.insert(
+            "height".to_string(),
+            dimension(&node_attrs, "height", self.height),
+        );
+        attrs.insert("layout".to_string(), Value::from("responsive"));
+        attrs.insert(
+            "sandbox".to_string(),
+            Value::from("allow-scripts allow-same-origin allow-popups"),
+        );
+        attrs.insert("frameborder".to_string(), Value::from("0"));
+
+        Ok(ctx
+            .tag("amp-iframe", false)
+            .render_with_attrs(String::new(), Some(&attrs)))
+    }
+}
+
+impl AmpIframePlugin {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self { width, height }
+    }
+}
+
+// An output profile for AMP pages. It overrides plugins, so apply it after
+// registering the defaults.
+pub struct AmpMode {
+    image_size: (u32, u32),
+    iframe_size: (u32, u32),
+}
+
+impl Default for AmpMode {
+    fn default() -> Self {
+        Self {
+            image_size: (1200, 675),
+            iframe_size: (560, 315),
+        }
+    }
+}
+
+impl AmpMode {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // The size used for images without their own, only the aspect ratio matters
+    pub fn with_image_size(mut self, width: u32, height: u32) -> Self {
+        self.image_size = (width, height);
+        self
+    }
+
+    pub fn with_iframe_size(mut self, width: u32, height: u32) -> Self {
+        self.iframe_size = (width, height);
+        self
+    }
+
+    pub fn apply(&self, prosemirror: &mut ProseMirror) {
+        let (width, height) = self.image_size;
+        prosemirror.add_plugin("image", Box::new(AmpImagePlugin::new(width, height)));
+        let (width, height) = self.iframe_size;
+        prosemirror.add_plugin("iframe", Box::new(AmpIframePlugin::new(width, height)));
+
+        let node_types = prosemirror.registered_types();
+        let options = prosemirror.options_mut();
+        options.strip_event_handlers = true;
+        for node_type in node_types {
+            options
+                .attr_filters
+                .entry(node_type)
+                .or_insert_with(|| AttrFilter::deny(&DISALLOWED_ATTRS));
+        }
+        prosemirror.add_post_processor(Box::new(|html| strip_elements(html, &STRIPPED_TAGS)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_renders_amp_html() {
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        AmpMode::new()
+            .with_image_size(800, 600)
+            .apply(&mut prose_mirror);
+        let content = json!({"type":"doc","content":[
+            {"type":"image","attrs":{"src":"/rink.png","alt":"Rink","width":"640","loading":"eager"}},
+            {"type":"paragraph","attrs":{"contenteditable":"true"},"content":[{"type":"text","text":"Go"}]},
+            {"type":"iframe","attrs":{"src":"https://www.youtube.com/embed/x","srcdoc":"<script></script>"}},
+            {"type":"iframe","attrs":{"src":"http://example.com"}}
+        ]});
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div><amp-img alt=\"Rink\" height=\"600\" layout=\"responsive\" src=\"/rink.png\" width=\"640\"></amp-img><p>Go</p><amp-iframe frameborder=\"0\" height=\"315\" layout=\"responsive\" sandbox=\"allow-scripts allow-same-origin allow-popups\" src=\"https://www.youtube.com/embed/x\" width=\"560\"></amp-iframe></div>"
+        );
+    }
+}
@@ -0,0 +1,100 @@
+// Renders a stored document while serializing, for API responses:
+//
+//     #[derive(Serialize, Deserialize)]
+//     struct Post {
+//         #[serde(with = "tiptap_to_html::as_html")]
+//         body: Value,
+//     }
+//
+// Deserializing reads the document JSON as is, so the same struct can load it
+use std::sync::OnceLock;
+
+use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+use crate::ProseMirror;
+
+static RENDERER: OnceLock<ProseMirror> = OnceLock::new();
+
+// The default plugins are used unless a renderer is set before the first
+// serialization, false if one is already in use
+pub fn set_renderer(prosemirror: ProseMirror) -> bool {
+    RENDERER.set(prosemirror).is_ok()
+}
+
+fn renderer() -> &'static ProseMirror {
+    RENDERER.get_or_init(ProseMirror::with_default_plugins)
+}
+
+pub fn serialize<S: Serializer>(content: &Value, serializer: S) -> Result<S::Ok, S::Error> {
+    let html = renderer()
+        .render(content)
+        .map_err(|err| ser::Error::custom(err.to_string()))?;
+    serializer.serialize_str(&html)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Value, D::Error> {
+    let content = Value::deserialize(deserializer)?;
+    match content {
+        Value::Object(_) | Value::Null => Ok(content),
+        _ => Err(de::Error::custom("expected a document object")),
+    }
+}
+
+// The same as a field type, for when an attribute can't be added
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RenderedHtml(pub Value);
+
+impl Serialize for RenderedHtml {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(&self.0, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for RenderedHtml {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize(deserializer).map(Self)
+    }
+}
+
+impl From<Value> for RenderedHtml {
+    fn from(content: Value) -> Self {
+        Self(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[derive(Serialize, Deserialize)]
+    struct Post {
+        title: String,
+        #[serde(with = "crate::as_html")]
+        body: Value,
+        summary: RenderedHtml,
+    }
+
+    #[test]
+    fn it_renders_fields_while_serializing() {
+        let post: Post = serde_json::from_value(json!({
+            "title": "Hi",
+            "body": {"type":"doc","content":[{"type":"paragraph"}]},
+            "summary": {"type":"text","text":"<short>"}
+        }))
+        .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&post).unwrap(),
+            json!({"title": "Hi", "body": "<div><p></p></div>", "summary": "&lt;short&gt;"})
+        );
+
+        let post = Post {
+            title: "Oops".to_string(),
+            body: json!({"type":"sparkle"}),
+            summary: RenderedHtml::default(),
+        };
+        assert!(serde_json::to_value(&post).is_err());
+    }
+}
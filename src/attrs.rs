@@ -0,0 +1,374 @@
+use serde_json::{Map, Value};
+
+pub type AttrTransform = Box<dyn Fn(&Value) -> Value + Send + Sync>;
+
+enum AttrRule {
+    Rename(String, String),
+    Drop(String),
+    Transform(String, AttrTransform),
+    KebabCase,
+}
+
+// Turns ProseMirror attrs into valid HTML attributes for one node type. Rules
+// run in the order they were added.
+#[derive(Default)]
+pub struct AttrMapping {
+    rules: Vec<AttrRule>,
+}
+
+impl AttrMapping {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn rename(mut self, from: &str, to: &str) -> Self {
+        self.rules
+            .push(AttrRule::Rename(from.to_string(), to.to_string()));
+        self
+    }
+
+    pub fn drop(mut self, attr: &str) -> Self {
+        self.rules.push(AttrRule::Drop(attr.to_string()));
+        self
+    }
+
+    pub fn transform<F>(mut self, attr: &str, transform: F) -> Self
+    where
+        F: Fn(&Value) -> Value + Send + Sync + 'static,
+    {
+        self.rules
+            .push(AttrRule::Transform(attr.to_string(), Box::new(transform)));
+        self
+    }
+
+    // `backgroundColor` -> `background-color` for every attr
+    pub fn kebab_case(mut self) -> Self {
+        self.rules.push(AttrRule::KebabCase);
+        self
+    }
+
+    pub fn apply(&self, attrs: &mut Map<String, Value>) {
+        for rule in &self.rules {
+            match rule {
+                AttrRule::Rename(from, to) => {
+                    if let Some(value) = attrs.remove(from) {
+                        attrs.insert(to.clone(), value);
+                    }
+                }
+                AttrRule::Drop(attr) => {
+                    attrs.remove(attr);
+                }
+                AttrRule::Transform(attr, transform) => {
+                    if let Some(value) = attrs.get_mut(attr) {
+                        *value = transform(value);
+                    }
+                }
+                AttrRule::KebabCase => {
+                    *attrs = std::mem::take(attrs)
+                        .into_iter()
+                        .map(|(key, value)| (kebab_case(&key), value))
+                        .collect();
+                }
+            }
+        }
+    }
+}
+
+// Which attrs from the document JSON a node type may emit
+#[derive(Debug, Clone)]
+pub enum AttrFilter {
+    AllowOnly(Vec<String>),
+    Deny(Vec<String>),
+}
+
+impl AttrFilter {
+    pub fn allow_only(attrs: &[&str]) -> Self {
+        Self::AllowOnly(attrs.iter().map(|attr| attr.to_string()).collect())
+    }
+
+    pub fn deny(attrs: &[&str]) -> Self {
+        Self::Deny(attrs.iter().map(|attr| attr.to_string()).collect())
+    }
+
+    pub fn allows(&self, attr: &str) -> bool {
+        match self {
+            Self::AllowOnly(allowed) => allowed.iter().any(|allowed| allowed == attr),
+            Self::Deny(denied) => !denied.iter().any(|denied| denied == attr),
+        }
+    }
+}
+
+// `onerror`, `onclick`... never make sense coming from stored content
+pub fn is_event_handler(attr: &str) -> bool {
+    attr.len() > 2 && attr[..2].eq_ignore_ascii_case("on")
+}
+
+// Standard HTML (and the MathML Tiptap extensions render) attribute names, for
+// telling a custom extension's own attrs apart
+const KNOWN_ATTRS: [&str; 126] = [
+    "abbr",
+    "accept",
+    "accept-charset",
+    "accesskey",
+    "action",
+    "align",
+    "allow",
+    "allowfullscreen",
+    "alt",
+    "async",
+    "autocapitalize",
+    "autocomplete",
+    "autofocus",
+    "autoplay",
+    "bgcolor",
+    "border",
+    "cellpadding",
+    "cellspacing",
+    "charset",
+    "checked",
+    "cite",
+    "class",
+    "cols",
+    "colspan",
+    "content",
+    "contenteditable",
+    "controls",
+    "coords",
+    "crossorigin",
+    "datetime",
+    "decoding",
+    "default",
+    "defer",
+    "dir",
+    "dirname",
+    "disabled",
+    "display",
+    "download",
+    "draggable",
+    "encoding",
+    "enctype",
+    "enterkeyhint",
+    "fetchpriority",
+    "for",
+    "form",
+    "frameborder",
+    "headers",
+    "height",
+    "hidden",
+    "high",
+    "href",
+    "hreflang",
+    "http-equiv",
+    "id",
+    "inert",
+    "inputmode",
+    "is",
+    "itemid",
+    "itemprop",
+    "itemref",
+    "itemscope",
+    "itemtype",
+    "kind",
+    "label",
+    "lang",
+    "list",
+    "loading",
+    "loop",
+    "low",
+    "mathvariant",
+    "max",
+    "maxlength",
+    "media",
+    "method",
+    "min",
+    "minlength",
+    "multiple",
+    "muted",
+    "name",
+    "nonce",
+    "novalidate",
+    "open",
+    "optimum",
+    "part",
+    "pattern",
+    "ping",
+    "placeholder",
+    "playsinline",
+    "popover",
+    "poster",
+    "preload",
+    "readonly",
+    "referrerpolicy",
+    "rel",
+    "required",
+    "reversed",
+    "role",
+    "rows",
+    "rowspan",
+    "sandbox",
+    "scope",
+    "selected",
+    "shape",
+    "size",
+    "sizes",
+    "slot",
+    "span",
+    "spellcheck",
+    "src",
+    "srcdoc",
+    "srclang",
+    "srcset",
+    "start",
+    "step",
+    "style",
+    "tabindex",
+    "target",
+    "title",
+    "translate",
+    "type",
+    "usemap",
+    "valign",
+    "value",
+    "width",
+    "wrap",
+    "xmlns",
+];
+
+// `data-` plus the kebab-cased name for anything that isn't an HTML attribute,
+// `data-*`, `aria-*` or namespaced like `xlink:href`
+pub fn data_attr_name(attr: &str) -> Option<String> {
+    let lower = attr.to_ascii_lowercase();
+    if lower.starts_with("data-")
+        || lower.starts_with("aria-")
+        || attr.contains(':')
+        || KNOWN_ATTRS.contains(&lower.as_str())
+    {
+        return None;
+    }
+    Some(format!("data-{}", kebab_case(attr)))
+}
+
+// Anything else could break out of the tag when written as `name="value"`
+pub fn is_valid_attr_name(attr: &str) -> bool {
+    !attr.is_empty()
+        && attr.chars().all(|c| {
+            !c.is_whitespace()
+                && !c.is_control()
+                && !matches!(c, '"' | '\'' | '>' | '<' | '/' | '=')
+        })
+}
+
+fn kebab_case(key: &str) -> String {
+    let mut kebab = String::with_capacity(key.len() + 2);
+    for c in key.chars() {
+        if c.is_ascii_uppercase() {
+            if !kebab.is_empty() {
+                kebab.push('-');
+            }
+            kebab.push(c.to_ascii_lowercase());
+        } else {
+            kebab.push(c);
+        }
+    }
+    kebab
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::UnknownAttrStrategy;
+    use crate::ProseMirror;
+    use serde_json::json;
+
+    #[test]
+    fn it_applies_rules_in_order() {
+        let mapping = AttrMapping::new()
+            .drop("internalId")
+            .rename("backgroundColor", "data-background")
+            .transform("data-background", |value| {
+                json!(format!("#{}", value.as_str().unwrap_or_default()))
+            })
+            .kebab_case();
+        let mut attrs = json!({"internalId":7,"backgroundColor":"00205b","dataTeamName":"Leafs"})
+            .as_object()
+            .cloned()
+            .unwrap();
+
+        mapping.apply(&mut attrs);
+        assert_eq!(
+            Value::Object(attrs),
+            json!({"data-background":"#00205b","data-team-name":"Leafs"})
+        );
+    }
+
+    #[test]
+    fn it_filters_attrs() {
+        let allow = AttrFilter::allow_only(&["src", "alt"]);
+        assert!(allow.allows("src"));
+        assert!(!allow.allows("title"));
+
+        let deny = AttrFilter::deny(&["style"]);
+        assert!(deny.allows("class"));
+        assert!(!deny.allows("style"));
+
+        assert!(is_event_handler("onError"));
+        assert!(!is_event_handler("on"));
+        assert!(!is_valid_attr_name("x\"><script>"));
+        assert!(is_valid_attr_name("data-id"));
+    }
+
+    #[test]
+    fn it_restricts_attrs_per_node_type() {
+        let prose_mirror = ProseMirror::builder()
+            .with_defaults()
+            .attr_filter(
+                "image",
+                AttrFilter::allow_only(&["src", "alt", "width", "height"]),
+            )
+            .attr_filter("paragraph", AttrFilter::deny(&["style"]))
+            .build();
+        let content = json!({"type":"doc","content":[
+            {"type":"paragraph","attrs":{"style":"x","class":"lead","onclick":"alert(1)"}},
+            {"type":"image","attrs":{"src":"/a.png","alt":"A","title":"t","onerror":"alert(1)"}}
+        ]});
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div><p class=\"lead\"></p><img alt=\"A\" decoding=\"async\" loading=\"lazy\" src=\"/a.png\" /></div>"
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn it_maps_attrs_per_node_type() {
+        let prose_mirror = ProseMirror::builder()
+            .with_defaults()
+            .attr_mapping("paragraph", AttrMapping::new().rename("nodeId", "data-id"))
+            .build();
+        let content = json!({"type":"blockquote","attrs":{"nodeId":"a"},"content":[
+            {"type":"paragraph","attrs":{"nodeId":"b"}}
+        ]});
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<blockquote nodeId=\"a\"><p data-id=\"b\"></p></blockquote>".to_string()
+        );
+    }
+
+    #[test]
+    fn it_prefixes_unknown_attrs_with_data() {
+        let prose_mirror = ProseMirror::builder()
+            .with_defaults()
+            .unknown_attrs(UnknownAttrStrategy::DataPrefix)
+            .build();
+        let content = json!({"type":"heading","attrs":{
+            "level":2,"blockId":"a1","id":"intro","aria-level":"2","data-x":"y","xml:lang":"en"
+        }});
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<h2 aria-level=\"2\" data-block-id=\"a1\" data-x=\"y\" id=\"intro\" xml:lang=\"en\"></h2>"
+        );
+        assert_eq!(data_attr_name("Title"), None);
+    }
+}
@@ -0,0 +1,451 @@
+use serde_json::{Map, Value};
+
+use crate::attrs::{AttrFilter, AttrMapping};
+use crate::error::ProseMirrorError;
+use crate::options::{
+    AttrCoercion, AttrOrder, AttrPolicy, EmptyNodePolicy, ExternalLinks, RenderOptions,
+    SerializationStyle, StyleStrategy, TagOverrides, TextAlignOutput, UnknownAttrStrategy,
+    UnknownNodeStrategy,
+};
+use crate::plugins::{FragmentDocPlugin, MarkPlugin, Plugin};
+use crate::sanitize::UrlPolicy;
+use crate::slug::HeadingAnchors;
+use crate::{
+    AmpMode, EmailMode, PostProcessor, ProseMirror, RenderCache, RenderContext, Transform,
+};
+
+#[derive(Default)]
+pub struct ProseMirrorBuilder {
+    prosemirror: ProseMirror,
+}
+
+impl ProseMirrorBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_plugin(mut self, node_type: &str, plugin: Box<dyn Plugin>) -> Self {
+        self.prosemirror.add_plugin(node_type, plugin);
+        self
+    }
+
+    pub fn with_fn_plugin<F>(mut self, node_type: &str, render: F) -> Self
+    where
+        F: Fn(&Value, &mut RenderContext) -> Result<String, ProseMirrorError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.prosemirror.add_fn_plugin(node_type, render);
+        self
+    }
+
+    pub fn with_mark_plugin(mut self, mark_type: &str, plugin: Box<dyn MarkPlugin>) -> Self {
+        self.prosemirror.add_mark_plugin(mark_type, plugin);
+        self
+    }
+
+    pub fn with_transform(mut self, transform: Box<dyn Transform>) -> Self {
+        self.prosemirror.add_transform(transform);
+        self
+    }
+
+    pub fn with_post_processor(mut self, post_processor: Box<dyn PostProcessor>) -> Self {
+        self.prosemirror.add_post_processor(post_processor);
+        self
+    }
+
+    pub fn with_cache(mut self, cache: Box<dyn RenderCache>) -> Self {
+        self.prosemirror.set_cache(cache);
+        self
+    }
+
+    pub fn with_defaults(mut self) -> Self {
+        self.prosemirror.register_defaults();
+        self
+    }
+
+    pub fn with_options(mut self, options: RenderOptions) -> Self {
+        self.prosemirror.options = options;
+        self
+    }
+
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.prosemirror.options.strict = strict;
+        self
+    }
+
+    pub fn unknown_nodes(mut self, strategy: UnknownNodeStrategy) -> Self {
+        self.prosemirror.options.unknown_nodes = strategy;
+        self
+    }
+
+    pub fn max_depth(mut self, max_depth: Option<usize>) -> Self {
+        self.prosemirror.options.max_depth = max_depth;
+        self
+    }
+
+    pub fn max_nodes(mut self, max_nodes: Option<usize>) -> Self {
+        self.prosemirror.options.max_nodes = max_nodes;
+        self
+    }
+
+    pub fn max_output_bytes(mut self, max_output_bytes: Option<usize>) -> Self {
+        self.prosemirror.options.max_output_bytes = max_output_bytes;
+        self
+    }
+
+    pub fn collect_pass(mut self, collect_pass: bool) -> Self {
+        self.prosemirror.options.collect_pass = collect_pass;
+        self
+    }
+
+    pub fn heading_anchors(mut self, heading_anchors: HeadingAnchors) -> Self {
+        self.prosemirror.options.heading_anchors = Some(heading_anchors);
+        self
+    }
+
+    pub fn src_rewriter<F>(mut self, rewriter: F) -> Self
+    where
+        F: Fn(&str) -> String + Send + Sync + 'static,
+    {
+        self.prosemirror.set_src_rewriter(rewriter);
+        self
+    }
+
+    pub fn link_rewriter<F>(mut self, rewriter: F) -> Self
+    where
+        F: Fn(&str) -> String + Send + Sync + 'static,
+    {
+        self.prosemirror.set_link_rewriter(rewriter);
+        self
+    }
+
+    pub fn external_links(mut self, external_links: ExternalLinks) -> Self {
+        self.prosemirror.options.external_links = Some(external_links);
+        self
+    }
+
+    pub fn text_align(mut self, text_align: TextAlignOutput) -> Self {
+        self.prosemirror.options.text_align = text_align;
+        self
+    }
+
+    pub fn attr_values(mut self, attr_values: AttrCoercion) -> Self {
+        self.prosemirror.options.attr_values = attr_values;
+        self
+    }
+
+    pub fn unknown_attrs(mut self, strategy: UnknownAttrStrategy) -> Self {
+        self.prosemirror.options.unknown_attrs = strategy;
+        self
+    }
+
+    pub fn style_strategy(mut self, style_strategy: StyleStrategy) -> Self {
+        self.prosemirror.options.style_strategy = style_strategy;
+        self
+    }
+
+    pub fn attr_order(mut self, attr_order: AttrOrder) -> Self {
+        self.prosemirror.options.attr_order = attr_order;
+        self
+    }
+
+    pub fn empty_nodes(mut self, empty_nodes: EmptyNodePolicy) -> Self {
+        self.prosemirror.options.empty_nodes = empty_nodes;
+        self
+    }
+
+    pub fn serialization(mut self, serialization: SerializationStyle) -> Self {
+        self.prosemirror.options.serialization = serialization;
+        self
+    }
+
+    // Overrides plugins, so it has to come after `with_defaults`
+    pub fn amp_mode(mut self, amp_mode: AmpMode) -> Self {
+        amp_mode.apply(&mut self.prosemirror);
+        self
+    }
+
+    // Overrides plugins, so it has to come after `with_defaults`
+    pub fn email_mode(mut self, email_mode: EmailMode) -> Self {
+        email_mode.apply(&mut self.prosemirror);
+        self
+    }
+
+    // Allows what the plugins registered so far render, so it comes after them
+    #[cfg(feature = "ammonia")]
+    pub fn clean_mode(mut self, clean_mode: crate::CleanMode) -> Self {
+        clean_mode.apply(&mut self.prosemirror);
+        self
+    }
+
+    // Renders the root `doc`'s children without the wrapping <div>, call it after
+    // `with_defaults` since it replaces the doc plugin
+    pub fn without_doc_wrapper(mut self) -> Self {
+        FragmentDocPlugin::register(&mut self.prosemirror);
+        self
+    }
+
+    pub fn tag_overrides(mut self, tag_overrides: TagOverrides) -> Self {
+        self.prosemirror.options.tag_overrides = tag_overrides;
+        self
+    }
+
+    pub fn pretty(mut self, indent: usize) -> Self {
+        self.prosemirror.options.indent = Some(indent);
+        self
+    }
+
+    pub fn minify(mut self, minify: bool) -> Self {
+        self.prosemirror.options.minify = minify;
+        self
+    }
+
+    pub fn highlight_terms(mut self, terms: &[String]) -> Self {
+        self.prosemirror.options.highlight_terms = terms.to_vec();
+        self
+    }
+
+    pub fn escape_html(mut self, escape_html: bool) -> Self {
+        self.prosemirror.options.escape_html = escape_html;
+        self
+    }
+
+    pub fn url_policy(mut self, url_policy: UrlPolicy) -> Self {
+        self.prosemirror.options.url_policy = url_policy;
+        self
+    }
+
+    pub fn attr_policy(mut self, node_type: &str, policy: AttrPolicy) -> Self {
+        self.prosemirror.set_attr_policy(node_type, policy);
+        self
+    }
+
+    pub fn attr_mapping(mut self, node_type: &str, mapping: AttrMapping) -> Self {
+        self.prosemirror.set_attr_mapping(node_type, mapping);
+        self
+    }
+
+    pub fn attr_filter(mut self, node_type: &str, filter: AttrFilter) -> Self {
+        self.prosemirror.set_attr_filter(node_type, filter);
+        self
+    }
+
+    pub fn html_attributes(mut self, node_type: &str, attrs: Map<String, Value>) -> Self {
+        self.prosemirror.set_html_attributes(node_type, attrs);
+        self
+    }
+
+    pub fn build(self) -> ProseMirror {
+        self.prosemirror
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ProseMirrorError;
+    use crate::plugins::marks::{CommentMarkPlugin, TextStyleMarkPlugin, YChangeMarkPlugin};
+    use crate::plugins::ParagraphPlugin;
+    use serde_json::json;
+
+    #[test]
+    fn it_builds_with_defaults() {
+        let content = json!({"type":"doc","content":[{"type":"paragraph","content":[{"text":"Built","type":"text"}]}]});
+        let prose_mirror = ProseMirror::builder().with_defaults().build();
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div><p>Built</p></div>".to_string()
+        );
+    }
+
+    #[test]
+    fn it_renders_doc_without_wrapper() {
+        let content = json!({"type":"doc","content":[
+            {"type":"paragraph","content":[{"type":"text","text":"Go"}]},
+            {"type":"paragraph","content":[{"type":"text","text":"Leafs"}]}
+        ]});
+        let prose_mirror = ProseMirror::builder()
+            .with_defaults()
+            .without_doc_wrapper()
+            .build();
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<p>Go</p><p>Leafs</p>".to_string()
+        );
+    }
+
+    #[test]
+    fn it_overrides_tags() {
+        let content = json!({"type":"doc","content":[
+            {"type":"paragraph","content":[{"type":"text","text":"Go","marks":[{"type":"bold"}]}]},
+            {"type":"codeBlock","content":[{"type":"text","text":"x"}]}
+        ]});
+        let prose_mirror = ProseMirror::builder()
+            .with_defaults()
+            .tag_overrides(
+                TagOverrides::new()
+                    .without_tag("doc")
+                    .with_tag("paragraph", "div")
+                    .with_tag("bold", "b")
+                    .with_tag("codeBlock", "div"),
+            )
+            .build();
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div><b>Go</b></div><div><code>x</code></div>".to_string()
+        );
+    }
+
+    #[test]
+    fn it_builds_with_single_plugin() {
+        let content = json!({"type":"paragraph"});
+        let prose_mirror = ProseMirror::builder()
+            .with_plugin("paragraph", Box::new(ParagraphPlugin::new()))
+            .build();
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<p></p>".to_string()
+        );
+    }
+
+    #[test]
+    fn it_skips_unknown_children_unless_strict() {
+        let content = json!({"type":"doc","content":[{"type":"youtube"}]});
+
+        let lenient = ProseMirror::builder().with_defaults().build();
+        assert_eq!(lenient.render(&content).unwrap(), "<div></div>".to_string());
+
+        let strict = ProseMirror::builder().with_defaults().strict(true).build();
+        assert_eq!(
+            strict.render(&content).unwrap_err(),
+            ProseMirrorError::TypeNotFound {
+                path: "/content/0".to_owned(),
+                type_name: Some("youtube".to_owned())
+            }
+        );
+    }
+
+    #[test]
+    fn it_merges_html_attributes_with_node_attrs() {
+        let content = json!({"type":"doc","content":[
+            {"type":"heading","attrs":{"level":2,"class":"title"},"content":[{"text":"Leafs","type":"text"}]},
+            {"type":"paragraph","content":[{"text":"Go ","type":"text"},{"text":"Leafs","type":"text","marks":[{"type":"bold"}]}]}
+        ]});
+        let prose_mirror = ProseMirror::builder()
+            .with_defaults()
+            .html_attributes(
+                "heading",
+                json!({"class":"prose-h"}).as_object().unwrap().clone(),
+            )
+            .html_attributes(
+                "bold",
+                json!({"class":"font-bold"}).as_object().unwrap().clone(),
+            )
+            .build();
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div><h2 class=\"prose-h title\">Leafs</h2><p>Go <strong class=\"font-bold\">Leafs</strong></p></div>"
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn it_merges_html_attributes_into_marks_with_attrs_of_their_own() {
+        let content = json!({"type":"paragraph","content":[
+            {"type":"text","text":"Go","marks":[{"type":"textStyle","attrs":{"color":"blue"}}]},
+            {"type":"text","text":"Leafs","marks":[{"type":"comment","attrs":{"commentId":"c1"}}]},
+            {"type":"text","text":"Go","marks":[{"type":"ychange","attrs":{"type":"added","user":"tim"}}]},
+            {"type":"text","text":"!","marks":[{"type":"ychange","attrs":{"type":null}}]}
+        ]});
+        let prose_mirror = ProseMirror::builder()
+            .with_defaults()
+            .with_mark_plugin("textStyle", Box::new(TextStyleMarkPlugin::new()))
+            .with_mark_plugin("comment", Box::new(CommentMarkPlugin::new()))
+            .with_mark_plugin("ychange", Box::new(YChangeMarkPlugin::new()))
+            .html_attributes(
+                "textStyle",
+                json!({"class":"styled","style":"font-weight: 600"})
+                    .as_object()
+                    .unwrap()
+                    .clone(),
+            )
+            .html_attributes(
+                "comment",
+                json!({"class":"comment"}).as_object().unwrap().clone(),
+            )
+            .html_attributes(
+                "ychange",
+                json!({"class":"change"}).as_object().unwrap().clone(),
+            )
+            .build();
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<p><span class=\"styled\" style=\"font-weight: 600; color: blue\">Go</span>\
+             <span class=\"comment\" data-comment-id=\"c1\">Leafs</span>\
+             <ins class=\"change\" data-user=\"tim\">Go</ins>!</p>"
+        );
+    }
+
+    #[test]
+    fn it_applies_unknown_node_strategy() {
+        let content = json!({"type":"doc","content":[
+            {"type":"youtube","content":[{"type":"paragraph","content":[{"text":"Caption","type":"text"}]}]}
+        ]});
+
+        let render = |strategy: UnknownNodeStrategy| {
+            ProseMirror::builder()
+                .with_defaults()
+                .unknown_nodes(strategy)
+                .build()
+                .render(&content)
+        };
+
+        assert_eq!(
+            render(UnknownNodeStrategy::Error).unwrap_err(),
+            ProseMirrorError::TypeNotFound {
+                path: "/content/0".to_owned(),
+                type_name: Some("youtube".to_owned())
+            }
+        );
+        assert_eq!(
+            render(UnknownNodeStrategy::Skip).unwrap(),
+            "<div></div>".to_string()
+        );
+        assert_eq!(
+            render(UnknownNodeStrategy::RenderChildren).unwrap(),
+            "<div><p>Caption</p></div>".to_string()
+        );
+        assert_eq!(
+            render(UnknownNodeStrategy::Placeholder(
+                "unsupported {type}".to_string()
+            ))
+            .unwrap(),
+            "<div><!-- unsupported youtube --></div>".to_string()
+        );
+    }
+
+    #[test]
+    fn it_injects_html_attributes() {
+        let content = json!({"type":"doc","content":[{"type":"paragraph","content":[{"text":"Styled","type":"text"}]}]});
+        let mut attrs = Map::new();
+        attrs.insert("class".to_string(), json!("prose-p"));
+
+        let prose_mirror = ProseMirror::builder()
+            .with_defaults()
+            .html_attributes("paragraph", attrs)
+            .build();
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div><p class=\"prose-p\">Styled</p></div>".to_string()
+        );
+    }
+}
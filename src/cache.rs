@@ -0,0 +1,152 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+
+use serde_json::Value;
+
+// Rendered HTML of top-level blocks, keyed by a hash of the block's JSON. A cache
+// is only valid for the renderer it was filled by, since the same block renders
+// differently with other plugins or options.
+pub trait RenderCache: Send + Sync {
+    fn get(&self, key: u64) -> Option<String>;
+    fn insert(&self, key: u64, html: String);
+}
+
+// Hashes the value itself rather than its serialization, so key order in the
+// JSON doesn't matter
+fn hash_value(value: &Value, hasher: &mut DefaultHasher) {
+    match value {
+        Value::Null => 0u8.hash(hasher),
+        Value::Bool(value) => (1u8, value).hash(hasher),
+        Value::Number(value) => (2u8, value.to_string()).hash(hasher),
+        Value::String(value) => (3u8, value).hash(hasher),
+        Value::Array(values) => {
+            (4u8, values.len()).hash(hasher);
+            for value in values {
+                hash_value(value, hasher);
+            }
+        }
+        Value::Object(map) => {
+            (5u8, map.len()).hash(hasher);
+            for (key, value) in map {
+                key.hash(hasher);
+                hash_value(value, hasher);
+            }
+        }
+    }
+}
+
+pub fn cache_key(node: &Value) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    hash_value(node, &mut hasher);
+    hasher.finish()
+}
+
+// An in-process cache that starts over once it holds `max_entries` blocks
+pub struct MemoryCache {
+    entries: Mutex<HashMap<u64, String>>,
+    max_entries: usize,
+}
+
+impl MemoryCache {
+    pub fn new(max_entries: usize) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            max_entries,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries
+            .lock()
+            .map(|entries| entries.len())
+            .unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl RenderCache for MemoryCache {
+    fn get(&self, key: u64) -> Option<String> {
+        self.entries.lock().ok()?.get(&key).cloned()
+    }
+
+    fn insert(&self, key: u64, html: String) {
+        if let Ok(mut entries) = self.entries.lock() {
+            if entries.len() >= self.max_entries {
+                entries.clear();
+            }
+            entries.insert(key, html);
+        }
+    }
+}
+
+impl<C: RenderCache + ?Sized> RenderCache for std::sync::Arc<C> {
+    fn get(&self, key: u64) -> Option<String> {
+        (**self).get(key)
+    }
+
+    fn insert(&self, key: u64, html: String) {
+        (**self).insert(key, html)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HeadingAnchors, ProseMirror};
+    use serde_json::json;
+    use std::sync::Arc;
+
+    #[test]
+    fn it_reuses_rendered_blocks() {
+        let cache = Arc::new(MemoryCache::new(16));
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        prose_mirror.set_cache(Box::new(cache.clone()));
+        let paragraph = json!({"type":"paragraph","content":[{"type":"text","text":"Go"}]});
+        let content = json!({"type":"doc","content":[paragraph, paragraph]});
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div><p>Go</p><p>Go</p></div>"
+        );
+        assert_eq!(cache.len(), 1);
+
+        cache.insert(cache_key(&paragraph), "<p>Cached</p>".to_string());
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div><p>Cached</p><p>Cached</p></div>"
+        );
+    }
+
+    #[test]
+    fn it_skips_the_cache_for_stateful_renders() {
+        let cache = Arc::new(MemoryCache::new(16));
+        let prose_mirror = ProseMirror::builder()
+            .with_defaults()
+            .heading_anchors(HeadingAnchors::new())
+            .with_cache(Box::new(cache.clone()))
+            .build();
+        let heading = json!({"type":"heading","attrs":{"level":1},"content":[{"type":"text","text":"Intro"}]});
+        let content = json!({"type":"doc","content":[heading, heading]});
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div><h1 id=\"intro\">Intro</h1><h1 id=\"intro-1\">Intro</h1></div>"
+        );
+        assert_eq!(cache.len(), 0);
+    }
+
+    #[test]
+    fn it_ignores_key_order() {
+        assert_eq!(
+            cache_key(&json!({"type":"paragraph","attrs":{"a":1,"b":2}})),
+            cache_key(
+                &serde_json::from_str(r#"{"attrs":{"b":2,"a":1},"type":"paragraph"}"#).unwrap()
+            )
+        );
+    }
+}
@@ -0,0 +1,350 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{PostProcessor, ProseMirror};
+
+type Elements = &'static [(&'static str, &'static [&'static str])];
+
+// The elements and attributes each built-in node and mark type renders, on top of
+// `GENERIC_ATTRIBUTES` and `data-*` which are allowed everywhere
+const KNOWN_TYPES: [(&str, Elements); 43] = [
+    ("doc", &[("div", &[])]),
+    ("paragraph", &[("p", &[])]),
+    (
+        "heading",
+        &[
+            ("h1", &[]),
+            ("h2", &[]),
+            ("h3", &[]),
+            ("h4", &[]),
+            ("h5", &[]),
+            ("h6", &[]),
+        ],
+    ),
+    ("bulletList", &[("ul", &[])]),
+    ("orderedList", &[("ol", &["start", "type"])]),
+    ("listItem", &[("li", &[])]),
+    ("blockquote", &[("blockquote", &[])]),
+    ("horizontalRule", &[("hr", &[])]),
+    ("hardBreak", &[("br", &[])]),
+    (
+        "image",
+        &[
+            (
+                "img",
+                &[
+                    "src", "alt", "title", "width", "height", "loading", "decoding", "srcset",
+                    "sizes",
+                ],
+            ),
+            ("figure", &[]),
+            ("figcaption", &[]),
+        ],
+    ),
+    (
+        "codeBlock",
+        &[("pre", &[]), ("code", &[]), ("span", &[]), ("div", &[])],
+    ),
+    (
+        "table",
+        &[
+            ("table", &[]),
+            ("thead", &[]),
+            ("tbody", &[]),
+            ("colgroup", &[]),
+            ("col", &["span"]),
+        ],
+    ),
+    ("tableRow", &[("tr", &[])]),
+    ("tableCell", &[("td", &["colspan", "rowspan", "colwidth"])]),
+    (
+        "tableHeader",
+        &[("th", &["colspan", "rowspan", "colwidth", "scope"])],
+    ),
+    ("taskList", &[("ul", &[])]),
+    (
+        "taskItem",
+        &[
+            ("li", &[]),
+            ("label", &[]),
+            ("input", &["type", "checked", "disabled"]),
+            ("div", &[]),
+        ],
+    ),
+    ("details", &[("details", &["open"])]),
+    ("detailsSummary", &[("summary", &[])]),
+    ("detailsContent", &[("div", &[])]),
+    ("footnoteReference", &[("sup", &[]), ("a", &["href"])]),
+    // The list of definitions at the end of the document
+    (
+        "footnote",
+        &[
+            ("section", &["role"]),
+            ("ol", &[]),
+            ("li", &[]),
+            ("p", &[]),
+            ("a", &["href"]),
+        ],
+    ),
+    (
+        "inlineMath",
+        &[
+            ("span", &[]),
+            ("math", &["display", "xmlns"]),
+            ("semantics", &[]),
+            ("annotation", &["encoding"]),
+            ("mrow", &[]),
+            ("mi", &["mathvariant"]),
+            ("mn", &[]),
+            ("mo", &["stretchy", "fence"]),
+            ("mtext", &[]),
+            ("msup", &[]),
+            ("msub", &[]),
+            ("msubsup", &[]),
+            ("mfrac", &[]),
+            ("msqrt", &[]),
+            ("mroot", &[]),
+            ("mstyle", &["displaystyle", "scriptlevel"]),
+            ("merror", &[]),
+        ],
+    ),
+    ("blockMath", &[("div", &[])]),
+    ("callout", &[("div", &["role"]), ("aside", &["role"])]),
+    ("mention", &[("span", &[]), ("a", &["href"])]),
+    ("emoji", &[("span", &[]), ("img", &["src", "alt"])]),
+    (
+        "iframe",
+        &[
+            ("div", &[]),
+            (
+                "iframe",
+                &[
+                    "src",
+                    "title",
+                    "width",
+                    "height",
+                    "loading",
+                    "allow",
+                    "allowfullscreen",
+                    "frameborder",
+                ],
+            ),
+        ],
+    ),
+    (
+        "audio",
+        &[
+            ("audio", &["src", "controls", "preload"]),
+            ("source", &["src", "type"]),
+        ],
+    ),
+    (
+        "video",
+        &[
+            (
+                "video",
+                &["src", "controls", "poster", "width", "height", "preload"],
+            ),
+            ("source", &["src", "type"]),
+        ],
+    ),
+    (
+        "tableOfContents",
+        &[
+            ("nav", &[]),
+            ("div", &[]),
+            ("h2", &[]),
+            ("ol", &[]),
+            ("li", &[]),
+            ("a", &["href"]),
+        ],
+    ),
+    ("bold", &[("strong", &[])]),
+    ("italic", &[("em", &[])]),
+    ("strike", &[("s", &[])]),
+    ("code", &[("code", &[])]),
+    ("underline", &[("u", &[])]),
+    ("subscript", &[("sub", &[])]),
+    ("superscript", &[("sup", &[])]),
+    ("link", &[("a", &["href", "target", "rel", "title"])]),
+    ("highlight", &[("mark", &[])]),
+    ("textStyle", &[("span", &[])]),
+    ("comment", &[("span", &[])]),
+    ("ychange", &[("ins", &[]), ("del", &[]), ("span", &[])]),
+];
+
+const GENERIC_ATTRIBUTES: [&str; 5] = ["class", "style", "id", "dir", "title"];
+
+const URL_SCHEMES: [&str; 4] = ["http", "https", "mailto", "tel"];
+
+// Runs the finished HTML through ammonia, as a second line of defense for user
+// content. Only what the registered plugins render is allowed, so apply it
+// after registering them, and add anything custom plugins render by hand.
+#[derive(Default)]
+pub struct CleanMode {
+    tags: Vec<(String, Vec<String>)>,
+}
+
+impl CleanMode {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_tag(mut self, tag: &str, attrs: &[&str]) -> Self {
+        self.tags.push((
+            tag.to_string(),
+            attrs.iter().map(|attr| attr.to_string()).collect(),
+        ));
+        self
+    }
+
+    pub fn apply(&self, prosemirror: &mut ProseMirror) {
+        let mut profile = CleanProfile::default();
+        for node_type in prosemirror.registered_types() {
+            let Some((_, tags)) = KNOWN_TYPES.iter().find(|(known, _)| *known == node_type) else {
+                continue;
+            };
+            let overridden = prosemirror.options().tag_overrides.get(&node_type);
+            for (index, (tag, attrs)) in tags.iter().enumerate() {
+                // An override replaces the type's main element
+                let tag = match overridden {
+                    Some(Some(tag)) if index == 0 => tag,
+                    Some(None) if index == 0 => continue,
+                    _ => tag,
+                };
+                profile.allow(tag, attrs.iter().copied());
+            }
+        }
+        for (tag, attrs) in &self.tags {
+            profile.allow(tag, attrs.iter().map(String::as_str));
+        }
+        if prosemirror.options().url_policy.allow_data_urls {
+            profile.url_schemes.insert("data".to_string());
+        }
+        prosemirror.add_post_processor(Box::new(profile));
+    }
+}
+
+struct CleanProfile {
+    tags: HashMap<String, HashSet<String>>,
+    url_schemes: HashSet<String>,
+}
+
+impl Default for CleanProfile {
+    fn default() -> Self {
+        Self {
+            tags: HashMap::new(),
+            url_schemes: URL_SCHEMES
+                .iter()
+                .map(|scheme| scheme.to_string())
+                .collect(),
+        }
+    }
+}
+
+impl CleanProfile {
+    fn allow<'a>(&mut self, tag: &str, attrs: impl Iterator<Item = &'a str>) {
+        self.tags
+            .entry(tag.to_string())
+            .or_default()
+            .extend(attrs.map(String::from));
+    }
+}
+
+impl PostProcessor for CleanProfile {
+    fn process(&self, html: String) -> String {
+        // The builder borrows the profile, which is cheap next to the parse
+        let mut builder = ammonia::Builder::empty();
+        builder
+            .tags(self.tags.keys().map(String::as_str).collect())
+            .tag_attributes(
+                self.tags
+                    .iter()
+                    .map(|(tag, attrs)| (tag.as_str(), attrs.iter().map(String::as_str).collect()))
+                    .collect(),
+            )
+            .generic_attributes(GENERIC_ATTRIBUTES.into_iter().collect())
+            .generic_attribute_prefixes(HashSet::from(["data-"]))
+            .url_schemes(self.url_schemes.iter().map(String::as_str).collect())
+            .link_rel(None)
+            .strip_comments(true);
+        builder.clean(&html).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::SerializationStyle;
+    use crate::plugins;
+    use serde_json::json;
+
+    #[test]
+    fn it_cleans_output_with_the_registered_plugins() {
+        let prose_mirror = ProseMirror::builder()
+            .with_defaults()
+            .escape_html(false)
+            .clean_mode(CleanMode::new())
+            .build();
+
+        let content = json!({"type":"doc","content":[
+            {"type":"paragraph","attrs":{"onclick":"go()"},"content":[
+                {"type":"text","text":"<script>go()</script><b>Hi</b> "},
+                {"type":"text","text":"link","marks":[{"type":"link","attrs":{"href":"https://example.com","target":"_blank"}}]}
+            ]},
+            {"type":"heading","attrs":{"level":2,"id":"intro"},"content":[{"type":"text","text":"Intro"}]}
+        ]});
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div><p>Hi <a href=\"https://example.com\" rel=\"noopener noreferrer\" target=\"_blank\">link</a></p><h2 id=\"intro\">Intro</h2></div>"
+        );
+    }
+
+    #[test]
+    fn it_keeps_everything_the_plugins_render() {
+        let mut prose_mirror = ProseMirror::new();
+        plugins::register_plugin_set(&mut prose_mirror, "all");
+        let content = json!({"type":"doc","content":[
+            {"type":"heading","attrs":{"level":1,"textAlign":"center"},"content":[{"type":"text","text":"Leafs"}]},
+            {"type":"paragraph","content":[
+                {"type":"text","text":"Last cup","marks":[{"type":"bold"},{"type":"highlight","attrs":{"color":"#ff0"}}]},
+                {"type":"footnoteReference","attrs":{"id":"cup"}},
+                {"type":"mention","attrs":{"id":"34","label":"Auston"}},
+                {"type":"emoji","attrs":{"name":"hockey"}},
+                {"type":"inlineMath","attrs":{"latex":"\\frac{a}{b}^2"}},
+                {"type":"text","text":"red","marks":[{"type":"textStyle","attrs":{"color":"red"}},{"type":"underline"},{"type":"subscript"}]}
+            ]},
+            {"type":"footnote","attrs":{"id":"cup"},"content":[{"type":"paragraph","content":[{"type":"text","text":"1967"}]}]},
+            {"type":"blockMath","attrs":{"latex":"\\sqrt{x}"}},
+            {"type":"orderedList","attrs":{"start":3},"content":[{"type":"listItem","content":[{"type":"paragraph"}]}]},
+            {"type":"taskList","content":[{"type":"taskItem","attrs":{"checked":true},"content":[{"type":"paragraph","content":[{"type":"text","text":"Win"}]}]}]},
+            {"type":"details","attrs":{"open":true},"content":[
+                {"type":"detailsSummary","content":[{"type":"text","text":"Lineup"}]},
+                {"type":"detailsContent","content":[{"type":"paragraph"}]}
+            ]},
+            {"type":"callout","attrs":{"variant":"warning"},"content":[{"type":"paragraph"}]},
+            {"type":"table","content":[{"type":"tableRow","content":[
+                {"type":"tableHeader","attrs":{"colspan":2,"rowspan":1,"colwidth":[100,50]},"content":[{"type":"paragraph"}]},
+                {"type":"tableCell","attrs":{"colspan":1,"rowspan":1,"colwidth":null},"content":[{"type":"paragraph"}]}
+            ]}]},
+            {"type":"image","attrs":{"src":"/rink.png","alt":"Rink","title":"Arena"}},
+            {"type":"video","attrs":{"src":"/goal.webm","controls":true}},
+            {"type":"audio","attrs":{"src":"/anthem.mp3"}},
+            {"type":"iframe","attrs":{"src":"https://www.youtube.com/embed/abc"}},
+            {"type":"codeBlock","attrs":{"language":"rust"},"content":[{"type":"text","text":"let x = 1;"}]},
+            {"type":"blockquote","content":[{"type":"paragraph","content":[
+                {"type":"text","text":"go","marks":[{"type":"link","attrs":{"href":"/go"}},{"type":"italic"},{"type":"strike"},{"type":"code"},{"type":"superscript"}]}
+            ]}]},
+            {"type":"horizontalRule"},
+            {"type":"paragraph","content":[{"type":"text","text":"a"},{"type":"hardBreak"}]}
+        ]});
+        prose_mirror.options_mut().serialization = SerializationStyle::Html5;
+        let html = prose_mirror.render(&content).unwrap();
+
+        CleanMode::new().apply(&mut prose_mirror);
+        // ammonia writes boolean attributes out with an empty value
+        assert_eq!(
+            prose_mirror.render(&content).unwrap().replace("=\"\"", ""),
+            html
+        );
+    }
+}
@@ -0,0 +1,219 @@
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::process::ExitCode;
+
+use serde_json::Value;
+use tiptap_to_html::ProseMirror;
+
+use super::page::Page;
+use super::{glob, Args};
+
+// Streams from the input straight into the output, so large exports never
+// have to fit in memory. A page needs the whole document for its title.
+fn convert(
+    prosemirror: &ProseMirror,
+    input: Option<&Path>,
+    output: Option<&Path>,
+    page: Option<&Page>,
+) -> Result<(), String> {
+    let reader: Box<dyn Read> = match input {
+        Some(path) => {
+            Box::new(BufReader::new(File::open(path).map_err(|err| {
+                format!("can't read {}: {}", path.display(), err)
+            })?))
+        }
+        None => Box::new(io::stdin().lock()),
+    };
+    let mut writer: Box<dyn Write> = match output {
+        Some(path) => {
+            Box::new(BufWriter::new(File::create(path).map_err(|err| {
+                format!("can't write {}: {}", path.display(), err)
+            })?))
+        }
+        None => Box::new(io::stdout().lock()),
+    };
+    let input_name = || {
+        input
+            .map(|path| path.display().to_string())
+            .unwrap_or_else(|| "stdin".to_string())
+    };
+    match page {
+        Some(page) => {
+            let content: Value = serde_json::from_reader(reader)
+                .map_err(|err| format!("{}: {}", input_name(), err))?;
+            let html = prosemirror
+                .render(&content)
+                .map_err(|err| format!("{}: {}", input_name(), err))?;
+            writer
+                .write_all(page.render(prosemirror, &content, &html).as_bytes())
+                .map_err(|err| err.to_string())?;
+        }
+        None => prosemirror
+            .render_reader(reader, &mut writer)
+            .map_err(|err| format!("{}: {}", input_name(), err))?,
+    }
+    writer.flush().map_err(|err| err.to_string())
+}
+
+// Every file matching `pattern` rendered into `out_dir`, keeping the directories
+// below the pattern's base. Returns the errors of the files that failed.
+fn convert_all(
+    prosemirror: &ProseMirror,
+    pattern: &str,
+    out_dir: &Path,
+    page: Option<&Page>,
+    parallel: bool,
+) -> Result<(usize, Vec<String>), String> {
+    let files = glob::expand(pattern)?;
+    let convert_one = |(input, relative): &(std::path::PathBuf, std::path::PathBuf)| {
+        let output = out_dir.join(relative).with_extension("html");
+        if let Some(parent) = output.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|err| format!("can't create {}: {}", parent.display(), err))?;
+        }
+        convert(prosemirror, Some(input), Some(&output), page)
+    };
+
+    let results: Vec<Result<(), String>> = if parallel {
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+            files.par_iter().map(convert_one).collect()
+        }
+        #[cfg(not(feature = "parallel"))]
+        return Err("--parallel needs a build with the `parallel` feature".to_string());
+    } else {
+        files.iter().map(convert_one).collect()
+    };
+    let errors: Vec<String> = results.into_iter().filter_map(|r| r.err()).collect();
+    Ok((files.len() - errors.len(), errors))
+}
+
+pub fn run(args: &[String]) -> Result<ExitCode, String> {
+    let args = Args::parse(args, &["parallel", "standalone"])?;
+    args.expect_only(&[
+        "input",
+        "output",
+        "plugins",
+        "config",
+        "glob",
+        "out-dir",
+        "parallel",
+        "template",
+        "standalone",
+        "stylesheet",
+    ])?;
+    let prosemirror = super::prosemirror(&args)?;
+    let page = Page::from_args(&args)?;
+
+    if let Some(pattern) = args.value("glob") {
+        let out_dir = args
+            .value("out-dir")
+            .ok_or("--glob needs an --out-dir to write to")?;
+        let (converted, errors) = convert_all(
+            &prosemirror,
+            pattern,
+            Path::new(out_dir),
+            page.as_ref(),
+            args.value("parallel").is_some(),
+        )?;
+        for err in &errors {
+            eprintln!("error: {}", err);
+        }
+        eprintln!(
+            "converted {} of {} documents",
+            converted,
+            converted + errors.len()
+        );
+        return Ok(if errors.is_empty() {
+            ExitCode::SUCCESS
+        } else {
+            ExitCode::FAILURE
+        });
+    }
+
+    let input = match (args.value("input"), args.positional()) {
+        (input, []) => input,
+        (None, [input]) => Some(input.as_str()),
+        _ => return Err("convert takes a single input file".to_string()),
+    };
+    match convert(
+        &prosemirror,
+        input.map(Path::new),
+        args.value("output").map(Path::new),
+        page.as_ref(),
+    ) {
+        Ok(()) => Ok(ExitCode::SUCCESS),
+        Err(err) => {
+            eprintln!("error: {}", err);
+            Ok(ExitCode::FAILURE)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::path::PathBuf;
+
+    const DOC: &str = r#"{"type":"doc","content":[{"type":"paragraph","content":[{"type":"text","text":"Go Leafs"}]}]}"#;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("tiptap-to-html-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn it_converts_a_file() {
+        let dir = temp_dir("convert");
+        let input = dir.join("doc.json");
+        let output = dir.join("doc.html");
+        fs::write(&input, DOC).unwrap();
+
+        convert(
+            &ProseMirror::with_default_plugins(),
+            Some(&input),
+            Some(&output),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&output).unwrap(),
+            "<div><p>Go Leafs</p></div>"
+        );
+        assert!(convert(&ProseMirror::new(), Some(&input), Some(&output), None).is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn it_converts_every_matching_file() {
+        let dir = temp_dir("batch");
+        fs::create_dir_all(dir.join("content/posts")).unwrap();
+        fs::write(dir.join("content/index.json"), DOC).unwrap();
+        fs::write(dir.join("content/posts/leafs.json"), DOC).unwrap();
+        fs::write(dir.join("content/posts/notes.txt"), "").unwrap();
+        fs::write(dir.join("content/posts/broken.json"), "{").unwrap();
+        let pattern = format!("{}/content/**/*.json", dir.display());
+
+        let (converted, errors) = convert_all(
+            &ProseMirror::with_default_plugins(),
+            &pattern,
+            &dir.join("dist"),
+            None,
+            false,
+        )
+        .unwrap();
+
+        assert_eq!((converted, errors.len()), (2, 1));
+        assert_eq!(
+            fs::read_to_string(dir.join("dist/posts/leafs.html")).unwrap(),
+            "<div><p>Go Leafs</p></div>"
+        );
+        assert!(dir.join("dist/index.html").exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,94 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// `*` and `?` within a path segment, `**` for any number of directories
+fn matches_segment(pattern: &[u8], name: &[u8]) -> bool {
+    match (pattern.first(), name.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => {
+            matches_segment(&pattern[1..], name)
+                || (!name.is_empty() && matches_segment(pattern, &name[1..]))
+        }
+        (Some(b'?'), Some(_)) => matches_segment(&pattern[1..], &name[1..]),
+        (Some(p), Some(n)) if p == n => matches_segment(&pattern[1..], &name[1..]),
+        _ => false,
+    }
+}
+
+fn matches(pattern: &[&str], path: &[&str]) -> bool {
+    match (pattern.first(), path.first()) {
+        (None, None) => true,
+        (Some(&"**"), _) => {
+            matches(&pattern[1..], path) || (!path.is_empty() && matches(pattern, &path[1..]))
+        }
+        (Some(segment), Some(name)) => {
+            matches_segment(segment.as_bytes(), name.as_bytes())
+                && matches(&pattern[1..], &path[1..])
+        }
+        _ => false,
+    }
+}
+
+fn walk(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries =
+        fs::read_dir(dir).map_err(|err| format!("can't read {}: {}", dir.display(), err))?;
+    for entry in entries {
+        let path = entry.map_err(|err| err.to_string())?.path();
+        if path.is_dir() {
+            walk(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+// The files matching `pattern`, each with its path below the part of the pattern
+// without wildcards, e.g. `content/**/*.json` finds `content/a/b.json` as `a/b.json`
+pub fn expand(pattern: &str) -> Result<Vec<(PathBuf, PathBuf)>, String> {
+    let segments: Vec<&str> = pattern.split('/').filter(|s| !s.is_empty()).collect();
+    let literal = segments
+        .iter()
+        .take_while(|segment| !segment.contains(['*', '?']))
+        .count()
+        // The last segment is always matched, even without wildcards
+        .min(segments.len().saturating_sub(1));
+    let mut base: PathBuf = segments[..literal].iter().collect();
+    if pattern.starts_with('/') {
+        base = Path::new("/").join(base);
+    }
+    if base.as_os_str().is_empty() {
+        base = PathBuf::from(".");
+    }
+
+    let mut files = vec![];
+    walk(&base, &mut files)?;
+    let mut matched: Vec<(PathBuf, PathBuf)> = files
+        .into_iter()
+        .filter_map(|path| {
+            let relative = path.strip_prefix(&base).ok()?.to_path_buf();
+            let names: Option<Vec<&str>> = relative.iter().map(|name| name.to_str()).collect();
+            matches(&segments[literal..], &names?).then_some((path, relative))
+        })
+        .collect();
+    matched.sort();
+    Ok(matched)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(path: &str) -> Vec<&str> {
+        path.split('/').collect()
+    }
+
+    #[test]
+    fn it_matches_globs() {
+        assert!(matches(&path("**/*.json"), &path("a/b/c.json")));
+        assert!(matches(&path("**/*.json"), &path("c.json")));
+        assert!(matches(&path("a/*/c?.json"), &path("a/b/c1.json")));
+        assert!(!matches(&path("a/*.json"), &path("a/b/c.json")));
+        assert!(!matches(&path("*.json"), &path("c.html")));
+    }
+}
@@ -0,0 +1,152 @@
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+// The request line and headers together, anything longer is refused
+const MAX_HEAD_BYTES: u64 = 16 * 1024;
+// So a client that stops sending doesn't hold its thread forever
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Just enough HTTP/1.1 for the preview and conversion servers: one request per
+// connection, bodies sized by Content-Length
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    // Only the conversion server takes a body
+    #[cfg_attr(not(feature = "serve"), allow(dead_code))]
+    pub body: Vec<u8>,
+}
+
+pub struct Response {
+    pub status: &'static str,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl Response {
+    pub fn new(status: &'static str, content_type: &'static str, body: String) -> Self {
+        Self {
+            status,
+            content_type,
+            body,
+        }
+    }
+
+    pub fn text(status: &'static str, body: &str) -> Self {
+        Self::new(status, "text/plain; charset=utf-8", body.to_string())
+    }
+
+    pub fn write_to(&self, stream: &mut impl Write) -> io::Result<()> {
+        write!(
+            stream,
+            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            self.content_type,
+            self.body.len(),
+            self.body
+        )
+    }
+}
+
+// A request that can't be read is answered right away with the response
+pub fn read_request(stream: &TcpStream, max_body_bytes: usize) -> Result<Request, Response> {
+    let bad_request = || Response::text("400 Bad Request", "bad request");
+    let too_large = || {
+        Response::text(
+            "431 Request Header Fields Too Large",
+            "request headers too large",
+        )
+    };
+    stream
+        .set_read_timeout(Some(READ_TIMEOUT))
+        .map_err(|_| bad_request())?;
+    let mut reader = BufReader::new(stream.take(MAX_HEAD_BYTES));
+    // Every line has to end before the head limit does
+    let mut read_line = |line: &mut String| match reader.read_line(line) {
+        Ok(_) if line.ends_with('\n') => Ok(()),
+        Ok(_) if reader.get_ref().limit() == 0 => Err(too_large()),
+        _ => Err(bad_request()),
+    };
+    let mut line = String::new();
+    read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(bad_request());
+    };
+    let method = method.to_string();
+    let path = target.split('?').next().unwrap_or(target).to_string();
+
+    let mut content_length = 0;
+    loop {
+        line.clear();
+        read_line(&mut line)?;
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().map_err(|_| bad_request())?;
+            }
+        }
+    }
+    if content_length > max_body_bytes {
+        return Err(Response::text(
+            "413 Payload Too Large",
+            "request body too large",
+        ));
+    }
+
+    let mut body = vec![0; content_length];
+    reader.get_mut().set_limit(content_length as u64);
+    reader.read_exact(&mut body).map_err(|_| bad_request())?;
+    Ok(Request { method, path, body })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::thread;
+
+    fn read(request: Vec<u8>) -> Result<Request, Response> {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let client = thread::spawn(move || {
+            let mut stream = TcpStream::connect(address).unwrap();
+            let _ = stream.write_all(&request);
+            // Held open, so only the limits can end an unfinished request
+            thread::sleep(Duration::from_millis(200));
+        });
+        let (stream, _) = listener.accept().unwrap();
+        let request = read_request(&stream, 64);
+        client.join().unwrap();
+        request
+    }
+
+    #[test]
+    fn it_bounds_the_request_head() {
+        let Ok(request) = read(b"POST /render HTTP/1.1\r\nContent-Length: 2\r\n\r\n{}".to_vec())
+        else {
+            panic!("a small request should be read");
+        };
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.body, b"{}");
+
+        let mut long_line = b"GET /".to_vec();
+        long_line.extend(vec![b'a'; MAX_HEAD_BYTES as usize]);
+        assert_eq!(
+            read(long_line).err().map(|response| response.status),
+            Some("431 Request Header Fields Too Large")
+        );
+
+        let mut many_headers = b"GET / HTTP/1.1\r\n".to_vec();
+        for _ in 0..MAX_HEAD_BYTES / 8 {
+            many_headers.extend(b"X-A: b\r\n");
+        }
+        assert_eq!(
+            read(many_headers).err().map(|response| response.status),
+            Some("431 Request Header Fields Too Large")
+        );
+    }
+}
@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+use std::fs;
+use std::process::ExitCode;
+
+use serde_json::{json, Value};
+use tiptap_to_html::error::ProseMirrorError;
+use tiptap_to_html::{ProseMirror, RenderConfig};
+
+mod convert;
+mod glob;
+mod http;
+mod page;
+#[cfg(feature = "serve")]
+mod serve;
+mod validate;
+mod watch;
+
+const USAGE: &str = "Usage: tiptap-to-html <command> [options]
+
+Commands:
+  convert [file]      Render Tiptap JSON to HTML
+  validate [file]     Check a document renders without problems, printing a JSON
+                      report and exiting with 1 if it doesn't
+  watch <file>        Render a full page again every time the file changes
+  serve               Answer POST /render with the HTML for the JSON body
+                      (needs the `serve` feature)
+
+Options for convert:
+  --input <file>      Read the document from a file instead of stdin, same as [file]
+  --output <file>     Write the HTML to a file instead of stdout
+  --glob <pattern>    Convert every matching file, e.g. 'content/**/*.json'
+  --out-dir <dir>     Where --glob writes its HTML, keeping the directory structure
+  --parallel          Convert --glob matches in parallel (needs the `parallel` feature)
+  --standalone        Wrap the HTML in a full page titled after the first heading
+  --template <file>   Wrap it in this page instead, with {{title}}, {{stylesheet}}
+                      and {{content}} filled in
+  --stylesheet <href> Link a stylesheet from the page
+  --plugins <sets>    Comma separated node sets to enable, `starter-kit` by default.
+                      One of: all, starter-kit, tables, task-lists, details, media,
+                      footnotes, math, callouts, mentions, emoji, iframes, formatting.
+  --config <file>     JSON renderer options, see `RenderConfig`
+                      validate and watch take --plugins and --config too.
+
+Options for watch:
+  --serve <address>   Serve the page with live reload, e.g. 127.0.0.1:8080
+  --output <file>     Write each render to a file instead of stdout
+  --template, --stylesheet as for convert
+
+Options for serve:
+  --listen <address>  Where to listen, 127.0.0.1:3000 by default
+  --max-body-bytes <n>
+                      Largest document accepted, 10485760 by default
+  --plugins, --config as for convert, e.g. `{\"rejectUnsafeUrls\": true}`";
+
+// `--name value` and `--name=value` options plus positional arguments. Options
+// listed in `flags` take no value.
+pub struct Args {
+    positional: Vec<String>,
+    options: HashMap<String, Vec<String>>,
+}
+
+impl Args {
+    pub fn parse(args: &[String], flags: &[&str]) -> Result<Self, String> {
+        let mut parsed = Args {
+            positional: vec![],
+            options: HashMap::new(),
+        };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let Some(name) = arg.strip_prefix("--") else {
+                parsed.positional.push(arg.clone());
+                continue;
+            };
+            let (name, value) = match name.split_once('=') {
+                Some((name, value)) => (name, value.to_string()),
+                None if flags.contains(&name) => (name, String::new()),
+                None => match args.next() {
+                    Some(value) => (name, value.clone()),
+                    None => return Err(format!("--{} needs a value", name)),
+                },
+            };
+            parsed
+                .options
+                .entry(name.to_string())
+                .or_default()
+                .push(value);
+        }
+        Ok(parsed)
+    }
+
+    pub fn positional(&self) -> &[String] {
+        &self.positional
+    }
+
+    // The last value given for the option
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.options
+            .get(name)
+            .and_then(|values| values.last())
+            .map(|value| value.as_str())
+    }
+
+    pub fn values(&self, name: &str) -> &[String] {
+        self.options
+            .get(name)
+            .map(|values| values.as_slice())
+            .unwrap_or(&[])
+    }
+
+    // Fails on any option the command doesn't know about
+    pub fn expect_only(&self, known: &[&str]) -> Result<(), String> {
+        match self
+            .options
+            .keys()
+            .find(|name| !known.contains(&name.as_str()))
+        {
+            Some(name) => Err(format!("unknown option --{}", name)),
+            None => Ok(()),
+        }
+    }
+}
+
+// A renderer set up by the `--config` JSON file, with the plugin sets named in
+// `--plugins` in place of the configured ones
+pub fn prosemirror(args: &Args) -> Result<ProseMirror, String> {
+    let mut config = match args.value("config") {
+        Some(path) => {
+            let json = fs::read_to_string(path)
+                .map_err(|err| format!("can't read config {}: {}", path, err))?;
+            RenderConfig::from_json_str(&json).map_err(|err| format!("{}: {}", path, err))?
+        }
+        None => RenderConfig::default(),
+    };
+    let sets: Vec<String> = args
+        .values("plugins")
+        .iter()
+        .flat_map(|value| value.split(','))
+        .map(|set| set.trim().to_string())
+        .filter(|set| !set.is_empty())
+        .collect();
+    if !sets.is_empty() {
+        config.plugins = sets;
+    }
+    config.build().map_err(|err| err.to_string())
+}
+
+// How the validate report and the server describe an error
+pub fn error_json(err: &ProseMirrorError) -> Value {
+    json!({"kind": err.kind(), "path": err.path(), "message": err.to_string()})
+}
+
+pub fn run(args: &[String]) -> Result<ExitCode, String> {
+    match args.first().map(|command| command.as_str()) {
+        Some("convert") => convert::run(&args[1..]),
+        Some("validate") => validate::run(&args[1..]),
+        Some("watch") => watch::run(&args[1..]),
+        #[cfg(feature = "serve")]
+        Some("serve") => serve::run(&args[1..]),
+        #[cfg(not(feature = "serve"))]
+        Some("serve") => Err("serve needs a build with the `serve` feature".to_string()),
+        Some("help" | "--help" | "-h") => {
+            println!("{}", USAGE);
+            Ok(ExitCode::SUCCESS)
+        }
+        Some(command) => Err(format!("unknown command `{}`\n\n{}", command, USAGE)),
+        None => Err(USAGE.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn it_parses_options_and_positional_arguments() {
+        let args = Args::parse(
+            &strings(&[
+                "doc.json",
+                "--plugins",
+                "tables",
+                "--plugins=math",
+                "--standalone",
+            ]),
+            &["standalone"],
+        )
+        .unwrap();
+
+        assert_eq!(args.positional(), ["doc.json"]);
+        assert_eq!(args.values("plugins"), ["tables", "math"]);
+        assert_eq!(args.value("plugins"), Some("math"));
+        assert_eq!(args.value("standalone"), Some(""));
+        assert!(args.expect_only(&["plugins"]).is_err());
+        assert!(Args::parse(&strings(&["--output"]), &[]).is_err());
+    }
+
+    #[test]
+    fn it_enables_the_chosen_plugin_sets() {
+        let args = Args::parse(&strings(&["--plugins", "starter-kit,tables"]), &[]).unwrap();
+        let enabled = prosemirror(&args).unwrap();
+
+        assert!(enabled.plugin("table").is_some());
+        assert!(enabled.plugin("callout").is_none());
+
+        let args = Args::parse(&strings(&["--plugins", "sparkles"]), &[]).unwrap();
+        assert!(prosemirror(&args).is_err());
+    }
+}
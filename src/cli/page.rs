@@ -0,0 +1,100 @@
+use std::fs;
+
+use serde_json::Value;
+use tiptap_to_html::ProseMirror;
+
+use super::Args;
+
+const STANDALONE: &str = "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">
+<title>{{title}}</title>
+{{stylesheet}}
+</head>
+<body>
+{{content}}
+</body>
+</html>
+";
+
+// A full HTML document the rendered content is put into. Templates use
+// `{{title}}`, `{{stylesheet}}` and `{{content}}`.
+pub struct Page {
+    template: String,
+    stylesheet: Option<String>,
+}
+
+impl Page {
+    // From `--template <file>` or `--standalone`, `None` when neither was given
+    pub fn from_args(args: &Args) -> Result<Option<Self>, String> {
+        if args.value("template").is_some() || args.value("standalone").is_some() {
+            return Self::from_args_or_standalone(args).map(Some);
+        }
+        if args.value("stylesheet").is_some() {
+            return Err("--stylesheet needs --standalone or --template".to_string());
+        }
+        Ok(None)
+    }
+
+    // For commands that always produce a full page, the standalone one unless
+    // there's a `--template`
+    pub fn from_args_or_standalone(args: &Args) -> Result<Self, String> {
+        let template = match args.value("template") {
+            Some(path) => fs::read_to_string(path)
+                .map_err(|err| format!("can't read template {}: {}", path, err))?,
+            None => STANDALONE.to_string(),
+        };
+        Ok(Page {
+            template,
+            stylesheet: args.value("stylesheet").map(|href| href.to_string()),
+        })
+    }
+
+    // The title is the document's first heading
+    pub fn render(&self, prosemirror: &ProseMirror, content: &Value, html: &str) -> String {
+        let title = prosemirror
+            .table_of_contents(content)
+            .into_iter()
+            .next()
+            .map(|heading| heading.text)
+            .unwrap_or_default();
+        let stylesheet = match &self.stylesheet {
+            Some(href) => format!(
+                "<link rel=\"stylesheet\" href=\"{}\">",
+                prosemirror.escape_text(href)
+            ),
+            None => String::new(),
+        };
+        // Content last, so any braces in it are left alone
+        self.template
+            .replace("{{title}}", &prosemirror.escape_text(&title))
+            .replace("{{stylesheet}}", &stylesheet)
+            .replace("{{content}}", html)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_wraps_content_in_a_page() {
+        let prosemirror = ProseMirror::with_default_plugins();
+        let content = json!({"type":"doc","content":[
+            {"type":"paragraph","content":[{"type":"text","text":"{{title}}"}]},
+            {"type":"heading","attrs":{"level":1},"content":[{"type":"text","text":"Leafs & Habs"}]}
+        ]});
+        let page = Page {
+            template: "<title>{{title}}</title>{{stylesheet}}<main>{{content}}</main>".to_string(),
+            stylesheet: Some("/style.css".to_string()),
+        };
+
+        assert_eq!(
+            page.render(&prosemirror, &content, "<p>{{title}}</p>"),
+            "<title>Leafs &amp; Habs</title><link rel=\"stylesheet\" href=\"/style.css\"><main><p>{{title}}</p></main>"
+        );
+    }
+}
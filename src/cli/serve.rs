@@ -0,0 +1,114 @@
+use std::net::{TcpListener, TcpStream};
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::thread;
+
+use serde_json::Value;
+use tiptap_to_html::ProseMirror;
+
+use super::http::{self, Request, Response};
+use super::{error_json, Args};
+
+const DEFAULT_ADDRESS: &str = "127.0.0.1:3000";
+const DEFAULT_MAX_BODY_BYTES: usize = 10 * 1024 * 1024;
+
+// Errors come back as the same JSON the validate report uses
+fn respond(prosemirror: &ProseMirror, request: &Request) -> Response {
+    match (request.method.as_str(), request.path.as_str()) {
+        ("POST", "/render") => {
+            let content: Value = match serde_json::from_slice(&request.body) {
+                Ok(content) => content,
+                Err(err) => return Response::text("400 Bad Request", &err.to_string()),
+            };
+            match prosemirror.render(&content) {
+                Ok(html) => Response::new("200 OK", "text/html; charset=utf-8", html),
+                Err(err) => Response::new(
+                    "422 Unprocessable Entity",
+                    "application/json",
+                    error_json(&err).to_string(),
+                ),
+            }
+        }
+        (_, "/render") => Response::text("405 Method Not Allowed", "POST a document to /render"),
+        ("GET", "/health") => Response::text("200 OK", "ok"),
+        _ => Response::text("404 Not Found", "not found"),
+    }
+}
+
+fn handle(mut stream: TcpStream, prosemirror: &ProseMirror, max_body_bytes: usize) {
+    let response = match http::read_request(&stream, max_body_bytes) {
+        Ok(request) => respond(prosemirror, &request),
+        Err(response) => response,
+    };
+    // The client hanging up early isn't worth reporting
+    let _ = response.write_to(&mut stream);
+}
+
+pub fn run(args: &[String]) -> Result<ExitCode, String> {
+    let args = Args::parse(args, &[])?;
+    args.expect_only(&["listen", "max-body-bytes", "plugins", "config"])?;
+    let prosemirror = Arc::new(super::prosemirror(&args)?);
+    let address = args.value("listen").unwrap_or(DEFAULT_ADDRESS);
+    let max_body_bytes = match args.value("max-body-bytes") {
+        Some(bytes) => bytes
+            .parse()
+            .map_err(|_| format!("--max-body-bytes must be a number, not {:?}", bytes))?,
+        None => DEFAULT_MAX_BODY_BYTES,
+    };
+
+    let listener = TcpListener::bind(address)
+        .map_err(|err| format!("can't listen on {}: {}", address, err))?;
+    eprintln!("listening on http://{}", address);
+    for stream in listener.incoming().flatten() {
+        let prosemirror = Arc::clone(&prosemirror);
+        thread::spawn(move || handle(stream, &prosemirror, max_body_bytes));
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(method: &str, path: &str, body: &str) -> Request {
+        Request {
+            method: method.to_string(),
+            path: path.to_string(),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn it_renders_posted_documents() {
+        let prosemirror = ProseMirror::with_default_plugins();
+
+        let response = respond(
+            &prosemirror,
+            &request(
+                "POST",
+                "/render",
+                r#"{"type":"doc","content":[{"type":"paragraph"}]}"#,
+            ),
+        );
+        assert_eq!(
+            (response.status, response.body.as_str()),
+            ("200 OK", "<div><p></p></div>")
+        );
+
+        let response = respond(
+            &prosemirror,
+            &request("POST", "/render", r#"{"type":"sparkle"}"#),
+        );
+        assert_eq!(response.status, "422 Unprocessable Entity");
+        assert!(response.body.contains("\"kind\":\"TypeNotFound\""));
+
+        assert_eq!(
+            respond(&prosemirror, &request("POST", "/render", "{")).status,
+            "400 Bad Request"
+        );
+        assert_eq!(
+            respond(&prosemirror, &request("GET", "/render", "")).status,
+            "405 Method Not Allowed"
+        );
+    }
+}
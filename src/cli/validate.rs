@@ -0,0 +1,125 @@
+use std::fs;
+use std::io::{self, Read};
+use std::process::ExitCode;
+
+use serde_json::{json, Value};
+use tiptap_to_html::attrs::is_valid_attr_name;
+use tiptap_to_html::error::ProseMirrorError;
+use tiptap_to_html::ProseMirror;
+
+use super::{error_json, Args};
+
+// Rendering never looks at attrs it doesn't know, so they're checked on their own:
+// `attrs` has to be an object and every name has to be writable as an attribute
+fn attr_errors(node: &Value, path: &str, errors: &mut Vec<ProseMirrorError>) {
+    let mut check = |attrs: Option<&Value>, path: String| match attrs {
+        None | Some(Value::Null) => {}
+        Some(Value::Object(attrs)) => {
+            for name in attrs.keys().filter(|name| !is_valid_attr_name(name)) {
+                errors.push(ProseMirrorError::InvalidNodeShape {
+                    path: path.clone(),
+                    reason: format!("invalid attribute name {:?}", name),
+                });
+            }
+        }
+        Some(_) => errors.push(ProseMirrorError::InvalidNodeShape {
+            path,
+            reason: "`attrs` must be an object".to_string(),
+        }),
+    };
+    check(node.get("attrs"), path.to_string());
+    if let Some(marks) = node.get("marks").and_then(|marks| marks.as_array()) {
+        for (index, mark) in marks.iter().enumerate() {
+            check(mark.get("attrs"), format!("{}/marks/{}", path, index));
+        }
+    }
+    if let Some(content) = node.get("content").and_then(|content| content.as_array()) {
+        for (index, child) in content.iter().enumerate() {
+            attr_errors(child, &format!("{}/content/{}", path, index), errors);
+        }
+    }
+}
+
+// A JSON report with every problem found, `valid` when there are none
+fn validate(prosemirror: &ProseMirror, json: &str) -> Value {
+    let content: Value = match serde_json::from_str(json) {
+        Ok(content) => content,
+        Err(err) => {
+            let err = ProseMirrorError::InvalidNodeShape {
+                path: String::new(),
+                reason: err.to_string(),
+            };
+            return json!({"valid": false, "errors": [error_json(&err)]});
+        }
+    };
+
+    let mut errors = prosemirror.render_with_report(&content).errors;
+    // Anything past the depth limit was never walked
+    if !errors.iter().any(|err| err.kind() == "LimitExceeded") {
+        attr_errors(&content, "", &mut errors);
+    }
+    json!({
+        "valid": errors.is_empty(),
+        "errors": errors.iter().map(error_json).collect::<Vec<_>>(),
+    })
+}
+
+pub fn run(args: &[String]) -> Result<ExitCode, String> {
+    let args = Args::parse(args, &[])?;
+    args.expect_only(&["plugins", "config"])?;
+    let prosemirror = super::prosemirror(&args)?;
+
+    let json = match args.positional() {
+        [] => {
+            let mut json = String::new();
+            io::stdin()
+                .read_to_string(&mut json)
+                .map_err(|err| format!("can't read stdin: {}", err))?;
+            json
+        }
+        [path] => {
+            fs::read_to_string(path).map_err(|err| format!("can't read {}: {}", path, err))?
+        }
+        _ => return Err("validate takes a single file".to_string()),
+    };
+
+    let report = validate(&prosemirror, &json);
+    println!("{}", report);
+    Ok(if report["valid"] == true {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_reports_every_problem() {
+        let prosemirror = ProseMirror::with_default_plugins();
+        let json = r#"{"type":"doc","content":[
+            {"type":"paragraph","attrs":{"x\"y":1},"content":[{"type":"text","text":"Go","marks":[{"type":"sparkle"}]}]},
+            {"type":"youtube","attrs":[]}
+        ]}"#;
+
+        assert_eq!(
+            validate(&prosemirror, json),
+            json!({"valid": false, "errors": [
+                {"kind": "MarkNotFound", "path": "/content/0/content/0/marks/0", "message": "Mark not found at \"/content/0/content/0/marks/0\": Some(\"sparkle\")"},
+                {"kind": "TypeNotFound", "path": "/content/1", "message": "Type not found at \"/content/1\": Some(\"youtube\")"},
+                {"kind": "InvalidNodeShape", "path": "/content/0", "message": "Invalid node at \"/content/0\": invalid attribute name \"x\\\"y\""},
+                {"kind": "InvalidNodeShape", "path": "/content/1", "message": "Invalid node at \"/content/1\": `attrs` must be an object"}
+            ]})
+        );
+        assert_eq!(
+            validate(&prosemirror, r#"{"type":"doc","content":[]}"#),
+            json!({"valid": true, "errors": []})
+        );
+        assert_eq!(
+            validate(&prosemirror, "{")["errors"][0]["kind"],
+            "InvalidNodeShape"
+        );
+    }
+}
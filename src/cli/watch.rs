@@ -0,0 +1,196 @@
+use std::fs;
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use serde_json::Value;
+use tiptap_to_html::ProseMirror;
+
+use super::http::{self, Request, Response};
+use super::page::Page;
+use super::Args;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+// Polls `/__version` and reloads once the document has been rendered again
+const LIVE_RELOAD: &str = "<script>
+(function () {
+  var version = null;
+  setInterval(function () {
+    fetch('/__version').then(function (response) { return response.text(); }).then(function (current) {
+      if (version !== null && current !== version) location.reload();
+      version = current;
+    }).catch(function () {});
+  }, 500);
+})();
+</script>";
+
+// The latest render, `version` goes up every time the file changes
+struct Preview {
+    version: u64,
+    html: String,
+}
+
+// A render error still makes a page, so it shows up where the preview would be
+fn render_preview(prosemirror: &ProseMirror, page: &Page, path: &Path) -> String {
+    let rendered = fs::read_to_string(path)
+        .map_err(|err| err.to_string())
+        .and_then(|json| serde_json::from_str::<Value>(&json).map_err(|err| err.to_string()))
+        .and_then(|content| {
+            let html = prosemirror
+                .render(&content)
+                .map_err(|err| err.to_string())?;
+            Ok(page.render(prosemirror, &content, &html))
+        });
+    match rendered {
+        Ok(html) => html,
+        Err(err) => {
+            eprintln!("error: {}: {}", path.display(), err);
+            let html = format!("<pre>{}</pre>", prosemirror.escape_text(&err));
+            page.render(prosemirror, &Value::Null, &html)
+        }
+    }
+}
+
+fn with_live_reload(html: &str) -> String {
+    match html.rfind("</body>") {
+        Some(index) => format!("{}{}\n{}", &html[..index], LIVE_RELOAD, &html[index..]),
+        None => format!("{}{}", html, LIVE_RELOAD),
+    }
+}
+
+fn respond(request: &Request, preview: &Preview) -> Response {
+    if request.method != "GET" {
+        return Response::text("405 Method Not Allowed", "the preview is read only");
+    }
+    match request.path.as_str() {
+        "/" | "/index.html" => Response::new(
+            "200 OK",
+            "text/html; charset=utf-8",
+            with_live_reload(&preview.html),
+        ),
+        "/__version" => Response::text("200 OK", &preview.version.to_string()),
+        _ => Response::text("404 Not Found", "not found"),
+    }
+}
+
+fn handle(mut stream: TcpStream, preview: &Mutex<Preview>) -> std::io::Result<()> {
+    let response = match http::read_request(&stream, 0) {
+        Ok(request) => match preview.lock() {
+            Ok(preview) => respond(&request, &preview),
+            Err(_) => return Ok(()),
+        },
+        Err(response) => response,
+    };
+    response.write_to(&mut stream)
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+// Calls `on_change` whenever the file's modification time changes
+fn watch(path: &Path, mut on_change: impl FnMut()) -> ! {
+    let mut last = modified(path);
+    loop {
+        thread::sleep(POLL_INTERVAL);
+        let current = modified(path);
+        if current != last {
+            last = current;
+            on_change();
+        }
+    }
+}
+
+pub fn run(args: &[String]) -> Result<ExitCode, String> {
+    let args = Args::parse(args, &[])?;
+    args.expect_only(&[
+        "serve",
+        "output",
+        "plugins",
+        "config",
+        "template",
+        "stylesheet",
+    ])?;
+    let prosemirror = super::prosemirror(&args)?;
+    let path = match args.positional() {
+        [path] => PathBuf::from(path),
+        _ => return Err("watch takes the file to watch".to_string()),
+    };
+    let page = Page::from_args_or_standalone(&args)?;
+
+    let Some(address) = args.value("serve") else {
+        // Without a server, each render just replaces the output
+        let output = args.value("output").map(PathBuf::from);
+        let write = || {
+            let html = render_preview(&prosemirror, &page, &path);
+            match &output {
+                Some(output) => match fs::write(output, html) {
+                    Ok(()) => eprintln!("rendered {}", output.display()),
+                    Err(err) => eprintln!("error: can't write {}: {}", output.display(), err),
+                },
+                None => println!("{}", html),
+            }
+        };
+        write();
+        watch(&path, write);
+    };
+
+    let listener = TcpListener::bind(address)
+        .map_err(|err| format!("can't listen on {}: {}", address, err))?;
+    let preview = Arc::new(Mutex::new(Preview {
+        version: 0,
+        html: render_preview(&prosemirror, &page, &path),
+    }));
+    eprintln!("serving {} on http://{}", path.display(), address);
+
+    let server = Arc::clone(&preview);
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let preview = Arc::clone(&server);
+            thread::spawn(move || handle(stream, &preview));
+        }
+    });
+    watch(&path, || {
+        let html = render_preview(&prosemirror, &page, &path);
+        if let Ok(mut preview) = preview.lock() {
+            preview.version += 1;
+            preview.html = html;
+        }
+        eprintln!("rendered {}", path.display());
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_serves_the_preview_with_live_reload() {
+        let preview = Preview {
+            version: 3,
+            html: "<html><body><p>Leafs</p></body></html>".to_string(),
+        };
+
+        let get = |path: &str| Request {
+            method: "GET".to_string(),
+            path: path.to_string(),
+            body: vec![],
+        };
+
+        let response = respond(&get("/"), &preview);
+        assert_eq!(response.status, "200 OK");
+        assert!(response
+            .body
+            .starts_with("<html><body><p>Leafs</p><script>"));
+        assert!(response.body.ends_with("</script>\n</body></html>"));
+        assert_eq!(respond(&get("/__version"), &preview).body, "3");
+        assert_eq!(
+            respond(&get("/favicon.ico"), &preview).status,
+            "404 Not Found"
+        );
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::options::{AttrOrder, SerializationStyle};
+#[cfg(feature = "syntax-highlight")]
+use crate::plugins::CodeBlockPlugin;
+use crate::plugins::FragmentDocPlugin;
+use crate::transform::{walk_nodes_mut, Transform};
+use crate::ProseMirror;
+
+// The order StarterKit's marks end up in the schema: Link's priority puts it first,
+// the rest follow the extension list. ProseMirror keeps a node's marks in this
+// order no matter how the JSON lists them, the first one rendering outermost.
+pub const STARTER_KIT_MARK_ORDER: [&str; 6] =
+    ["link", "bold", "code", "italic", "strike", "underline"];
+
+// Reorders every node's marks by schema rank, marks not in the list keep their
+// relative order after the ranked ones
+pub struct MarkOrder {
+    order: Vec<String>,
+}
+
+impl MarkOrder {
+    pub fn new(order: &[&str]) -> Self {
+        Self {
+            order: order.iter().map(|mark| mark.to_string()).collect(),
+        }
+    }
+}
+
+impl Transform for MarkOrder {
+    fn transform(&self, doc: &mut Value) {
+        let rank = |mark: &Value| {
+            let mark_type = mark.get("type").and_then(|t| t.as_str());
+            self.order
+                .iter()
+                .position(|ranked| Some(ranked.as_str()) == mark_type)
+                .unwrap_or(self.order.len())
+        };
+        walk_nodes_mut(doc, &mut |node| {
+            if let Some(Value::Array(marks)) = node.get_mut("marks") {
+                marks.sort_by_key(rank);
+            }
+        });
+    }
+}
+
+// A renderer aiming for the exact output of `generateHTML` from `@tiptap/html`
+// with StarterKit: no wrapper around the doc, HTML5 void elements, marks in
+// schema order and link attributes in the order Tiptap merges them. The
+// fixtures in tests/fixtures/tiptap.json are what it's held to.
+pub fn tiptap() -> ProseMirror {
+    let mut prosemirror = ProseMirror::with_default_plugins();
+    FragmentDocPlugin::register(&mut prosemirror);
+    // generateHTML leaves highlighting to the client
+    #[cfg(feature = "syntax-highlight")]
+    prosemirror.add_plugin(
+        "codeBlock",
+        Box::new(CodeBlockPlugin::new().with_highlighting(false)),
+    );
+    prosemirror.add_transform(Box::new(MarkOrder::new(&STARTER_KIT_MARK_ORDER)));
+
+    let options = prosemirror.options_mut();
+    options.serialization = SerializationStyle::Html5;
+    let link_order = ["target", "rel", "class", "href"];
+    options.attr_order = AttrOrder::Explicit(HashMap::from([(
+        "a".to_string(),
+        link_order.iter().map(|attr| attr.to_string()).collect(),
+    )]));
+    prosemirror
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_matches_generate_html() {
+        let fixtures: Vec<Value> =
+            serde_json::from_str(include_str!("../tests/fixtures/tiptap.json")).unwrap();
+        let prose_mirror = tiptap();
+
+        for fixture in fixtures {
+            assert_eq!(
+                prose_mirror.render(&fixture["doc"]).unwrap(),
+                fixture["html"].as_str().unwrap(),
+                "{}",
+                fixture["name"]
+            );
+        }
+    }
+}
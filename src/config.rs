@@ -0,0 +1,139 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::error::ProseMirrorError;
+use crate::limits::DEFAULT_MAX_DEPTH;
+use crate::options::SerializationStyle;
+use crate::plugins;
+use crate::sanitize::UnsafeUrlAction;
+use crate::ProseMirror;
+
+// Renderer setup as plain JSON, for everything that configures it from outside
+// Rust: the CLI, the HTTP server and the language bindings. Keys are camelCase,
+// e.g. `{"plugins": ["starter-kit", "tables"], "rejectUnsafeUrls": true}`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct RenderConfig {
+    // Names from `plugins::PLUGIN_SETS`, or `all`
+    pub plugins: Vec<String>,
+    pub strict: bool,
+    pub max_depth: Option<usize>,
+    pub max_nodes: Option<usize>,
+    pub max_output_bytes: Option<usize>,
+    // Fail on `javascript:` and friends instead of dropping the attribute
+    pub reject_unsafe_urls: bool,
+    pub allow_data_urls: bool,
+    // Replaces the default `javascript` and `vbscript`
+    pub blocked_schemes: Option<Vec<String>>,
+    pub html5: bool,
+}
+
+impl Default for RenderConfig {
+    fn default() -> Self {
+        Self {
+            plugins: vec!["starter-kit".to_string()],
+            strict: false,
+            max_depth: Some(DEFAULT_MAX_DEPTH),
+            max_nodes: None,
+            max_output_bytes: None,
+            reject_unsafe_urls: false,
+            allow_data_urls: false,
+            blocked_schemes: None,
+            html5: false,
+        }
+    }
+}
+
+impl RenderConfig {
+    // `null` is the default configuration
+    pub fn from_json(options: &Value) -> Result<Self, ProseMirrorError> {
+        if options.is_null() {
+            return Ok(Self::default());
+        }
+        Self::deserialize(options).map_err(|err| ProseMirrorError::InvalidOptions {
+            path: String::new(),
+            reason: err.to_string(),
+        })
+    }
+
+    pub fn from_json_str(options: &str) -> Result<Self, ProseMirrorError> {
+        let options: Value =
+            serde_json::from_str(options).map_err(|err| ProseMirrorError::InvalidOptions {
+                path: String::new(),
+                reason: err.to_string(),
+            })?;
+        Self::from_json(&options)
+    }
+
+    pub fn build(&self) -> Result<ProseMirror, ProseMirrorError> {
+        let mut prosemirror = ProseMirror::new();
+        for (index, set) in self.plugins.iter().enumerate() {
+            if !plugins::register_plugin_set(&mut prosemirror, set) {
+                return Err(ProseMirrorError::InvalidOptions {
+                    path: format!("/plugins/{}", index),
+                    reason: format!("unknown plugin set {:?}", set),
+                });
+            }
+        }
+
+        let options = prosemirror.options_mut();
+        options.strict = self.strict;
+        options.max_depth = self.max_depth;
+        options.max_nodes = self.max_nodes;
+        options.max_output_bytes = self.max_output_bytes;
+        if self.reject_unsafe_urls {
+            options.url_policy.action = UnsafeUrlAction::Reject;
+        }
+        options.url_policy.allow_data_urls = self.allow_data_urls;
+        if let Some(blocked_schemes) = &self.blocked_schemes {
+            options.url_policy.blocked_schemes = blocked_schemes.clone();
+        }
+        if self.html5 {
+            options.serialization = SerializationStyle::Html5;
+        }
+        Ok(prosemirror)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_builds_a_renderer_from_json() {
+        let config = RenderConfig::from_json(&json!({
+            "plugins": ["starter-kit", "tables"],
+            "rejectUnsafeUrls": true,
+            "html5": true
+        }))
+        .unwrap();
+        let prosemirror = config.build().unwrap();
+        let content = json!({"type":"doc","content":[
+            {"type":"table","content":[]},
+            {"type":"hardBreak"}
+        ]});
+
+        assert_eq!(
+            prosemirror.render(&content).unwrap(),
+            "<div><table><tbody></tbody></table><br></div>"
+        );
+        assert!(prosemirror
+            .render(&json!({"type":"image","attrs":{"src":"javascript:alert(1)"}}))
+            .is_err());
+        assert_eq!(
+            RenderConfig::from_json(&Value::Null).unwrap(),
+            RenderConfig::default()
+        );
+    }
+
+    #[test]
+    fn it_rejects_bad_options() {
+        let config = RenderConfig::from_json(&json!({"plugins": ["tables", "sparkles"]})).unwrap();
+        assert_eq!(config.build().err().unwrap().path(), "/plugins/1");
+        assert!(matches!(
+            RenderConfig::from_json_str(r#"{"stict": true}"#),
+            Err(ProseMirrorError::InvalidOptions { .. })
+        ));
+    }
+}
@@ -0,0 +1,748 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use serde_json::{json, Map, Value};
+
+use crate::cache::{cache_key, RenderCache};
+use crate::error::ProseMirrorError;
+use crate::model::Node;
+use crate::options::{EmptyNodePolicy, RenderOptions, UnknownNodeStrategy};
+use crate::plugins::{Plugin, Tag};
+use crate::stats::RenderStats;
+use crate::ProseMirror;
+
+// Free-form state shared by every plugin for the duration of one render
+#[derive(Debug, Default)]
+pub struct RenderState {
+    values: HashMap<String, Value>,
+}
+
+impl RenderState {
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.values.get(key)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        self.values.get_mut(key)
+    }
+
+    pub fn get_or_insert(&mut self, key: &str, default: Value) -> &mut Value {
+        self.values.entry(key.to_string()).or_insert(default)
+    }
+
+    pub fn insert(&mut self, key: &str, value: Value) -> Option<Value> {
+        self.values.insert(key.to_string(), value)
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        self.values.remove(key)
+    }
+}
+
+pub struct RenderContext<'a> {
+    prosemirror: &'a ProseMirror,
+    ancestors: Vec<String>,
+    index: usize,
+    path: Vec<String>,
+    // Only set while rendering through `render_with_report`
+    errors: Option<Vec<ProseMirrorError>>,
+    // Only set while rendering through `render_with_stats`
+    stats: Option<RenderStats>,
+    // The root is only a wrapper around the top-level nodes of a fragment
+    fragment: bool,
+    // Set once plugins have appended their end of document output
+    finished: bool,
+    // HTML already produced by async plugins, keyed by node path
+    #[cfg(feature = "async")]
+    resolved: HashMap<String, String>,
+    pub state: RenderState,
+}
+
+impl<'a> RenderContext<'a> {
+    pub fn new(prosemirror: &'a ProseMirror) -> Self {
+        Self {
+            prosemirror,
+            ancestors: vec![],
+            index: 0,
+            path: vec![],
+            errors: None,
+            stats: None,
+            fragment: false,
+            finished: false,
+            #[cfg(feature = "async")]
+            resolved: HashMap::new(),
+            state: RenderState::default(),
+        }
+    }
+
+    pub(crate) fn collecting(prosemirror: &'a ProseMirror) -> Self {
+        Self {
+            errors: Some(vec![]),
+            ..Self::new(prosemirror)
+        }
+    }
+
+    pub(crate) fn measuring(prosemirror: &'a ProseMirror) -> Self {
+        Self {
+            stats: Some(RenderStats::default()),
+            ..Self::new(prosemirror)
+        }
+    }
+
+    pub(crate) fn fragment(prosemirror: &'a ProseMirror) -> Self {
+        Self {
+            fragment: true,
+            ..Self::new(prosemirror)
+        }
+    }
+
+    #[cfg(feature = "async")]
+    pub(crate) fn with_resolved(mut self, resolved: HashMap<String, String>) -> Self {
+        self.resolved = resolved;
+        self
+    }
+
+    pub(crate) fn take_errors(&mut self) -> Vec<ProseMirrorError> {
+        self.errors.take().unwrap_or_default()
+    }
+
+    pub(crate) fn take_stats(&mut self) -> RenderStats {
+        self.stats.take().unwrap_or_default()
+    }
+
+    pub fn prosemirror(&self) -> &'a ProseMirror {
+        self.prosemirror
+    }
+
+    pub fn options(&self) -> &'a RenderOptions {
+        self.prosemirror.options()
+    }
+
+    pub fn parent_type(&self) -> Option<&str> {
+        self.ancestors.last().map(|t| t.as_str())
+    }
+
+    pub fn ancestors(&self) -> &[String] {
+        &self.ancestors
+    }
+
+    // The root node is at depth 0
+    pub fn depth(&self) -> usize {
+        self.ancestors.len()
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    // JSON pointer to the node currently being rendered
+    pub fn path(&self) -> String {
+        self.path.concat()
+    }
+
+    pub fn tag(&self, name: &'static str, is_self_closing: bool) -> Tag<'a> {
+        self.prosemirror.tag(name, is_self_closing)
+    }
+
+    pub fn tag_for(&self, node_type: &str, name: &'static str, is_self_closing: bool) -> Tag<'a> {
+        self.prosemirror.tag_for(node_type, name, is_self_closing)
+    }
+
+    pub fn escape_text(&self, text: &str) -> String {
+        self.prosemirror.escape_text(text)
+    }
+
+    pub fn node_attrs(&self, node_type: &str, node: &Value) -> Option<Map<String, Value>> {
+        self.prosemirror.node_attrs(node_type, node)
+    }
+
+    pub fn sanitize_url_attr(
+        &self,
+        attrs: &mut Map<String, Value>,
+        key: &str,
+    ) -> Result<(), ProseMirrorError> {
+        self.prosemirror.sanitize_url_attr(attrs, key)
+    }
+
+    // Stores the error, with a path relative to the current node, when a report is
+    // being collected. Otherwise hands it back so it can be propagated.
+    pub fn record(&mut self, err: ProseMirrorError) -> Option<ProseMirrorError> {
+        match self.errors.as_mut() {
+            Some(errors) => {
+                errors.push(err.with_path_prefix(&self.path.concat()));
+                None
+            }
+            None => Some(err),
+        }
+    }
+
+    // The pre-pass: hands every node to its plugin's `collect` in document order
+    pub(crate) fn collect(&mut self, root: &Value) -> Result<(), ProseMirrorError> {
+        let root = Node::from_value(root)
+            .map_err(|err| ProseMirrorError::invalid_shape(&err.to_string()))?;
+        let mut stack = vec![&root];
+        while let Some(node) = stack.pop() {
+            if let Some(plugin) = self.prosemirror.plugin(node.node_type.as_str()) {
+                plugin.collect(node, &mut self.state);
+            }
+            stack.extend(node.content.iter().rev());
+        }
+        Ok(())
+    }
+
+    // The root node plus whatever plugins append once the document is done, which
+    // goes inside a `doc` root's wrapper or after any other root
+    pub(crate) fn render_document(
+        &mut self,
+        root: &Value,
+        writer: &mut dyn fmt::Write,
+    ) -> Result<(), ProseMirrorError> {
+        match root.get("content") {
+            Some(Value::Array(content)) if self.fragment => {
+                self.render_children_to(content, writer)?
+            }
+            _ => self.render_node_to(root, true, writer)?,
+        }
+        self.finish_document(writer)
+    }
+
+    pub(crate) fn finish_document(
+        &mut self,
+        writer: &mut dyn fmt::Write,
+    ) -> Result<(), ProseMirrorError> {
+        if self.finished {
+            return Ok(());
+        }
+        self.finished = true;
+        let prosemirror = self.prosemirror;
+        for plugin in prosemirror.plugins_in_order() {
+            plugin.finish(self, writer)?;
+        }
+        Ok(())
+    }
+
+    pub fn render_content(&mut self, node: &Value) -> Result<String, ProseMirrorError> {
+        let mut output = String::new();
+        self.render_content_to(node, &mut output)?;
+        Ok(output)
+    }
+
+    pub fn render_content_to(
+        &mut self,
+        node: &Value,
+        writer: &mut dyn fmt::Write,
+    ) -> Result<(), ProseMirrorError> {
+        let content = match node.get("content") {
+            None | Some(Value::Null) => return Ok(()),
+            Some(Value::Array(content)) => content,
+            Some(_) => {
+                return Err(ProseMirrorError::invalid_shape(
+                    "`content` must be an array",
+                ))
+            }
+        };
+
+        let node_type = node
+            .get("type")
+            .and_then(|t| t.as_str())
+            .unwrap_or_default();
+        let is_root_doc = self.ancestors.is_empty() && node_type == "doc";
+        self.ancestors.push(node_type.to_string());
+        let index = self.index;
+        let result = self.render_children_to(content, writer);
+        self.index = index;
+        self.ancestors.pop();
+        if is_root_doc && result.is_ok() {
+            return self.finish_document(writer);
+        }
+        result
+    }
+
+    fn render_children_to(
+        &mut self,
+        content: &[Value],
+        writer: &mut dyn fmt::Write,
+    ) -> Result<(), ProseMirrorError> {
+        for (index, child_node) in content.iter().enumerate() {
+            self.render_child_to(index, child_node, writer)?;
+        }
+        Ok(())
+    }
+
+    fn render_child_to(
+        &mut self,
+        index: usize,
+        child_node: &Value,
+        writer: &mut dyn fmt::Write,
+    ) -> Result<(), ProseMirrorError> {
+        if child_node.get("type").is_none() {
+            return Ok(());
+        }
+        let segment = format!("/content/{}", index);
+        self.path.push(segment.clone());
+        self.index = index;
+        if let Some(cache) = self.cache() {
+            let key = cache_key(child_node);
+            let cached = cache.get(key);
+            if let Some(stats) = self.stats.as_mut() {
+                match cached {
+                    Some(_) => stats.cache_hits += 1,
+                    None => stats.cache_misses += 1,
+                }
+            }
+            let child = match cached {
+                Some(html) => writer.write_str(&html).map_err(|err| err.into()),
+                None => {
+                    let mut buffer = String::new();
+                    self.render_node_to(child_node, false, &mut buffer)
+                        .and_then(|_| Ok(writer.write_str(&buffer)?))
+                        .map(|_| cache.insert(key, buffer))
+                }
+            };
+            self.path.pop();
+            return child.map_err(|err| err.with_path_prefix(&segment));
+        }
+        // While collecting a report a failing child must not leave partial
+        // output behind, so it's buffered instead of streamed
+        let child = if self.errors.is_some() {
+            let mut buffer = String::new();
+            self.render_node_to(child_node, false, &mut buffer)
+                .and_then(|_| Ok(writer.write_str(&buffer)?))
+        } else {
+            self.render_node_to(child_node, false, writer)
+        };
+        self.path.pop();
+
+        if let Err(err) = child {
+            if let Some(err) = self.record(err.with_path_prefix(&segment)) {
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    // Only top-level blocks are cached, and only when rendering them can't depend
+    // on anything but their own JSON, so never alongside `RenderState`
+    fn cache(&self) -> Option<&'a dyn RenderCache> {
+        #[cfg(feature = "async")]
+        if !self.resolved.is_empty() {
+            return None;
+        }
+        let prosemirror = self.prosemirror;
+        prosemirror.cache.as_deref().filter(|_| {
+            self.ancestors.len() <= 1 && self.errors.is_none() && !prosemirror.uses_render_state()
+        })
+    }
+
+    // A child of the root handed over one at a time, for input that's parsed as
+    // it's rendered
+    pub(crate) fn render_streamed_child(
+        &mut self,
+        root_type: &str,
+        index: usize,
+        child_node: &Value,
+        writer: &mut dyn fmt::Write,
+    ) -> Result<(), ProseMirrorError> {
+        self.ancestors.push(root_type.to_string());
+        let result = self.render_child_to(index, child_node, writer);
+        self.ancestors.pop();
+        result
+    }
+
+    // A root node can't be skipped, so `Skip` only applies below the root
+    pub(crate) fn render_node_to(
+        &mut self,
+        node: &Value,
+        is_root: bool,
+        writer: &mut dyn fmt::Write,
+    ) -> Result<(), ProseMirrorError> {
+        #[cfg(feature = "async")]
+        if !self.resolved.is_empty() {
+            if let Some(html) = self.resolved.remove(&self.path()) {
+                writer.write_str(&html)?;
+                return Ok(());
+            }
+        }
+
+        let node_type = match node.get("type") {
+            None => None,
+            Some(Value::String(node_type)) => Some(node_type.as_str()),
+            Some(_) => return Err(ProseMirrorError::invalid_shape("`type` must be a string")),
+        };
+        let prosemirror = self.prosemirror;
+        // The built-ins of `with_static_plugins` get a direct, non-virtual call
+        if let Some(plugin) = node_type.and_then(|t| prosemirror.static_plugin(t)) {
+            return self.render_with(plugin, node, node_type, writer);
+        }
+        if let Some(plugin) = node_type.and_then(|t| prosemirror.plugin(t)) {
+            return self.render_with(plugin, node, node_type, writer);
+        }
+
+        let not_found = || ProseMirrorError::TypeNotFound {
+            path: String::new(),
+            type_name: node
+                .get("type")
+                .map(|t| t.as_str().unwrap_or_default().to_string()),
+        };
+        match self.options().unknown_node_strategy() {
+            UnknownNodeStrategy::Skip if !is_root => {}
+            UnknownNodeStrategy::Error | UnknownNodeStrategy::Skip => return Err(not_found()),
+            UnknownNodeStrategy::RenderChildren => self.render_content_to(node, writer)?,
+            UnknownNodeStrategy::Placeholder(comment) => write!(
+                writer,
+                "<!-- {} -->",
+                comment
+                    .replace("{type}", node_type.unwrap_or_default())
+                    .replace("--", "")
+            )?,
+        };
+        // Degrading gracefully still counts as a problem worth reporting
+        if self.errors.is_some() {
+            self.record(not_found());
+        }
+        Ok(())
+    }
+
+    // Generic so a statically dispatched plugin stays a direct call
+    fn render_with<P: Plugin + ?Sized>(
+        &mut self,
+        plugin: &P,
+        node: &Value,
+        node_type: Option<&str>,
+        writer: &mut dyn fmt::Write,
+    ) -> Result<(), ProseMirrorError> {
+        let Some(start) = self.stats.as_mut().map(|stats| stats.enter()) else {
+            return self.render_plugin(plugin, node, node_type, writer);
+        };
+        let result = self.render_plugin(plugin, node, node_type, writer);
+        if let Some(stats) = self.stats.as_mut() {
+            stats.exit_node(node_type.unwrap_or_default(), start);
+        }
+        result
+    }
+
+    fn render_plugin<P: Plugin + ?Sized>(
+        &mut self,
+        plugin: &P,
+        node: &Value,
+        node_type: Option<&str>,
+        writer: &mut dyn fmt::Write,
+    ) -> Result<(), ProseMirrorError> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!(
+            "render_node",
+            node_type = node_type.unwrap_or_default(),
+            path = %self.path()
+        )
+        .entered();
+        let is_empty = || {
+            node.get("content")
+                .and_then(|content| content.as_array())
+                .is_none_or(|content| content.is_empty())
+        };
+        let empty_nodes = self.options().empty_nodes;
+        if empty_nodes != EmptyNodePolicy::Keep
+            && node_type.is_some_and(|t| self.options().is_empty_node_type(t))
+            && is_empty()
+        {
+            if empty_nodes == EmptyNodePolicy::Drop {
+                return Ok(());
+            }
+            // Rendered through the hardBreak plugin so it follows the serialization style
+            let mut node = node.clone();
+            node["content"] = json!([{"type": "hardBreak"}]);
+            return plugin.render_to(&node, self, writer);
+        }
+        plugin.render_to(node, self, writer)
+    }
+
+    // Wraps rendered text in the node's marks, the first mark ending up outermost
+    pub fn render_marks(
+        &mut self,
+        output: String,
+        node: &Value,
+    ) -> Result<String, ProseMirrorError> {
+        let mut output = output;
+        let marks = match node.get("marks") {
+            None | Some(Value::Null) => return Ok(output),
+            Some(Value::Array(marks)) => marks,
+            Some(_) => return Err(ProseMirrorError::invalid_shape("`marks` must be an array")),
+        };
+        let prosemirror = self.prosemirror;
+        for (index, mark) in marks.iter().enumerate().rev() {
+            let segment = format!("/marks/{}", index);
+            let mark_type = mark.get("type").and_then(|t| t.as_str());
+            #[cfg(feature = "tracing")]
+            let _span =
+                tracing::trace_span!("render_mark", mark_type = mark_type.unwrap_or_default())
+                    .entered();
+            let err = match mark_type.and_then(|t| prosemirror.mark_plugin(t)) {
+                // The unmarked text is only needed again if a failing mark gets
+                // recorded instead of ending the render
+                Some(plugin) if self.stats.is_some() => {
+                    let start = self.stats.as_mut().map(|stats| stats.enter());
+                    let marked = plugin.render(output, mark, self);
+                    if let (Some(stats), Some(start)) = (self.stats.as_mut(), start) {
+                        stats.exit_mark(mark_type.unwrap_or_default(), start);
+                    }
+                    output = marked.map_err(|err| err.with_path_prefix(&segment))?;
+                    continue;
+                }
+                Some(plugin) if self.errors.is_none() => {
+                    output = plugin
+                        .render(output, mark, self)
+                        .map_err(|err| err.with_path_prefix(&segment))?;
+                    continue;
+                }
+                Some(plugin) => match plugin.render(output.clone(), mark, self) {
+                    Ok(marked) => {
+                        output = marked;
+                        continue;
+                    }
+                    Err(err) => err.with_path_prefix(&segment),
+                },
+                None if self.options().strict || self.errors.is_some() => {
+                    ProseMirrorError::MarkNotFound {
+                        path: segment,
+                        mark_name: mark_type.map(|t| t.to_string()),
+                    }
+                }
+                None => continue,
+            };
+            // A failing mark is left off and the text rendered without it
+            if let Some(err) = self.record(err) {
+                return Err(err);
+            }
+        }
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::{ParagraphPlugin, Plugin, TextPlugin};
+    use serde_json::json;
+
+    #[derive(Default)]
+    struct WherePlugin;
+
+    impl Plugin for WherePlugin {
+        fn render(
+            &self,
+            _node: &Value,
+            ctx: &mut RenderContext,
+        ) -> Result<String, ProseMirrorError> {
+            Ok(format!(
+                "[{} {} {} {}]",
+                ctx.parent_type().unwrap_or("-"),
+                ctx.depth(),
+                ctx.index(),
+                ctx.path()
+            ))
+        }
+    }
+
+    #[derive(Default)]
+    struct CounterPlugin;
+
+    impl Plugin for CounterPlugin {
+        fn render(
+            &self,
+            _node: &Value,
+            ctx: &mut RenderContext,
+        ) -> Result<String, ProseMirrorError> {
+            let count = ctx.state.get_or_insert("count", json!(0));
+            *count = json!(count.as_u64().unwrap_or_default() + 1);
+            Ok(count.to_string())
+        }
+    }
+
+    // Renders "n/total" so it needs to know how many there are up front
+    #[derive(Default)]
+    struct StepPlugin;
+
+    impl Plugin for StepPlugin {
+        fn render(
+            &self,
+            _node: &Value,
+            ctx: &mut RenderContext,
+        ) -> Result<String, ProseMirrorError> {
+            let total = ctx.state.get("steps").cloned().unwrap_or(json!(0));
+            let step = ctx.state.get_or_insert("step", json!(0));
+            *step = json!(step.as_u64().unwrap_or_default() + 1);
+            Ok(format!("{}/{}", step, total))
+        }
+
+        fn collect(&self, _node: &Node, state: &mut RenderState) {
+            let steps = state.get_or_insert("steps", json!(0));
+            *steps = json!(steps.as_u64().unwrap_or_default() + 1);
+        }
+    }
+
+    #[test]
+    fn it_collects_before_rendering() {
+        let content = json!({"type":"doc","content":[
+            {"type":"step"},
+            {"type":"blockquote","content":[{"type":"step"}]},
+            {"type":"step"}
+        ]});
+        let prose_mirror = ProseMirror::builder()
+            .with_defaults()
+            .with_plugin("step", Box::new(StepPlugin))
+            .collect_pass(true)
+            .build();
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div>1/3<blockquote>2/3</blockquote>3/3</div>".to_string()
+        );
+
+        let prose_mirror = ProseMirror::builder()
+            .with_defaults()
+            .with_plugin("step", Box::new(StepPlugin))
+            .build();
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div>1/0<blockquote>2/0</blockquote>3/0</div>".to_string()
+        );
+    }
+
+    #[test]
+    fn it_tracks_position_in_the_tree() {
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        prose_mirror.add_plugin("where", Box::new(WherePlugin));
+        let content = json!({"type":"doc","content":[
+            {"type":"where"},
+            {"type":"blockquote","content":[{"type":"paragraph"},{"type":"where"}]}
+        ]});
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div>[doc 1 0 /content/0]<blockquote><p></p>[blockquote 2 1 /content/1/content/1]</blockquote></div>"
+                .to_string()
+        );
+        assert_eq!(
+            prose_mirror.render(&json!({"type":"where"})).unwrap(),
+            "[- 0 0 ]".to_string()
+        );
+    }
+
+    #[test]
+    fn it_shares_state_within_one_render() {
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        prose_mirror.add_plugin("counter", Box::new(CounterPlugin));
+        let content = json!({"type":"doc","content":[
+            {"type":"counter"},
+            {"type":"paragraph","content":[{"type":"counter"}]},
+            {"type":"counter"}
+        ]});
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div>1<p>2</p>3</div>".to_string()
+        );
+        // Every render starts from a fresh state
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div>1<p>2</p>3</div>".to_string()
+        );
+    }
+
+    #[test]
+    fn it_applies_the_empty_node_policy() {
+        let content = json!({"type":"doc","content":[
+            {"type":"paragraph"},
+            {"type":"paragraph","content":[]},
+            {"type":"paragraph","content":[{"type":"text","text":"Go"}]},
+            {"type":"blockquote"}
+        ]});
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div><p></p><p></p><p>Go</p><blockquote></blockquote></div>".to_string()
+        );
+        prose_mirror.options_mut().empty_nodes = EmptyNodePolicy::BrPlaceholder;
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div><p><br /></p><p><br /></p><p>Go</p><blockquote></blockquote></div>".to_string()
+        );
+        prose_mirror.options_mut().empty_nodes = EmptyNodePolicy::Drop;
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div><p>Go</p><blockquote></blockquote></div>".to_string()
+        );
+    }
+
+    #[test]
+    fn it_renders_fragments() {
+        let mut prose_mirror = ProseMirror::new();
+        ParagraphPlugin::register(&mut prose_mirror);
+        TextPlugin::register(&mut prose_mirror);
+        let caption = json!([
+            {"type":"paragraph","content":[{"type":"text","text":"Go"}]},
+            {"type":"text","text":"Leafs"}
+        ]);
+
+        assert_eq!(
+            prose_mirror.render_fragment(&caption).unwrap(),
+            "<p>Go</p>Leafs".to_string()
+        );
+        assert_eq!(
+            prose_mirror
+                .render_fragment(&json!({"type":"text","text":"Go"}))
+                .unwrap(),
+            "Go".to_string()
+        );
+        assert_eq!(
+            prose_mirror.render_fragment(&json!([])).unwrap(),
+            String::new()
+        );
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn it_renders_blocks_in_parallel() {
+        let prose_mirror = ProseMirror::with_default_plugins();
+        let blocks: Vec<Value> = (0..64)
+            .map(|index| {
+                json!({"type":"paragraph","content":[{"type":"text","text":index.to_string()}]})
+            })
+            .collect();
+        let content = json!({"type":"doc","content":blocks});
+
+        assert_eq!(
+            prose_mirror.render_parallel(&content).unwrap(),
+            prose_mirror.render(&content).unwrap()
+        );
+        assert_eq!(
+            prose_mirror
+                .render_parallel(&json!({"type":"doc","content":[{"type":"youtube"},{"type":"paragraph","content":7}]}))
+                .unwrap_err(),
+            prose_mirror
+                .render(&json!({"type":"doc","content":[{"type":"youtube"},{"type":"paragraph","content":7}]}))
+                .unwrap_err()
+        );
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn it_renders_stateful_documents_in_order() {
+        let prose_mirror = ProseMirror::builder()
+            .with_defaults()
+            .heading_anchors(crate::HeadingAnchors::new())
+            .build();
+        let heading = json!({"type":"heading","attrs":{"level":1},"content":[{"type":"text","text":"Intro"}]});
+        let content = json!({"type":"doc","content":[heading, heading, heading]});
+
+        assert_eq!(
+            prose_mirror.render_parallel(&content).unwrap(),
+            prose_mirror.render(&content).unwrap()
+        );
+        assert!(prose_mirror
+            .render_parallel(&content)
+            .unwrap()
+            .contains("id=\"intro-2\""));
+    }
+}
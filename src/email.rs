@@ -0,0 +1,131 @@
+use serde_json::{Map, Value};
+
+use crate::options::TextAlignOutput;
+use crate::plugins::ImagePlugin;
+use crate::sanitize::strip_elements;
+use crate::{merge_attributes, ProseMirror};
+
+// Email clients drop <style> blocks, so the few styles that matter go inline
+const DEFAULT_STYLES: [(&str, &str); 9] = [
+    (
+        "blockquote",
+        "margin: 0 0 0 0.8em; padding-left: 0.8em; border-left: 3px solid #dddddd",
+    ),
+    (
+        "codeBlock",
+        "background-color: #f5f5f5; padding: 12px; font-family: monospace; white-space: pre-wrap",
+    ),
+    ("code", "background-color: #f5f5f5; font-family: monospace"),
+    ("horizontalRule", "border: 0; border-top: 1px solid #dddddd"),
+    ("image", "max-width: 100%; height: auto; border: 0"),
+    ("link", "color: #1a73e8"),
+    ("table", "border-collapse: collapse"),
+    ("tableCell", "border: 1px solid #dddddd; padding: 6px"),
+    (
+        "tableHeader",
+        "border: 1px solid #dddddd; padding: 6px; text-align: left",
+    ),
+];
+
+// Elements no email client runs, in case they come in through unescaped HTML
+const STRIPPED_TAGS: [&str; 4] = ["embed", "iframe", "object", "script"];
+
+// A render profile for transactional emails. It overrides plugins, so apply it
+// after registering the defaults.
+pub struct EmailMode {
+    styles: Vec<(String, String)>,
+}
+
+impl Default for EmailMode {
+    fn default() -> Self {
+        Self {
+            styles: DEFAULT_STYLES
+                .iter()
+                .map(|(node_type, style)| (node_type.to_string(), style.to_string()))
+                .collect(),
+        }
+    }
+}
+
+impl EmailMode {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Replaces the inline style of a node or mark type, an empty style removes it
+    pub fn with_style(mut self, node_type: &str, style: &str) -> Self {
+        self.styles.retain(|(existing, _)| existing != node_type);
+        if !style.is_empty() {
+            self.styles.push((node_type.to_string(), style.to_string()));
+        }
+        self
+    }
+
+    pub fn apply(&self, prosemirror: &mut ProseMirror) {
+        for (node_type, style) in &self.styles {
+            let mut extra = Map::new();
+            extra.insert("style".to_string(), Value::from(style.as_str()));
+            let attrs = prosemirror
+                .options_mut()
+                .html_attributes
+                .entry(node_type.clone())
+                .or_default();
+            merge_attributes(attrs, &extra);
+        }
+        prosemirror.options_mut().text_align = TextAlignOutput::StyleAndAlign;
+
+        prosemirror.add_plugin(
+            "image",
+            Box::new(
+                ImagePlugin::new()
+                    .with_loading(None)
+                    .with_decoding(None)
+                    .with_figure(false),
+            ),
+        );
+        // <details> doesn't collapse anywhere but Apple Mail, so it's shown expanded
+        prosemirror.add_fn_plugin("details", |node, ctx| {
+            let content = ctx.render_content(node)?;
+            Ok(ctx.tag("div", false).render_with_attrs(content, None))
+        });
+        prosemirror.add_fn_plugin("detailsSummary", |node, ctx| {
+            let summary = ctx.render_content(node)?;
+            let strong = ctx.tag("strong", false).render_with_attrs(summary, None);
+            Ok(ctx.tag("p", false).render_with_attrs(strong, None))
+        });
+        prosemirror.add_fn_plugin("iframe", |_, _| Ok(String::new()));
+        prosemirror.add_post_processor(Box::new(|html| strip_elements(html, &STRIPPED_TAGS)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::register_details_plugins;
+    use serde_json::json;
+
+    #[test]
+    fn it_renders_email_safe_html() {
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        register_details_plugins(&mut prose_mirror);
+        EmailMode::new()
+            .with_style("link", "")
+            .apply(&mut prose_mirror);
+        let content = json!({"type":"doc","content":[
+            {"type":"paragraph","attrs":{"textAlign":"center"},"content":[
+                {"type":"text","text":"Go","marks":[{"type":"link","attrs":{"href":"https://nhl.com"}}]}
+            ]},
+            {"type":"image","attrs":{"src":"/rink.png","title":"Rink"}},
+            {"type":"details","content":[
+                {"type":"detailsSummary","content":[{"type":"text","text":"Lineup"}]},
+                {"type":"detailsContent","content":[{"type":"paragraph","content":[{"type":"text","text":"Matthews"}]}]}
+            ]},
+            {"type":"iframe","attrs":{"src":"https://www.youtube.com/embed/x"}}
+        ]});
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div><p align=\"center\" style=\"text-align: center\"><a href=\"https://nhl.com\">Go</a></p><table role=\"presentation\"><tr><td><img src=\"/rink.png\" style=\"max-width: 100%; height: auto; border: 0\" /></td></tr><tr><td>Rink</td></tr></table><div><p><strong>Lineup</strong></p><div data-type=\"detailsContent\"><p>Matthews</p></div></div></div>"
+        );
+    }
+}
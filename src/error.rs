@@ -1,18 +1,150 @@
 use std::error::Error;
 use std::fmt;
 
+// Which of the `RenderOptions` limits a document ran into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limit {
+    // `max_depth`
+    Depth,
+    // `max_nodes`
+    Nodes,
+}
+
 // A generic error type for Tiptap
 #[derive(PartialEq)]
 pub enum ProseMirrorError {
-    TypeNotFound { type_name: Option<String> },
+    // Every `path` is a JSON pointer to the failing node, e.g. `/content/3/content/0`
+    TypeNotFound {
+        path: String,
+        type_name: Option<String>,
+    },
+    MarkNotFound {
+        path: String,
+        mark_name: Option<String>,
+    },
+    UnsafeUrl {
+        path: String,
+        url: String,
+    },
+    InvalidNodeShape {
+        path: String,
+        reason: String,
+    },
+    WriteFailed {
+        path: String,
+        reason: String,
+    },
+    LimitExceeded {
+        path: String,
+        limit: Limit,
+        max: usize,
+    },
+    OutputLimitExceeded {
+        path: String,
+        max_bytes: usize,
+    },
+    // `path` points into the options rather than the document
+    InvalidOptions {
+        path: String,
+        reason: String,
+    },
     // You could add more error types here
 }
 
+impl ProseMirrorError {
+    pub(crate) fn invalid_shape(reason: &str) -> Self {
+        ProseMirrorError::InvalidNodeShape {
+            path: String::new(),
+            reason: reason.to_string(),
+        }
+    }
+
+    pub fn path(&self) -> &str {
+        match self {
+            ProseMirrorError::TypeNotFound { path, .. }
+            | ProseMirrorError::MarkNotFound { path, .. }
+            | ProseMirrorError::UnsafeUrl { path, .. }
+            | ProseMirrorError::InvalidNodeShape { path, .. }
+            | ProseMirrorError::WriteFailed { path, .. }
+            | ProseMirrorError::LimitExceeded { path, .. }
+            | ProseMirrorError::OutputLimitExceeded { path, .. }
+            | ProseMirrorError::InvalidOptions { path, .. } => path,
+        }
+    }
+
+    // The variant's name, for reporting errors as data
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ProseMirrorError::TypeNotFound { .. } => "TypeNotFound",
+            ProseMirrorError::MarkNotFound { .. } => "MarkNotFound",
+            ProseMirrorError::UnsafeUrl { .. } => "UnsafeUrl",
+            ProseMirrorError::InvalidNodeShape { .. } => "InvalidNodeShape",
+            ProseMirrorError::WriteFailed { .. } => "WriteFailed",
+            ProseMirrorError::LimitExceeded { .. } => "LimitExceeded",
+            ProseMirrorError::OutputLimitExceeded { .. } => "OutputLimitExceeded",
+            ProseMirrorError::InvalidOptions { .. } => "InvalidOptions",
+        }
+    }
+
+    // Errors bubble up through each ancestor, which prepends its own segment
+    pub(crate) fn with_path_prefix(mut self, prefix: &str) -> Self {
+        match &mut self {
+            ProseMirrorError::TypeNotFound { path, .. }
+            | ProseMirrorError::MarkNotFound { path, .. }
+            | ProseMirrorError::UnsafeUrl { path, .. }
+            | ProseMirrorError::InvalidNodeShape { path, .. }
+            | ProseMirrorError::WriteFailed { path, .. }
+            | ProseMirrorError::LimitExceeded { path, .. }
+            | ProseMirrorError::OutputLimitExceeded { path, .. }
+            | ProseMirrorError::InvalidOptions { path, .. } => path.insert_str(0, prefix),
+        }
+        self
+    }
+
+    pub(crate) fn with_reason(self, reason: &str) -> Self {
+        match self {
+            ProseMirrorError::WriteFailed { path, .. } => ProseMirrorError::WriteFailed {
+                path,
+                reason: reason.to_string(),
+            },
+            err => err,
+        }
+    }
+}
+
 impl fmt::Display for ProseMirrorError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            ProseMirrorError::TypeNotFound { type_name } => {
-                write!(f, "Type not found: {:?}", type_name)
+            ProseMirrorError::TypeNotFound { path, type_name } => {
+                write!(f, "Type not found at {:?}: {:?}", path, type_name)
+            }
+            ProseMirrorError::MarkNotFound { path, mark_name } => {
+                write!(f, "Mark not found at {:?}: {:?}", path, mark_name)
+            }
+            ProseMirrorError::UnsafeUrl { path, url } => {
+                write!(f, "Unsafe URL at {:?}: {:?}", path, url)
+            }
+            ProseMirrorError::InvalidNodeShape { path, reason } => {
+                write!(f, "Invalid node at {:?}: {}", path, reason)
+            }
+            ProseMirrorError::WriteFailed { path, reason } => {
+                write!(f, "Failed writing output at {:?}: {}", path, reason)
+            }
+            ProseMirrorError::LimitExceeded {
+                path,
+                limit: Limit::Depth,
+                max,
+            } => write!(f, "Nesting deeper than {} at {:?}", max, path),
+            ProseMirrorError::LimitExceeded {
+                path,
+                limit: Limit::Nodes,
+                max,
+            } => write!(f, "More than {} nodes at {:?}", max, path),
+            ProseMirrorError::OutputLimitExceeded { path, max_bytes } => {
+                write!(f, "Output larger than {} bytes at {:?}", max_bytes, path)
+            }
+            ProseMirrorError::InvalidOptions { path, reason } => {
+                write!(f, "Invalid options at {:?}: {}", path, reason)
             }
         }
     }
@@ -26,20 +158,11 @@ impl fmt::Debug for ProseMirrorError {
 
 impl Error for ProseMirrorError {}
 
-impl From<TypeNotFound> for ProseMirrorError {
-    fn from(err: TypeNotFound) -> Self {
-        ProseMirrorError::TypeNotFound {
-            type_name: err.type_name,
+impl From<fmt::Error> for ProseMirrorError {
+    fn from(_: fmt::Error) -> Self {
+        ProseMirrorError::WriteFailed {
+            path: String::new(),
+            reason: "the writer returned an error".to_string(),
         }
     }
 }
-
-struct TypeNotFound {
-    type_name: Option<String>,
-}
-
-impl TypeNotFound {
-    fn new(type_name: Option<String>) -> TypeNotFound {
-        TypeNotFound { type_name }
-    }
-}
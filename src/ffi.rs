@@ -0,0 +1,131 @@
+// The C interface, see include/tiptap_to_html.h. Every string crossing it is
+// NUL-terminated UTF-8, and strings handed out are owned by the caller until
+// passed to `tiptap_free`.
+#![allow(clippy::missing_safety_doc)]
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+
+use serde_json::Value;
+
+use crate::error::ProseMirrorError;
+use crate::RenderConfig;
+
+pub const TIPTAP_OK: i32 = 0;
+pub const TIPTAP_NULL_ARGUMENT: i32 = 1;
+pub const TIPTAP_INVALID_UTF8: i32 = 2;
+pub const TIPTAP_INVALID_JSON: i32 = 3;
+pub const TIPTAP_INVALID_OPTIONS: i32 = 4;
+pub const TIPTAP_RENDER_FAILED: i32 = 5;
+
+thread_local! {
+    // The code and message of the last failed call on this thread
+    static LAST_ERROR: RefCell<(i32, CString)> = RefCell::new((TIPTAP_OK, CString::default()));
+}
+
+fn set_last_error(code: i32, message: &str) {
+    // A message can't hold a NUL byte, so anything after one is cut off
+    let message = message.split('\0').next().unwrap_or_default();
+    let message = CString::new(message).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = (code, message));
+}
+
+unsafe fn read_str<'a>(s: *const c_char) -> Result<&'a str, (i32, String)> {
+    if s.is_null() {
+        return Err((TIPTAP_NULL_ARGUMENT, "argument is NULL".to_string()));
+    }
+    CStr::from_ptr(s)
+        .to_str()
+        .map_err(|err| (TIPTAP_INVALID_UTF8, err.to_string()))
+}
+
+unsafe fn render(
+    json: *const c_char,
+    options_json: *const c_char,
+) -> Result<String, (i32, String)> {
+    let content: Value = serde_json::from_str(read_str(json)?)
+        .map_err(|err| (TIPTAP_INVALID_JSON, err.to_string()))?;
+    let config = match options_json.is_null() {
+        true => RenderConfig::default(),
+        false => RenderConfig::from_json_str(read_str(options_json)?)
+            .map_err(|err| (TIPTAP_INVALID_OPTIONS, err.to_string()))?,
+    };
+    let prosemirror = config
+        .build()
+        .map_err(|err| (TIPTAP_INVALID_OPTIONS, err.to_string()))?;
+    prosemirror.render(&content).map_err(|err| {
+        let code = match err {
+            ProseMirrorError::InvalidOptions { .. } => TIPTAP_INVALID_OPTIONS,
+            _ => TIPTAP_RENDER_FAILED,
+        };
+        (code, err.to_string())
+    })
+}
+
+// Renders `json` with the `RenderConfig` in `options_json`, which may be NULL for
+// the defaults. Returns NULL on failure, with the reason in `tiptap_last_error_*`.
+#[no_mangle]
+pub unsafe extern "C" fn tiptap_render(
+    json: *const c_char,
+    options_json: *const c_char,
+) -> *mut c_char {
+    let html = render(json, options_json)
+        .and_then(|html| CString::new(html).map_err(|err| (TIPTAP_RENDER_FAILED, err.to_string())));
+    match html {
+        Ok(html) => {
+            set_last_error(TIPTAP_OK, "");
+            html.into_raw()
+        }
+        Err((code, message)) => {
+            set_last_error(code, &message);
+            ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn tiptap_last_error_code() -> i32 {
+    LAST_ERROR.with(|last| last.borrow().0)
+}
+
+// Valid until the next call into the library on the same thread
+#[no_mangle]
+pub extern "C" fn tiptap_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().1.as_ptr())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn tiptap_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_renders_through_the_c_interface() {
+        let json = CString::new(r#"{"type":"doc","content":[{"type":"paragraph"}]}"#).unwrap();
+        let options = CString::new(r#"{"plugins":["sparkles"]}"#).unwrap();
+
+        unsafe {
+            let html = tiptap_render(json.as_ptr(), ptr::null());
+            assert_eq!(CStr::from_ptr(html).to_str().unwrap(), "<div><p></p></div>");
+            assert_eq!(tiptap_last_error_code(), TIPTAP_OK);
+            tiptap_free(html);
+
+            assert!(tiptap_render(json.as_ptr(), options.as_ptr()).is_null());
+            assert_eq!(tiptap_last_error_code(), TIPTAP_INVALID_OPTIONS);
+            assert!(CStr::from_ptr(tiptap_last_error_message())
+                .to_str()
+                .unwrap()
+                .contains("sparkles"));
+
+            assert!(tiptap_render(ptr::null(), ptr::null()).is_null());
+            assert_eq!(tiptap_last_error_code(), TIPTAP_NULL_ARGUMENT);
+        }
+    }
+}
@@ -0,0 +1,306 @@
+// Reformats rendered HTML, either pretty-printed with block-level tags on their
+// own indented lines or minified. Anything inside <pre> is left untouched.
+const BLOCK_TAGS: [&str; 37] = [
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "col",
+    "colgroup",
+    "dd",
+    "details",
+    "div",
+    "dl",
+    "dt",
+    "figcaption",
+    "figure",
+    "footer",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "li",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "summary",
+    "table",
+    "tbody",
+    "td",
+    "tfoot",
+    "th",
+    "thead",
+    "tr",
+    "ul",
+];
+
+const VOID_TAGS: [&str; 2] = ["col", "hr"];
+
+#[derive(PartialEq)]
+enum Line {
+    // Right after a block's opening tag, its content can follow on the same line
+    Opened,
+    // After a block closed, whatever comes next starts a new line
+    Closed,
+    Inline,
+}
+
+enum Token<'a> {
+    Open(&'a str, &'a str),
+    Close(&'a str, &'a str),
+    Text(&'a str),
+}
+
+fn tag_name(tag: &str) -> &str {
+    let name = tag.trim_start_matches(['<', '/']);
+    let end = name
+        .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+        .unwrap_or(name.len());
+    &name[..end]
+}
+
+fn tokenize(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = vec![];
+    let mut rest = html;
+    while !rest.is_empty() {
+        if rest.starts_with("<!--") {
+            let end = rest.find("-->").map(|end| end + 3).unwrap_or(rest.len());
+            tokens.push(Token::Text(&rest[..end]));
+            rest = &rest[end..];
+        } else if rest.starts_with('<') {
+            let end = rest.find('>').map(|end| end + 1).unwrap_or(rest.len());
+            let tag = &rest[..end];
+            let name = tag_name(tag);
+            if tag.starts_with("</") {
+                tokens.push(Token::Close(tag, name));
+            } else {
+                tokens.push(Token::Open(tag, name));
+            }
+            rest = &rest[end..];
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            tokens.push(Token::Text(&rest[..end]));
+            rest = &rest[end..];
+        }
+    }
+    tokens
+}
+
+pub fn pretty(html: &str, indent: usize) -> String {
+    let mut output = String::with_capacity(html.len() * 2);
+    let mut depth = 0usize;
+    let mut line = Line::Inline;
+    let mut in_pre = 0usize;
+
+    let newline = |output: &mut String, depth: usize| {
+        if !output.is_empty() {
+            output.push('\n');
+        }
+        output.push_str(&" ".repeat(depth * indent));
+    };
+
+    for token in tokenize(html) {
+        match token {
+            _ if in_pre > 0 => match token {
+                Token::Open(tag, "pre") => {
+                    in_pre += 1;
+                    output.push_str(tag);
+                }
+                Token::Close(tag, "pre") => {
+                    in_pre -= 1;
+                    output.push_str(tag);
+                    if in_pre == 0 {
+                        depth = depth.saturating_sub(1);
+                        line = Line::Closed;
+                    }
+                }
+                Token::Open(text, _) | Token::Close(text, _) | Token::Text(text) => {
+                    output.push_str(text)
+                }
+            },
+            Token::Open(tag, name) if BLOCK_TAGS.contains(&name) => {
+                newline(&mut output, depth);
+                output.push_str(tag);
+                if name == "pre" {
+                    in_pre = 1;
+                    depth += 1;
+                } else if VOID_TAGS.contains(&name) || tag.ends_with("/>") {
+                    line = Line::Closed;
+                } else {
+                    depth += 1;
+                    line = Line::Opened;
+                }
+            }
+            Token::Close(tag, name) if BLOCK_TAGS.contains(&name) => {
+                depth = depth.saturating_sub(1);
+                if line == Line::Closed {
+                    newline(&mut output, depth);
+                }
+                output.push_str(tag);
+                line = Line::Closed;
+            }
+            Token::Open(text, _) | Token::Close(text, _) | Token::Text(text) => {
+                if line == Line::Closed {
+                    if text.trim().is_empty() {
+                        continue;
+                    }
+                    newline(&mut output, depth);
+                }
+                line = Line::Inline;
+                output.push_str(text);
+            }
+        }
+    }
+    output
+}
+
+// Collapses whitespace outside of quoted attr values, e.g. `<p  class="a" >`
+// becomes `<p class="a">`. The ` />` of void elements is left as it was written.
+fn minify_tag(tag: &str) -> String {
+    let mut output = String::with_capacity(tag.len());
+    let mut quote = None;
+    let mut space = false;
+    for c in tag.chars() {
+        match quote {
+            Some(open) => {
+                output.push(c);
+                if c == open {
+                    quote = None;
+                }
+            }
+            None if c.is_whitespace() => space = true,
+            None => {
+                if space && c != '>' {
+                    output.push(' ');
+                }
+                space = false;
+                if c == '"' || c == '\'' {
+                    quote = Some(c);
+                }
+                output.push(c);
+            }
+        }
+    }
+    output
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut space = false;
+    for c in text.chars() {
+        if c.is_whitespace() {
+            space = true;
+        } else {
+            if space {
+                output.push(' ');
+            }
+            space = false;
+            output.push(c);
+        }
+    }
+    if space {
+        output.push(' ');
+    }
+    output
+}
+
+// Whitespace runs in text become a single space, and whitespace-only text next
+// to a block-level tag is dropped since it never renders
+pub fn minify(html: &str) -> String {
+    let tokens = tokenize(html);
+    let is_block = |token: Option<&Token>| match token {
+        Some(Token::Open(_, name)) | Some(Token::Close(_, name)) => BLOCK_TAGS.contains(name),
+        Some(Token::Text(_)) => false,
+        None => true,
+    };
+
+    let mut output = String::with_capacity(html.len());
+    let mut in_pre = 0usize;
+    for (index, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Open(tag, name) => {
+                if *name == "pre" {
+                    in_pre += 1;
+                }
+                output.push_str(&minify_tag(tag));
+            }
+            Token::Close(tag, name) => {
+                if *name == "pre" {
+                    in_pre = in_pre.saturating_sub(1);
+                }
+                output.push_str(&minify_tag(tag));
+            }
+            Token::Text(text) if in_pre > 0 || text.starts_with("<!--") => output.push_str(text),
+            Token::Text(text) => {
+                let previous = index.checked_sub(1).and_then(|index| tokens.get(index));
+                if text.trim().is_empty() && (is_block(previous) || is_block(tokens.get(index + 1)))
+                {
+                    continue;
+                }
+                output.push_str(&collapse_whitespace(text));
+            }
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ProseMirror, RenderOptions};
+    use serde_json::json;
+
+    #[test]
+    fn it_indents_block_tags() {
+        assert_eq!(
+            pretty(
+                "<div><p>Go <strong>Leafs</strong></p><hr /><ul><li><p>One</p></li></ul></div>",
+                2
+            ),
+            "<div>\n  <p>Go <strong>Leafs</strong></p>\n  <hr />\n  <ul>\n    <li>\n      <p>One</p>\n    </li>\n  </ul>\n</div>"
+        );
+    }
+
+    #[test]
+    fn it_leaves_pre_alone() {
+        assert_eq!(
+            pretty(
+                "<div><pre><code>fn main() {\n    <b>x</b>\n}</code></pre><p>a</p></div>",
+                4
+            ),
+            "<div>\n    <pre><code>fn main() {\n    <b>x</b>\n}</code></pre>\n    <p>a</p>\n</div>"
+        );
+    }
+
+    #[test]
+    fn it_minifies() {
+        assert_eq!(
+            minify("<div>\n  <p  class=\"a  b\" >Go   <b>Leafs</b>  <i>go</i></p>\n  <hr />\n  <pre>a\n   b</pre>\n</div>"),
+            "<div><p class=\"a  b\">Go <b>Leafs</b> <i>go</i></p><hr /><pre>a\n   b</pre></div>"
+        );
+    }
+
+    #[test]
+    fn it_renders_pretty() {
+        let content = json!({"type":"doc","content":[
+            {"type":"paragraph","content":[{"type":"text","text":"Go"}]},
+            {"type":"blockquote","content":[{"type":"paragraph","content":[{"type":"text","text":"Leafs"}]}]}
+        ]});
+        let prose_mirror = ProseMirror::builder()
+            .with_defaults()
+            .with_options(RenderOptions::pretty(2))
+            .build();
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div>\n  <p>Go</p>\n  <blockquote>\n    <p>Leafs</p>\n  </blockquote>\n</div>"
+                .to_string()
+        );
+    }
+}
@@ -0,0 +1,693 @@
+use error::ProseMirrorError;
+use model::Node;
+use plugins::starter_kit::StaticPlugins;
+#[cfg(feature = "async")]
+use plugins::AsyncPlugin;
+use plugins::{FnPlugin, MarkPlugin, Plugin, Tag, TocEntry};
+use serde_json::{json, Map, Value};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::time::Instant;
+use std::{fmt, io};
+
+mod amp;
+pub mod as_html;
+pub mod attrs;
+mod builder;
+mod cache;
+#[cfg(feature = "ammonia")]
+mod clean;
+pub mod compat;
+mod config;
+mod context;
+mod email;
+pub mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod format;
+mod limits;
+pub mod markdown;
+#[cfg(feature = "markdown-parser")]
+mod markdown_parse;
+pub mod model;
+mod options;
+#[cfg(feature = "html-parser")]
+pub mod parse;
+mod plain_text;
+pub mod plugins;
+mod post_process;
+#[cfg(feature = "python")]
+mod python;
+mod report;
+mod sanitize;
+mod search;
+mod slug;
+mod stats;
+mod stream;
+mod stylesheet;
+#[cfg(any(feature = "tera", feature = "askama"))]
+pub mod templates;
+pub mod transform;
+mod utils;
+#[cfg(feature = "wasm")]
+mod wasm;
+
+pub use amp::{AmpIframePlugin, AmpImagePlugin, AmpMode};
+pub use as_html::RenderedHtml;
+pub use attrs::{AttrFilter, AttrMapping};
+pub use builder::ProseMirrorBuilder;
+pub use cache::{MemoryCache, RenderCache};
+#[cfg(feature = "ammonia")]
+pub use clean::CleanMode;
+pub use config::RenderConfig;
+pub use context::{RenderContext, RenderState};
+pub use email::EmailMode;
+pub use markdown::MarkdownSerializer;
+#[cfg(feature = "markdown-parser")]
+pub use markdown_parse::from_markdown;
+pub use options::{
+    AttrCoercion, AttrOrder, AttrPolicy, EmptyNodePolicy, ExternalLinks, LinkRewriter, NullAttrs,
+    ObjectAttrs, RenderOptions, SerializationStyle, StyleClasses, StyleStrategy, TagOverrides,
+    TextAlignOutput, UnknownAttrStrategy, UnknownNodeStrategy, UrlRewriter,
+};
+#[cfg(feature = "html-parser")]
+pub use parse::parse_html;
+pub use post_process::PostProcessor;
+pub use report::RenderReport;
+pub use sanitize::{UnsafeUrlAction, UrlPolicy};
+pub use slug::{slugify, HeadingAnchors};
+pub use stats::{RenderStats, TypeStats};
+pub use stylesheet::StyledHtml;
+pub use transform::Transform;
+pub use utils::merge_attributes;
+
+#[derive(Default)]
+pub struct ProseMirror {
+    plugins: HashMap<String, Box<dyn Plugin>>,
+    marks: HashMap<String, Box<dyn MarkPlugin>>,
+    #[cfg(feature = "async")]
+    async_plugins: HashMap<String, Box<dyn AsyncPlugin>>,
+    transforms: Vec<Box<dyn Transform>>,
+    post_processors: Vec<Box<dyn PostProcessor>>,
+    cache: Option<Box<dyn RenderCache>>,
+    static_plugins: Option<StaticPlugins>,
+    options: RenderOptions,
+}
+
+impl ProseMirror {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn builder() -> ProseMirrorBuilder {
+        ProseMirrorBuilder::new()
+    }
+
+    pub fn with_default_plugins() -> Self {
+        let mut prose_mirror = Self::new();
+        prose_mirror.register_defaults();
+        prose_mirror
+    }
+
+    // The same plugins as `with_default_plugins`, but built in at compile time:
+    // built-in nodes are rendered through a match instead of a HashMap lookup and
+    // a virtual call. Plugins added later still override them.
+    pub fn with_static_plugins() -> Self {
+        Self {
+            static_plugins: Some(StaticPlugins::starter_kit()),
+            ..Self::default()
+        }
+    }
+
+    // Registers every built-in node and mark plugin from Tiptap's StarterKit
+    pub fn register_defaults(&mut self) {
+        plugins::register_defaults(self);
+    }
+
+    pub fn add_plugin(&mut self, node_type: &str, plugin: Box<dyn Plugin>) {
+        if let Some(static_plugins) = &mut self.static_plugins {
+            static_plugins.remove_node(node_type);
+        }
+        self.plugins.insert(node_type.to_string(), plugin);
+    }
+
+    // Registers a closure as the renderer for a node type
+    pub fn add_fn_plugin<F>(&mut self, node_type: &str, render: F)
+    where
+        F: Fn(&Value, &mut RenderContext) -> Result<String, ProseMirrorError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.add_plugin(node_type, Box::new(FnPlugin::new(render)));
+    }
+
+    pub fn add_mark_plugin(&mut self, mark_type: &str, plugin: Box<dyn MarkPlugin>) {
+        if let Some(static_plugins) = &mut self.static_plugins {
+            static_plugins.remove_mark(mark_type);
+        }
+        self.marks.insert(mark_type.to_string(), plugin);
+    }
+
+    // Async plugins only take part in `render_async`, the sync render methods
+    // fall back to the regular plugin for the type
+    #[cfg(feature = "async")]
+    pub fn add_async_plugin(&mut self, node_type: &str, plugin: Box<dyn AsyncPlugin>) {
+        self.async_plugins.insert(node_type.to_string(), plugin);
+    }
+
+    // Transforms run in the order they were added, on a copy of the document
+    pub fn add_transform(&mut self, transform: Box<dyn Transform>) {
+        self.transforms.push(transform);
+    }
+
+    // Post-processors run in the order they were added
+    pub fn add_post_processor(&mut self, post_processor: Box<dyn PostProcessor>) {
+        self.post_processors.push(post_processor);
+    }
+
+    // Reuses the HTML of top-level blocks that rendered before. Skipped whenever
+    // `RenderState` is in use, since it can change how a block renders.
+    pub fn set_cache(&mut self, cache: Box<dyn RenderCache>) {
+        self.cache = Some(cache);
+    }
+
+    pub fn plugin(&self, node_type: &str) -> Option<&dyn Plugin> {
+        match self.static_plugin(node_type) {
+            Some(plugin) => Some(plugin),
+            None => self.plugins.get(node_type).map(|plugin| plugin.as_ref()),
+        }
+    }
+
+    pub(crate) fn static_plugin(&self, node_type: &str) -> Option<&plugins::StarterKitNode> {
+        self.static_plugins.as_ref()?.node(node_type)
+    }
+
+    // Every node type with a plugin, then every mark type
+    pub(crate) fn registered_types(&self) -> Vec<String> {
+        let static_plugins = self.static_plugins.as_ref();
+        self.plugins
+            .keys()
+            .cloned()
+            .chain(
+                static_plugins
+                    .into_iter()
+                    .flat_map(|s| s.node_types().map(String::from)),
+            )
+            .chain(self.marks.keys().cloned())
+            .chain(
+                static_plugins
+                    .into_iter()
+                    .flat_map(|s| s.mark_types().map(String::from)),
+            )
+            .collect()
+    }
+
+    // Sorted by node type so anything that depends on plugin order is deterministic
+    pub(crate) fn plugins_in_order(&self) -> Vec<&dyn Plugin> {
+        let mut plugins: Vec<(&str, &dyn Plugin)> = self
+            .plugins
+            .iter()
+            .map(|(node_type, plugin)| (node_type.as_str(), plugin.as_ref()))
+            .collect();
+        if let Some(static_plugins) = &self.static_plugins {
+            for node_type in static_plugins.node_types() {
+                if let Some(plugin) = static_plugins.node(node_type) {
+                    plugins.push((node_type, plugin));
+                }
+            }
+        }
+        plugins.sort_by_key(|(node_type, _)| *node_type);
+        plugins.into_iter().map(|(_, plugin)| plugin).collect()
+    }
+
+    // Whether a block's HTML can depend on the blocks before it: collected state,
+    // heading anchors de-duplicated across the document, footnote numbers...
+    pub(crate) fn uses_render_state(&self) -> bool {
+        self.runs_collect_pass() || self.plugins.values().any(|plugin| plugin.uses_state())
+    }
+
+    // Heading anchors need it too, explicit ids are reserved before any slug is
+    // generated so a later `id` can't collide with an earlier generated one
+    pub(crate) fn runs_collect_pass(&self) -> bool {
+        self.options.collect_pass || self.options.heading_anchors.is_some()
+    }
+
+    pub fn mark_plugin(&self, mark_type: &str) -> Option<&dyn MarkPlugin> {
+        match self.static_plugins.as_ref().and_then(|s| s.mark(mark_type)) {
+            Some(plugin) => Some(plugin),
+            None => self.marks.get(mark_type).map(|plugin| plugin.as_ref()),
+        }
+    }
+
+    pub fn options(&self) -> &RenderOptions {
+        &self.options
+    }
+
+    pub fn options_mut(&mut self) -> &mut RenderOptions {
+        &mut self.options
+    }
+
+    pub fn tag(&self, name: &'static str, is_self_closing: bool) -> Tag<'_> {
+        Tag::with_options(name, is_self_closing, &self.options)
+    }
+
+    // The tag a plugin renders for `node_type`, unless `tag_overrides` swaps it
+    pub fn tag_for(&self, node_type: &str, name: &'static str, is_self_closing: bool) -> Tag<'_> {
+        match self.options.tag_overrides.get(node_type) {
+            Some(Some(name)) => Tag::with_options(name, is_self_closing, &self.options),
+            Some(None) => Tag::with_options("", is_self_closing, &self.options),
+            None => self.tag(name, is_self_closing),
+        }
+    }
+
+    pub fn escape_text(&self, text: &str) -> String {
+        if self.options.escape_html {
+            utils::escape_html(text)
+        } else {
+            text.to_string()
+        }
+    }
+
+    // Applies the URL policy to a `href`/`src` style attribute in place
+    pub fn sanitize_url_attr(
+        &self,
+        attrs: &mut Map<String, Value>,
+        key: &str,
+    ) -> Result<(), ProseMirrorError> {
+        let url = match attrs.get(key).and_then(|url| url.as_str()) {
+            Some(url) => url,
+            None => return Ok(()),
+        };
+        if self.options.url_policy.is_allowed(url) {
+            return Ok(());
+        }
+        match self.options.url_policy.action {
+            UnsafeUrlAction::Strip => {
+                attrs.remove(key);
+                Ok(())
+            }
+            UnsafeUrlAction::Reject => Err(ProseMirrorError::UnsafeUrl {
+                path: String::new(),
+                url: url.to_string(),
+            }),
+        }
+    }
+
+    pub fn render(&self, content: &Value) -> Result<String, ProseMirrorError> {
+        let mut output = String::with_capacity(utils::estimated_len(content));
+        self.render_to(content, &mut output)?;
+        Ok(output)
+    }
+
+    // Streams the rendered document into `writer`. On error the writer may
+    // already hold partial output.
+    pub fn render_to<W: fmt::Write>(
+        &self,
+        content: &Value,
+        writer: &mut W,
+    ) -> Result<(), ProseMirrorError> {
+        self.render_with_context(content, &mut RenderContext::new(self), writer)
+    }
+
+    pub fn render_with_context(
+        &self,
+        content: &Value,
+        ctx: &mut RenderContext,
+        writer: &mut dyn fmt::Write,
+    ) -> Result<(), ProseMirrorError> {
+        #[cfg(feature = "tracing")]
+        let _span = document_span(content).entered();
+        let content = self.transformed(content)?;
+        let content = content.as_ref();
+        if self.runs_collect_pass() {
+            ctx.collect(content)?;
+        }
+        let max_bytes = self.options.max_output_bytes;
+        if self.post_processors.is_empty() && self.options.indent.is_none() && !self.options.minify
+        {
+            return limits::write_limited(writer, max_bytes, |writer| {
+                ctx.render_document(content, writer)
+            });
+        }
+
+        // Formatting and post-processors need the whole document, so there's
+        // nothing to stream
+        let mut output = String::new();
+        limits::write_limited(&mut output, max_bytes, |writer| {
+            ctx.render_document(content, writer)
+        })?;
+        let output = self.post_process(output);
+        limits::write_limited(writer, max_bytes, |writer| Ok(writer.write_str(&output)?))
+    }
+
+    // Transforms may recurse or add nodes, so the limits are checked before them
+    // as well as after
+    fn transformed<'v>(&self, content: &'v Value) -> Result<Cow<'v, Value>, ProseMirrorError> {
+        let check_limits = |content: &Value| self.tree_limits().check(content, "", 0);
+        check_limits(content)?;
+        if self.transforms.is_empty() {
+            return Ok(Cow::Borrowed(content));
+        }
+
+        let mut content = content.clone();
+        for transform in &self.transforms {
+            transform.transform(&mut content);
+        }
+        check_limits(&content)?;
+        Ok(Cow::Owned(content))
+    }
+
+    pub(crate) fn tree_limits(&self) -> limits::TreeLimits {
+        limits::TreeLimits::new(self.options.max_depth, self.options.max_nodes)
+    }
+
+    fn post_process(&self, html: String) -> String {
+        let html = if self.options.minify {
+            format::minify(&html)
+        } else {
+            html
+        };
+        let html = match self.options.indent {
+            Some(indent) => format::pretty(&html, indent),
+            None => html,
+        };
+        self.post_processors
+            .iter()
+            .fold(html, |html, post_processor| post_processor.process(html))
+    }
+
+    pub fn render_to_io<W: io::Write>(
+        &self,
+        content: &Value,
+        writer: &mut W,
+    ) -> Result<(), ProseMirrorError> {
+        let mut adapter = utils::IoWriter::new(writer);
+        let result = self.render_to(content, &mut adapter);
+        adapter.into_result(result)
+    }
+
+    // Parses and renders JSON from `reader` in one go, keeping only a single
+    // top-level block in memory at a time
+    pub fn render_reader<R: io::Read, W: io::Write>(
+        &self,
+        reader: R,
+        writer: &mut W,
+    ) -> Result<(), ProseMirrorError> {
+        stream::render_reader(self, reader, writer)
+    }
+
+    // Renders a stored fragment, either a bare array of nodes or any single node,
+    // without it needing a `doc` around it
+    pub fn render_fragment(&self, content: &Value) -> Result<String, ProseMirrorError> {
+        let mut output = String::new();
+        match content {
+            Value::Array(nodes) => {
+                let fragment = json!({"type": "", "content": nodes});
+                let mut ctx = RenderContext::fragment(self);
+                self.render_with_context(&fragment, &mut ctx, &mut output)?;
+            }
+            _ => self.render_to(content, &mut output)?,
+        }
+        Ok(output)
+    }
+
+    // Readable plain text for notification emails, previews and search indexing:
+    // blocks separated by blank lines, list markers, and link URLs in parentheses
+    pub fn render_text(&self, content: &Value) -> Result<String, ProseMirrorError> {
+        let content = self.transformed(content)?;
+        Ok(plain_text::render_text(&content))
+    }
+
+    pub fn render_node(&self, node: &Node) -> Result<String, ProseMirrorError> {
+        self.render(&node.to_value())
+    }
+
+    // Awaits every node that has an async plugin first, then renders the rest of the
+    // document around the resolved HTML
+    #[cfg(feature = "async")]
+    pub async fn render_async(&self, content: &Value) -> Result<String, ProseMirrorError> {
+        let content = self.transformed(content)?;
+        let content = content.as_ref();
+
+        let mut resolved = HashMap::new();
+        let mut stack = vec![(String::new(), content)];
+        while let Some((path, node)) = stack.pop() {
+            let plugin = node
+                .get("type")
+                .and_then(|t| t.as_str())
+                .and_then(|t| self.async_plugins.get(t));
+            if let Some(plugin) = plugin {
+                let html = plugin
+                    .render(node)
+                    .await
+                    .map_err(|err| err.with_path_prefix(&path))?;
+                resolved.insert(path, html);
+                continue;
+            }
+            if let Some(children) = node.get("content").and_then(|c| c.as_array()) {
+                for (index, child) in children.iter().enumerate().rev() {
+                    stack.push((format!("{}/content/{}", path, index), child));
+                }
+            }
+        }
+
+        // Entered only once nothing is awaited anymore
+        #[cfg(feature = "tracing")]
+        let _span = document_span(content).entered();
+        let mut ctx = RenderContext::new(self).with_resolved(resolved);
+        if self.runs_collect_pass() {
+            ctx.collect(content)?;
+        }
+        let mut output = String::new();
+        limits::write_limited(&mut output, self.options.max_output_bytes, |writer| {
+            ctx.render_document(content, writer)
+        })?;
+        Ok(self.post_process(output))
+    }
+
+    // Renders the root's children on rayon's thread pool and joins them in order.
+    // Each block gets a context of its own, so whenever `RenderState` is in use
+    // (heading anchors, footnotes...) this is just `render`.
+    #[cfg(feature = "parallel")]
+    pub fn render_parallel(&self, content: &Value) -> Result<String, ProseMirrorError> {
+        use rayon::prelude::*;
+
+        #[cfg(feature = "tracing")]
+        let span = document_span(content);
+        #[cfg(feature = "tracing")]
+        let _entered = span.enter();
+        let content = self.transformed(content)?;
+        let content = content.as_ref();
+        let halves = content
+            .as_object()
+            .filter(|_| !self.uses_render_state())
+            .and_then(|root| Some((root, root.get("content")?.as_array()?)))
+            .and_then(|(root, children)| Some((stream::split_root(self, root)?, children)));
+        let Some(((prefix, suffix), children)) = halves else {
+            return self.render(content);
+        };
+
+        let root_type = content["type"].as_str().unwrap_or_default();
+        let blocks = children
+            .par_iter()
+            .enumerate()
+            .map(|(index, child)| {
+                // rayon's threads don't know about the span entered above
+                #[cfg(feature = "tracing")]
+                let _entered = span.enter();
+                let mut output = String::new();
+                limits::write_limited(&mut output, self.options.max_output_bytes, |writer| {
+                    RenderContext::new(self).render_streamed_child(root_type, index, child, writer)
+                })?;
+                Ok(output)
+            })
+            .collect::<Result<Vec<String>, ProseMirrorError>>()?;
+
+        // Each block was only held to the limit on its own
+        let mut output = String::new();
+        limits::write_limited(&mut output, self.options.max_output_bytes, |writer| {
+            writer.write_str(&prefix)?;
+            for (index, block) in blocks.iter().enumerate() {
+                writer.write_str(block).map_err(|err| {
+                    ProseMirrorError::from(err).with_path_prefix(&format!("/content/{}", index))
+                })?;
+            }
+            writer.write_str(&suffix)?;
+            RenderContext::new(self).finish_document(writer)
+        })?;
+        Ok(self.post_process(output))
+    }
+
+    // The headings of a document with the slugs their anchors get when rendered
+    pub fn table_of_contents(&self, content: &Value) -> Vec<TocEntry> {
+        match &self.options.heading_anchors {
+            Some(anchors) => plugins::table_of_contents(content, anchors),
+            None => plugins::table_of_contents(content, &HeadingAnchors::default()),
+        }
+    }
+
+    // Renders as much as possible, collecting every problem instead of stopping
+    // at the first one
+    pub fn render_with_report(&self, content: &Value) -> RenderReport {
+        let mut ctx = RenderContext::collecting(self);
+        let mut html = String::new();
+        let result = self.render_with_context(content, &mut ctx, &mut html);
+
+        let mut errors = ctx.take_errors();
+        if let Err(err) = result {
+            errors.push(err);
+            html.clear();
+        }
+        RenderReport { html, errors }
+    }
+
+    // For a strict CSP, see `StyledHtml`
+    pub fn render_with_stylesheet(&self, content: &Value) -> Result<StyledHtml, ProseMirrorError> {
+        Ok(StyledHtml::extract(&self.render(content)?))
+    }
+
+    // Renders like `render`, timing every plugin along the way
+    pub fn render_with_stats(
+        &self,
+        content: &Value,
+    ) -> Result<(String, RenderStats), ProseMirrorError> {
+        let start = Instant::now();
+        let mut ctx = RenderContext::measuring(self);
+        let mut html = String::new();
+        self.render_with_context(content, &mut ctx, &mut html)?;
+
+        let mut stats = ctx.take_stats();
+        stats.output_bytes = html.len();
+        stats.total_time = start.elapsed();
+        Ok((html, stats))
+    }
+
+    // The node's own attrs layered over any configured HTMLAttributes for its type,
+    // with null values resolved by the type's `AttrPolicy`
+    pub fn node_attrs(&self, node_type: &str, node: &Value) -> Option<Map<String, Value>> {
+        let node_attrs = node.get("attrs").and_then(|attrs| attrs.as_object());
+        let mut attrs = match self.options.html_attributes.get(node_type) {
+            Some(extra) => {
+                let mut attrs = extra.clone();
+                if let Some(node_attrs) = node_attrs {
+                    merge_attributes(&mut attrs, node_attrs);
+                }
+                attrs
+            }
+            None => node_attrs.cloned()?,
+        };
+
+        if let Some(mapping) = self.options.attr_mappings.get(node_type) {
+            mapping.apply(&mut attrs);
+        }
+        if let Some(text_align) = attrs.remove("textAlign") {
+            self.apply_text_align(&mut attrs, &text_align);
+        }
+
+        let filter = self.options.attr_filters.get(node_type);
+        attrs.retain(|key, _| {
+            !(self.options.strip_event_handlers && attrs::is_event_handler(key))
+                && filter.map(|filter| filter.allows(key)).unwrap_or(true)
+        });
+
+        let policy = self.options.attr_policy_for(node_type);
+        attrs.retain(|key, value| !value.is_null() || policy.renders_null(key));
+        for value in attrs.values_mut() {
+            if value.is_null() {
+                *value = Value::String(String::new());
+            }
+        }
+        Some(attrs)
+    }
+
+    // Like Tiptap, the default `left` alignment isn't rendered at all
+    fn apply_text_align(&self, attrs: &mut Map<String, Value>, text_align: &Value) {
+        let align = match text_align.as_str() {
+            Some(align @ ("center" | "right" | "justify")) => align,
+            _ => return,
+        };
+        let mut extra = Map::new();
+        if let StyleStrategy::Classes(classes) = &self.options.style_strategy {
+            extra.insert(
+                "class".to_string(),
+                Value::from(classes.text_align_class(align)),
+            );
+            merge_attributes(attrs, &extra);
+            return;
+        }
+        match &self.options.text_align {
+            TextAlignOutput::Style => {
+                extra.insert(
+                    "style".to_string(),
+                    Value::from(format!("text-align: {}", align)),
+                );
+            }
+            TextAlignOutput::StyleAndAlign => {
+                extra.insert(
+                    "style".to_string(),
+                    Value::from(format!("text-align: {}", align)),
+                );
+                extra.insert("align".to_string(), Value::from(align));
+            }
+            TextAlignOutput::Class(prefix) => {
+                extra.insert(
+                    "class".to_string(),
+                    Value::from(format!("{}{}", prefix, align)),
+                );
+            }
+        }
+        merge_attributes(attrs, &extra);
+    }
+
+    // Equivalent of Tiptap's `configure({ HTMLAttributes })` for a node or mark type
+    pub fn set_html_attributes(&mut self, node_type: &str, attrs: Map<String, Value>) {
+        self.options
+            .html_attributes
+            .insert(node_type.to_string(), attrs);
+    }
+
+    pub fn set_src_rewriter<F>(&mut self, rewriter: F)
+    where
+        F: Fn(&str) -> String + Send + Sync + 'static,
+    {
+        self.options.image_src_rewriter = Some(Box::new(rewriter));
+    }
+
+    pub fn set_link_rewriter<F>(&mut self, rewriter: F)
+    where
+        F: Fn(&str) -> String + Send + Sync + 'static,
+    {
+        self.options.link_rewriter = Some(Box::new(rewriter));
+    }
+
+    pub fn set_attr_mapping(&mut self, node_type: &str, mapping: AttrMapping) {
+        self.options
+            .attr_mappings
+            .insert(node_type.to_string(), mapping);
+    }
+
+    pub fn set_attr_filter(&mut self, node_type: &str, filter: AttrFilter) {
+        self.options
+            .attr_filters
+            .insert(node_type.to_string(), filter);
+    }
+
+    pub fn set_attr_policy(&mut self, node_type: &str, policy: AttrPolicy) {
+        self.options
+            .attr_policies
+            .insert(node_type.to_string(), policy);
+    }
+}
+
+// The span every node rendered for one document nests under
+#[cfg(feature = "tracing")]
+fn document_span(content: &Value) -> tracing::Span {
+    tracing::debug_span!(
+        "render_document",
+        root_type = content
+            .get("type")
+            .and_then(|t| t.as_str())
+            .unwrap_or_default()
+    )
+}
@@ -0,0 +1,247 @@
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::error::{Limit, ProseMirrorError};
+
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+fn children(node: &Value) -> &[Value] {
+    node.get("content")
+        .and_then(|content| content.as_array())
+        .map(|content| content.as_slice())
+        .unwrap_or(&[])
+}
+
+// The depth and node count limits, checked over the whole document before
+// rendering starts. Counts carry over between `check` calls, so a document
+// that arrives a block at a time is held to the same totals.
+pub struct TreeLimits {
+    max_depth: Option<usize>,
+    max_nodes: Option<usize>,
+    nodes: usize,
+}
+
+impl TreeLimits {
+    pub fn new(max_depth: Option<usize>, max_nodes: Option<usize>) -> Self {
+        Self {
+            max_depth,
+            max_nodes,
+            nodes: 0,
+        }
+    }
+
+    // Counts a single node at `depth`, without looking at its children
+    pub fn enter(&mut self, path: &str, depth: usize) -> Result<(), ProseMirrorError> {
+        self.nodes += 1;
+        let exceeded = |limit, max| ProseMirrorError::LimitExceeded {
+            path: path.to_string(),
+            limit,
+            max,
+        };
+        match (self.max_depth, self.max_nodes) {
+            (Some(max_depth), _) if depth > max_depth => Err(exceeded(Limit::Depth, max_depth)),
+            (_, Some(max_nodes)) if self.nodes > max_nodes => {
+                Err(exceeded(Limit::Nodes, max_nodes))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // Walks the tree with an explicit stack, so checking an adversarial document
+    // can't overflow the stack the way the recursive render would
+    pub fn check(
+        &mut self,
+        root: &Value,
+        path: &str,
+        depth: usize,
+    ) -> Result<(), ProseMirrorError> {
+        self.enter(path, depth)?;
+        let mut path: Vec<String> = vec![path.to_string()];
+        let mut stack = vec![children(root).iter().enumerate()];
+
+        while let Some(siblings) = stack.last_mut() {
+            match siblings.next() {
+                Some((index, child)) => {
+                    path.push(format!("/content/{}", index));
+                    self.enter(&path.concat(), depth + stack.len())?;
+                    stack.push(children(child).iter().enumerate());
+                }
+                None => {
+                    stack.pop();
+                    path.pop();
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn check_depth(root: &Value, max_depth: usize) -> Result<(), ProseMirrorError> {
+    TreeLimits::new(Some(max_depth), None).check(root, "", 0)
+}
+
+// Fails once more than `max_bytes` have gone through it, so an oversized
+// document stops rendering instead of growing the output without bound
+pub struct LimitedWriter<'w> {
+    inner: &'w mut dyn fmt::Write,
+    written: usize,
+    max_bytes: usize,
+    exceeded: bool,
+}
+
+impl<'w> LimitedWriter<'w> {
+    pub fn new(inner: &'w mut dyn fmt::Write, max_bytes: usize) -> Self {
+        Self {
+            inner,
+            written: 0,
+            max_bytes,
+            exceeded: false,
+        }
+    }
+
+    // The write error raised by the limit, bubbled up with the path of the
+    // node that crossed it, becomes `OutputLimitExceeded`
+    pub fn into_result(self, result: Result<(), ProseMirrorError>) -> Result<(), ProseMirrorError> {
+        if !self.exceeded {
+            return result;
+        }
+        let path = match result {
+            Err(ProseMirrorError::WriteFailed { path, .. }) => path,
+            _ => String::new(),
+        };
+        Err(ProseMirrorError::OutputLimitExceeded {
+            path,
+            max_bytes: self.max_bytes,
+        })
+    }
+}
+
+impl fmt::Write for LimitedWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.exceeded || self.written + s.len() > self.max_bytes {
+            self.exceeded = true;
+            return Err(fmt::Error);
+        }
+        self.written += s.len();
+        self.inner.write_str(s)
+    }
+}
+
+// Runs `render` against `writer`, capped at `max_bytes` when there's a limit
+pub fn write_limited<F>(
+    writer: &mut dyn fmt::Write,
+    max_bytes: Option<usize>,
+    render: F,
+) -> Result<(), ProseMirrorError>
+where
+    F: FnOnce(&mut dyn fmt::Write) -> Result<(), ProseMirrorError>,
+{
+    match max_bytes {
+        Some(max_bytes) => {
+            let mut limited = LimitedWriter::new(writer, max_bytes);
+            let result = render(&mut limited);
+            limited.into_result(result)
+        }
+        None => render(writer),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ProseMirror;
+    use serde_json::json;
+
+    // Built by hand, `json!` would re-serialize the whole subtree on every level
+    fn nested(depth: usize) -> Value {
+        let mut node = json!({"type":"paragraph"});
+        for _ in 0..depth {
+            let mut parent = json!({"type":"blockquote","content":[]});
+            parent["content"].as_array_mut().unwrap().push(node);
+            node = parent;
+        }
+        node
+    }
+
+    #[test]
+    fn it_allows_documents_within_the_limit() {
+        assert!(check_depth(&nested(3), 3).is_ok());
+        assert!(check_depth(&json!({"type":"doc"}), 0).is_ok());
+    }
+
+    #[test]
+    fn it_rejects_documents_past_the_limit() {
+        let content = json!({"type":"doc","content":[{"type":"paragraph"}, nested(3)]});
+
+        assert_eq!(
+            check_depth(&content, 3).unwrap_err(),
+            ProseMirrorError::LimitExceeded {
+                path: "/content/1/content/0/content/0/content/0".to_owned(),
+                limit: Limit::Depth,
+                max: 3
+            }
+        );
+    }
+
+    #[test]
+    fn it_rejects_documents_with_too_many_nodes() {
+        let content = json!({"type":"doc","content":[
+            {"type":"paragraph","content":[{"type":"text","text":"Go"}]},
+            {"type":"paragraph","content":[{"type":"text","text":"Leafs"}]}
+        ]});
+        let prose_mirror = ProseMirror::builder()
+            .with_defaults()
+            .max_nodes(Some(4))
+            .build();
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap_err(),
+            ProseMirrorError::LimitExceeded {
+                path: "/content/1/content/0".to_owned(),
+                limit: Limit::Nodes,
+                max: 4
+            }
+        );
+        assert!(TreeLimits::new(None, Some(5))
+            .check(&content, "", 0)
+            .is_ok());
+    }
+
+    #[test]
+    fn it_stops_rendering_past_the_output_limit() {
+        let paragraph =
+            json!({"type":"paragraph","content":[{"type":"text","text":"Go Leafs Go"}]});
+        let content = json!({"type":"doc","content":[paragraph, paragraph, paragraph]});
+        let prose_mirror = ProseMirror::builder()
+            .with_defaults()
+            .max_output_bytes(Some(40))
+            .build();
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap_err(),
+            ProseMirrorError::OutputLimitExceeded {
+                path: "/content/1".to_owned(),
+                max_bytes: 40
+            }
+        );
+        assert!(prose_mirror
+            .render(&json!({"type":"doc","content":[paragraph]}))
+            .is_ok());
+    }
+
+    #[test]
+    fn it_guards_rendering_by_default() {
+        let prose_mirror = ProseMirror::with_default_plugins();
+
+        assert!(prose_mirror.render(&nested(DEFAULT_MAX_DEPTH)).is_ok());
+        assert!(matches!(
+            prose_mirror.render(&nested(1_000)),
+            Err(ProseMirrorError::LimitExceeded {
+                limit: Limit::Depth,
+                max: DEFAULT_MAX_DEPTH,
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,475 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::error::ProseMirrorError;
+use crate::limits::{self, DEFAULT_MAX_DEPTH};
+
+// Nodes that are part of a paragraph's text rather than blocks of their own
+const INLINE_TYPES: [&str; 3] = ["hardBreak", "image", "text"];
+
+// Turns one node into CommonMark, the Markdown counterpart of `Plugin`
+pub trait MarkdownPlugin: Send + Sync {
+    fn serialize(&self, node: &Value, md: &MarkdownSerializer) -> Result<String, ProseMirrorError>;
+}
+
+impl<F> MarkdownPlugin for F
+where
+    F: Fn(&Value, &MarkdownSerializer) -> Result<String, ProseMirrorError> + Send + Sync,
+{
+    fn serialize(&self, node: &Value, md: &MarkdownSerializer) -> Result<String, ProseMirrorError> {
+        self(node, md)
+    }
+}
+
+// Wraps the Markdown of a run of text in a mark, the counterpart of `MarkPlugin`
+pub trait MarkdownMarkPlugin: Send + Sync {
+    fn serialize(&self, output: String, mark: &Value) -> String;
+}
+
+impl<F> MarkdownMarkPlugin for F
+where
+    F: Fn(String, &Value) -> String + Send + Sync,
+{
+    fn serialize(&self, output: String, mark: &Value) -> String {
+        self(output, mark)
+    }
+}
+
+#[derive(Default)]
+pub struct MarkdownSerializer {
+    plugins: HashMap<String, Box<dyn MarkdownPlugin>>,
+    marks: HashMap<String, Box<dyn MarkdownMarkPlugin>>,
+}
+
+impl MarkdownSerializer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // StarterKit plus tables, task lists and images
+    pub fn with_default_plugins() -> Self {
+        let mut md = Self::new();
+        md.add_plugin("doc", Box::new(serialize_doc));
+        md.add_plugin("paragraph", Box::new(serialize_paragraph));
+        md.add_plugin("heading", Box::new(serialize_heading));
+        md.add_plugin("blockquote", Box::new(serialize_blockquote));
+        md.add_plugin("codeBlock", Box::new(serialize_code_block));
+        md.add_plugin(
+            "horizontalRule",
+            Box::new(|_: &Value, _: &Self| Ok("---".to_string())),
+        );
+        md.add_plugin(
+            "hardBreak",
+            Box::new(|_: &Value, _: &Self| Ok("\\\n".to_string())),
+        );
+        md.add_plugin("image", Box::new(serialize_image));
+        for list in ["bulletList", "orderedList", "taskList"] {
+            md.add_plugin(list, Box::new(serialize_list));
+        }
+        md.add_plugin("table", Box::new(serialize_table));
+
+        md.add_mark_plugin("bold", Box::new(|output, _: &Value| delimit(output, "**")));
+        md.add_mark_plugin("italic", Box::new(|output, _: &Value| delimit(output, "*")));
+        md.add_mark_plugin(
+            "strike",
+            Box::new(|output, _: &Value| delimit(output, "~~")),
+        );
+        md.add_mark_plugin("code", Box::new(serialize_code));
+        md.add_mark_plugin("link", Box::new(serialize_link));
+        md
+    }
+
+    pub fn add_plugin(&mut self, node_type: &str, plugin: Box<dyn MarkdownPlugin>) {
+        self.plugins.insert(node_type.to_string(), plugin);
+    }
+
+    pub fn add_mark_plugin(&mut self, mark_type: &str, plugin: Box<dyn MarkdownMarkPlugin>) {
+        self.marks.insert(mark_type.to_string(), plugin);
+    }
+
+    pub fn serialize(&self, content: &Value) -> Result<String, ProseMirrorError> {
+        limits::check_depth(content, DEFAULT_MAX_DEPTH)?;
+        Ok(self.serialize_node(content)?.trim_end().to_string())
+    }
+
+    // Nodes without a plugin are unwrapped, so only their children show up
+    pub fn serialize_node(&self, node: &Value) -> Result<String, ProseMirrorError> {
+        let plugin = node
+            .get("type")
+            .and_then(|t| t.as_str())
+            .and_then(|t| self.plugins.get(t));
+        match plugin {
+            Some(plugin) => plugin.serialize(node, self),
+            None if is_inline(node) => self.serialize_inline(children(node)),
+            None => self.serialize_blocks(node, "\n\n"),
+        }
+    }
+
+    // Runs of inline children are joined into one block each
+    pub fn serialize_blocks(
+        &self,
+        node: &Value,
+        separator: &str,
+    ) -> Result<String, ProseMirrorError> {
+        let nodes = children(node);
+        let mut blocks = vec![];
+        let mut run_start = None;
+        for (index, node) in nodes.iter().enumerate() {
+            if is_inline(node) {
+                run_start.get_or_insert(index);
+                continue;
+            }
+            if let Some(start) = run_start.take() {
+                blocks.push(self.serialize_inline(&nodes[start..index])?);
+            }
+            blocks.push(self.serialize_node(node)?);
+        }
+        if let Some(start) = run_start {
+            blocks.push(self.serialize_inline(&nodes[start..])?);
+        }
+        blocks.retain(|block| !block.is_empty());
+        Ok(blocks.join(separator))
+    }
+
+    pub fn serialize_inline(&self, nodes: &[Value]) -> Result<String, ProseMirrorError> {
+        let mut output = String::new();
+        let mut index = 0;
+        while index < nodes.len() {
+            let node = &nodes[index];
+            if node.get("type").and_then(|t| t.as_str()) != Some("text") {
+                output.push_str(&self.serialize_node(node)?);
+                index += 1;
+                continue;
+            }
+
+            // Neighbouring text with the same marks is merged, so `**a****b**` can't happen
+            let marks = node.get("marks");
+            let mut text = String::new();
+            while let Some(node) = nodes.get(index).filter(|node| {
+                node.get("type").and_then(|t| t.as_str()) == Some("text")
+                    && node.get("marks") == marks
+            }) {
+                text.push_str(
+                    node.get("text")
+                        .and_then(|t| t.as_str())
+                        .unwrap_or_default(),
+                );
+                index += 1;
+            }
+            output.push_str(&self.serialize_marks(text, marks));
+        }
+        Ok(output)
+    }
+
+    // The first mark ends up outermost, same as in the HTML renderer
+    fn serialize_marks(&self, text: String, marks: Option<&Value>) -> String {
+        let marks = marks
+            .and_then(|marks| marks.as_array())
+            .map(|marks| marks.as_slice())
+            .unwrap_or_default();
+        let is_code = marks
+            .iter()
+            .any(|mark| mark.get("type").and_then(|t| t.as_str()) == Some("code"));
+        let mut output = if is_code { text } else { escape(&text) };
+        for mark in marks.iter().rev() {
+            let plugin = mark
+                .get("type")
+                .and_then(|t| t.as_str())
+                .and_then(|t| self.marks.get(t));
+            if let Some(plugin) = plugin {
+                output = plugin.serialize(output, mark);
+            }
+        }
+        output
+    }
+}
+
+fn children(node: &Value) -> &[Value] {
+    node.get("content")
+        .and_then(|content| content.as_array())
+        .map(|content| content.as_slice())
+        .unwrap_or_default()
+}
+
+fn attr<'v>(node: &'v Value, key: &str) -> Option<&'v Value> {
+    node.get("attrs").and_then(|attrs| attrs.get(key))
+}
+
+fn is_inline(node: &Value) -> bool {
+    node.get("type")
+        .and_then(|t| t.as_str())
+        .is_some_and(|t| INLINE_TYPES.contains(&t))
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(
+            c,
+            '\\' | '*' | '_' | '`' | '[' | ']' | '<' | '>' | '#' | '~' | '|'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+// CommonMark doesn't allow `** Go**`, so surrounding whitespace moves outside
+fn delimit(output: String, delimiter: &str) -> String {
+    let trimmed = output.trim();
+    if trimmed.is_empty() {
+        return output;
+    }
+    let start = output.len() - output.trim_start().len();
+    let end = output.trim_end().len();
+    format!(
+        "{}{}{}{}{}",
+        &output[..start],
+        delimiter,
+        trimmed,
+        delimiter,
+        &output[end..]
+    )
+}
+
+fn prefix_lines(text: &str, first: &str, rest: &str) -> String {
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| {
+            let prefix = if index == 0 { first } else { rest };
+            format!("{}{}", prefix, line).trim_end().to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn serialize_doc(node: &Value, md: &MarkdownSerializer) -> Result<String, ProseMirrorError> {
+    md.serialize_blocks(node, "\n\n")
+}
+
+fn serialize_paragraph(node: &Value, md: &MarkdownSerializer) -> Result<String, ProseMirrorError> {
+    md.serialize_inline(children(node))
+}
+
+fn serialize_heading(node: &Value, md: &MarkdownSerializer) -> Result<String, ProseMirrorError> {
+    let level = attr(node, "level")
+        .and_then(|level| level.as_u64())
+        .unwrap_or(1)
+        .clamp(1, 6);
+    Ok(format!(
+        "{} {}",
+        "#".repeat(level as usize),
+        md.serialize_inline(children(node))?
+    ))
+}
+
+fn serialize_blockquote(node: &Value, md: &MarkdownSerializer) -> Result<String, ProseMirrorError> {
+    Ok(prefix_lines(
+        &md.serialize_blocks(node, "\n\n")?,
+        "> ",
+        "> ",
+    ))
+}
+
+fn serialize_code_block(node: &Value, _: &MarkdownSerializer) -> Result<String, ProseMirrorError> {
+    let code: String = children(node)
+        .iter()
+        .filter_map(|text| text.get("text").and_then(|text| text.as_str()))
+        .collect();
+    // The fence has to be longer than any run of backticks in the code
+    let longest_run = code
+        .split(|c| c != '`')
+        .map(|run| run.len())
+        .max()
+        .unwrap_or_default();
+    let fence = "`".repeat((longest_run + 1).max(3));
+    let language = attr(node, "language")
+        .and_then(|language| language.as_str())
+        .unwrap_or_default();
+    Ok(format!("{}{}\n{}\n{}", fence, language, code, fence))
+}
+
+fn serialize_image(node: &Value, _: &MarkdownSerializer) -> Result<String, ProseMirrorError> {
+    let text = |key| {
+        attr(node, key)
+            .and_then(|value| value.as_str())
+            .unwrap_or_default()
+    };
+    let title = match text("title") {
+        "" => String::new(),
+        title => format!(" \"{}\"", title.replace('"', "\\\"")),
+    };
+    Ok(format!(
+        "![{}]({}{})",
+        escape(text("alt")),
+        text("src"),
+        title
+    ))
+}
+
+// Items are tight, their blocks only need a blank line between paragraphs
+fn serialize_list(node: &Value, md: &MarkdownSerializer) -> Result<String, ProseMirrorError> {
+    let node_type = node.get("type").and_then(|t| t.as_str());
+    let start = attr(node, "start")
+        .and_then(|start| start.as_u64())
+        .unwrap_or(1);
+    let mut items = vec![];
+    for (index, item) in children(node).iter().enumerate() {
+        let marker = match node_type {
+            Some("orderedList") => format!("{}. ", start + index as u64),
+            Some("taskList") => {
+                let checked = attr(item, "checked").and_then(|checked| checked.as_bool());
+                if checked == Some(true) {
+                    "- [x] "
+                } else {
+                    "- [ ] "
+                }
+                .to_string()
+            }
+            _ => "- ".to_string(),
+        };
+
+        let mut text = String::new();
+        let mut previous_is_list = true;
+        for block in children(item) {
+            let is_list = block
+                .get("type")
+                .and_then(|t| t.as_str())
+                .is_some_and(|t| t.ends_with("List"));
+            if !text.is_empty() {
+                text.push_str(if is_list || previous_is_list {
+                    "\n"
+                } else {
+                    "\n\n"
+                });
+            }
+            text.push_str(&md.serialize_node(block)?);
+            previous_is_list = is_list;
+        }
+        let indent = " ".repeat(marker.len().min(4));
+        items.push(prefix_lines(&text, &marker, &indent));
+    }
+    Ok(items.join("\n"))
+}
+
+fn serialize_table(node: &Value, md: &MarkdownSerializer) -> Result<String, ProseMirrorError> {
+    let mut rows = vec![];
+    for row in children(node) {
+        let mut cells = vec![];
+        for cell in children(row) {
+            let text = md.serialize_blocks(cell, " ")?;
+            cells.push(text.replace('\n', " "));
+        }
+        rows.push(format!("| {} |", cells.join(" | ")));
+        // Pipe tables always need a header, the first row is it
+        if rows.len() == 1 {
+            let columns = children(row).len().max(1);
+            rows.push(format!("|{}", " --- |".repeat(columns)));
+        }
+    }
+    Ok(rows.join("\n"))
+}
+
+fn serialize_code(output: String, _: &Value) -> String {
+    let fence = if output.contains('`') { "``" } else { "`" };
+    let padding = if output.starts_with('`') || output.ends_with('`') {
+        " "
+    } else {
+        ""
+    };
+    format!("{}{}{}{}{}", fence, padding, output, padding, fence)
+}
+
+fn serialize_link(output: String, mark: &Value) -> String {
+    let href = attr(mark, "href")
+        .and_then(|href| href.as_str())
+        .unwrap_or_default();
+    format!(
+        "[{}]({})",
+        output,
+        href.replace(' ', "%20").replace(')', "%29")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_serializes_commonmark() {
+        let content = json!({"type":"doc","content":[
+            {"type":"heading","attrs":{"level":2},"content":[{"type":"text","text":"Leafs #1"}]},
+            {"type":"paragraph","content":[
+                {"type":"text","text":"Go ","marks":[{"type":"bold"}]},
+                {"type":"text","text":"Leafs","marks":[{"type":"bold"},{"type":"italic"}]},
+                {"type":"text","text":" at "},
+                {"type":"text","text":"nhl.com","marks":[{"type":"link","attrs":{"href":"https://nhl.com"}}]},
+                {"type":"hardBreak"},
+                {"type":"text","text":"a*b","marks":[{"type":"code"}]}
+            ]},
+            {"type":"bulletList","content":[
+                {"type":"listItem","content":[
+                    {"type":"paragraph","content":[{"type":"text","text":"Matthews"}]},
+                    {"type":"orderedList","attrs":{"start":34},"content":[
+                        {"type":"listItem","content":[{"type":"paragraph","content":[{"type":"text","text":"Goals"}]}]}
+                    ]}
+                ]}
+            ]},
+            {"type":"codeBlock","attrs":{"language":"rust"},"content":[{"type":"text","text":"let x = \"```\";"}]},
+            {"type":"blockquote","content":[{"type":"paragraph","content":[{"type":"text","text":"Quote"}]}]},
+            {"type":"horizontalRule"}
+        ]});
+
+        assert_eq!(
+            MarkdownSerializer::with_default_plugins().serialize(&content).unwrap(),
+            "## Leafs \\#1\n\n**Go** ***Leafs*** at [nhl.com](https://nhl.com)\\\n`a*b`\n\n- Matthews\n  34. Goals\n\n````rust\nlet x = \"```\";\n````\n\n> Quote\n\n---"
+        );
+    }
+
+    #[test]
+    fn it_serializes_pipe_tables_and_task_lists() {
+        let cell = |tag: &str, text: &str| json!({"type":tag,"content":[{"type":"paragraph","content":[{"type":"text","text":text}]}]});
+        let content = json!({"type":"doc","content":[
+            {"type":"table","content":[
+                {"type":"tableRow","content":[cell("tableHeader","Team"),cell("tableHeader","W|L")]},
+                {"type":"tableRow","content":[cell("tableCell","Leafs"),cell("tableCell","52")]}
+            ]},
+            {"type":"taskList","content":[
+                {"type":"taskItem","attrs":{"checked":true},"content":[{"type":"paragraph","content":[{"type":"text","text":"Win"}]}]},
+                {"type":"taskItem","attrs":{"checked":false},"content":[{"type":"paragraph","content":[{"type":"text","text":"Cup"}]}]}
+            ]}
+        ]});
+
+        assert_eq!(
+            MarkdownSerializer::with_default_plugins()
+                .serialize(&content)
+                .unwrap(),
+            "| Team | W\\|L |\n| --- | --- |\n| Leafs | 52 |\n\n- [x] Win\n- [ ] Cup"
+        );
+    }
+
+    #[test]
+    fn it_uses_custom_plugins() {
+        let mut md = MarkdownSerializer::with_default_plugins();
+        md.add_plugin(
+            "mention",
+            Box::new(|node: &Value, _: &MarkdownSerializer| {
+                Ok(format!(
+                    "@{}",
+                    attr(node, "id")
+                        .and_then(|id| id.as_str())
+                        .unwrap_or_default()
+                ))
+            }),
+        );
+        let content = json!({"type":"paragraph","content":[
+            {"type":"text","text":"Hi "},
+            {"type":"mention","attrs":{"id":"matthews"}}
+        ]});
+
+        assert_eq!(md.serialize(&content).unwrap(), "Hi @matthews");
+    }
+}
@@ -0,0 +1,387 @@
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag};
+use serde_json::{json, Map, Value};
+
+use crate::limits::DEFAULT_MAX_DEPTH;
+
+// Node types whose content has to be blocks, tight list items give them bare text
+const BLOCK_CONTAINERS: [&str; 5] = ["blockquote", "doc", "listItem", "tableCell", "taskItem"];
+
+// Blocks nested deeper than this are flattened into their parent, like `parse_html`
+// does, so the document stays within the renderer's depth limit and can be
+// dropped without overflowing the stack
+const MAX_FRAMES: usize = DEFAULT_MAX_DEPTH - 1;
+
+struct Frame {
+    node_type: &'static str,
+    attrs: Map<String, Value>,
+    content: Vec<Value>,
+    // Opened for bare text rather than by the parser
+    implicit: bool,
+}
+
+impl Frame {
+    fn new(node_type: &'static str, attrs: Map<String, Value>) -> Self {
+        Self {
+            node_type,
+            attrs,
+            content: vec![],
+            implicit: false,
+        }
+    }
+
+    fn into_node(self) -> Value {
+        let mut node = Map::new();
+        node.insert("type".to_string(), Value::from(self.node_type));
+        if !self.attrs.is_empty() {
+            node.insert("attrs".to_string(), Value::Object(self.attrs));
+        }
+        if !self.content.is_empty() || self.node_type == "doc" {
+            node.insert("content".to_string(), Value::Array(self.content));
+        }
+        Value::Object(node)
+    }
+}
+
+// What each `Event::Start` opened, so the matching `Event::End` can undo it
+enum Opened {
+    Node,
+    Mark,
+    // Alt text is collected from the events inside the image
+    Image(Map<String, Value>, String),
+    // Table cells hold inline content in CommonMark, but a paragraph in Tiptap
+    Cell,
+    // Too deep to open, the content went into the parent instead
+    Flattened,
+    Nothing,
+}
+
+struct Builder {
+    frames: Vec<Frame>,
+    opened: Vec<Opened>,
+    marks: Vec<Value>,
+    in_table_head: bool,
+}
+
+impl Builder {
+    fn new() -> Self {
+        Self {
+            frames: vec![Frame::new("doc", Map::new())],
+            opened: vec![],
+            marks: vec![],
+            in_table_head: false,
+        }
+    }
+
+    fn top(&mut self) -> &mut Frame {
+        self.frames
+            .last_mut()
+            .expect("the doc frame is never closed")
+    }
+
+    fn close_frame(&mut self) {
+        if self.frames.len() == 1 {
+            return;
+        }
+        if let Some(frame) = self.frames.pop() {
+            let node = frame.into_node();
+            self.top().content.push(node);
+        }
+    }
+
+    fn close_implicit(&mut self) {
+        if self.top().implicit {
+            self.close_frame();
+        }
+    }
+
+    fn open(&mut self, node_type: &'static str, attrs: Map<String, Value>) {
+        self.close_implicit();
+        if self.frames.len() >= MAX_FRAMES {
+            self.opened.push(Opened::Flattened);
+            return;
+        }
+        self.frames.push(Frame::new(node_type, attrs));
+        self.opened.push(Opened::Node);
+    }
+
+    fn push_inline(&mut self, node: Value) {
+        if let Some(Opened::Image(_, alt)) = self.opened.last_mut() {
+            alt.push_str(
+                node.get("text")
+                    .and_then(|t| t.as_str())
+                    .unwrap_or_default(),
+            );
+            return;
+        }
+        if BLOCK_CONTAINERS.contains(&self.top().node_type) {
+            let mut paragraph = Frame::new("paragraph", Map::new());
+            paragraph.implicit = true;
+            self.frames.push(paragraph);
+        }
+
+        let content = &mut self.top().content;
+        if let (Some(last), Some(text)) = (content.last_mut(), node.get("text")) {
+            if last.get("text").is_some() && last.get("marks") == node.get("marks") {
+                let mut merged = last["text"].as_str().unwrap_or_default().to_string();
+                merged.push_str(text.as_str().unwrap_or_default());
+                last["text"] = Value::from(merged);
+                return;
+            }
+        }
+        content.push(node);
+    }
+
+    fn push_text(&mut self, text: &str, extra_mark: Option<Value>) {
+        if self.top().node_type == "codeBlock" {
+            self.top().content.push(json!({"type":"text","text":text}));
+            return;
+        }
+        let mut node = json!({"type":"text","text":text});
+        let marks: Vec<Value> = self.marks.iter().cloned().chain(extra_mark).collect();
+        if !marks.is_empty() {
+            node["marks"] = Value::Array(marks);
+        }
+        self.push_inline(node);
+    }
+
+    fn start(&mut self, tag: Tag) {
+        let mut attrs = Map::new();
+        match tag {
+            Tag::Paragraph => self.open("paragraph", attrs),
+            Tag::Heading { level, .. } => {
+                attrs.insert("level".to_string(), Value::from(level as u64));
+                self.open("heading", attrs);
+            }
+            Tag::BlockQuote(_) => self.open("blockquote", attrs),
+            Tag::CodeBlock(kind) => {
+                if let CodeBlockKind::Fenced(info) = kind {
+                    if let Some(language) = info.split_whitespace().next() {
+                        attrs.insert("language".to_string(), Value::from(language));
+                    }
+                }
+                self.open("codeBlock", attrs);
+            }
+            Tag::List(Some(start)) => {
+                attrs.insert("start".to_string(), Value::from(start));
+                self.open("orderedList", attrs);
+            }
+            Tag::List(None) => self.open("bulletList", attrs),
+            Tag::Item => self.open("listItem", attrs),
+            Tag::Table(_) => self.open("table", attrs),
+            Tag::TableHead => {
+                self.in_table_head = true;
+                self.open("tableRow", attrs);
+            }
+            Tag::TableRow => self.open("tableRow", attrs),
+            Tag::TableCell if self.frames.len() >= MAX_FRAMES => {
+                self.opened.push(Opened::Flattened)
+            }
+            Tag::TableCell => {
+                let cell = if self.in_table_head {
+                    "tableHeader"
+                } else {
+                    "tableCell"
+                };
+                self.frames.push(Frame::new(cell, attrs));
+                self.frames.push(Frame::new("paragraph", Map::new()));
+                self.opened.push(Opened::Cell);
+            }
+            Tag::Emphasis => self.open_mark(json!({"type":"italic"})),
+            Tag::Strong => self.open_mark(json!({"type":"bold"})),
+            Tag::Strikethrough => self.open_mark(json!({"type":"strike"})),
+            Tag::Superscript => self.open_mark(json!({"type":"superscript"})),
+            Tag::Subscript => self.open_mark(json!({"type":"subscript"})),
+            Tag::Link {
+                dest_url, title, ..
+            } => {
+                attrs.insert("href".to_string(), Value::from(dest_url.as_ref()));
+                if !title.is_empty() {
+                    attrs.insert("title".to_string(), Value::from(title.as_ref()));
+                }
+                self.open_mark(json!({"type":"link","attrs":attrs}));
+            }
+            Tag::Image {
+                dest_url, title, ..
+            } => {
+                attrs.insert("src".to_string(), Value::from(dest_url.as_ref()));
+                if !title.is_empty() {
+                    attrs.insert("title".to_string(), Value::from(title.as_ref()));
+                }
+                self.opened.push(Opened::Image(attrs, String::new()));
+            }
+            // Raw HTML, footnotes and front matter have no Tiptap counterpart
+            _ => self.opened.push(Opened::Nothing),
+        }
+    }
+
+    fn open_mark(&mut self, mark: Value) {
+        self.marks.push(mark);
+        self.opened.push(Opened::Mark);
+    }
+
+    fn end(&mut self) {
+        match self.opened.pop() {
+            Some(Opened::Node) => {
+                self.close_implicit();
+                if self.top().node_type == "tableRow" {
+                    self.in_table_head = false;
+                }
+                self.close_frame();
+            }
+            Some(Opened::Mark) => {
+                self.marks.pop();
+            }
+            Some(Opened::Image(mut attrs, alt)) => {
+                if !alt.is_empty() {
+                    attrs.insert("alt".to_string(), Value::from(alt));
+                }
+                self.push_inline(json!({"type":"image","attrs":attrs}));
+            }
+            Some(Opened::Cell) => {
+                self.close_frame();
+                self.close_frame();
+            }
+            Some(Opened::Flattened) => self.close_implicit(),
+            Some(Opened::Nothing) | None => {}
+        }
+    }
+
+    fn check_task(&mut self, checked: bool) {
+        let Some(index) = self
+            .frames
+            .iter()
+            .rposition(|frame| frame.node_type == "listItem")
+        else {
+            return;
+        };
+        let item = &mut self.frames[index];
+        item.node_type = "taskItem";
+        item.attrs
+            .insert("checked".to_string(), Value::from(checked));
+        if let Some(list) = index.checked_sub(1).map(|index| &mut self.frames[index]) {
+            list.node_type = "taskList";
+            list.attrs.remove("start");
+        }
+    }
+
+    fn process(&mut self, event: Event) {
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(_) => self.end(),
+            Event::Text(text) => self.push_text(&text, None),
+            Event::Code(code) => self.push_text(&code, Some(json!({"type":"code"}))),
+            Event::InlineMath(math) | Event::DisplayMath(math) => self.push_text(&math, None),
+            Event::SoftBreak => self.push_text(" ", None),
+            Event::HardBreak => self.push_inline(json!({"type":"hardBreak"})),
+            Event::Rule => {
+                self.open("horizontalRule", Map::new());
+                if let Some(Opened::Flattened) = self.opened.last() {
+                    self.top().content.push(json!({"type":"horizontalRule"}));
+                }
+                self.end();
+            }
+            Event::TaskListMarker(checked) => self.check_task(checked),
+            _ => {}
+        }
+    }
+
+    fn finish(mut self) -> Value {
+        while self.frames.len() > 1 {
+            self.close_frame();
+        }
+        self.frames.pop().map(Frame::into_node).unwrap_or_default()
+    }
+}
+
+// Builds a Tiptap document from CommonMark plus tables, strikethrough and task
+// lists. Raw HTML in the Markdown is dropped rather than trusted.
+pub fn from_markdown(markdown: &str) -> Value {
+    let options =
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let mut builder = Builder::new();
+    for event in Parser::new_ext(markdown, options) {
+        builder.process(event);
+    }
+    builder.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::{register_table_plugins, register_task_list_plugins};
+    use crate::{MarkdownSerializer, ProseMirror};
+
+    #[test]
+    fn it_builds_tiptap_documents() {
+        let markdown = "# Leafs\n\nGo **Leafs** [go](https://nhl.com \"NHL\") `now`\\\nagain <b>x</b>\n\n- [x] Win\n- [ ] Cup\n\n```rust\nlet x = 1;\n```\n\n![Rink](/rink.png)\n";
+
+        assert_eq!(
+            from_markdown(markdown),
+            json!({"type":"doc","content":[
+                {"type":"heading","attrs":{"level":1},"content":[{"type":"text","text":"Leafs"}]},
+                {"type":"paragraph","content":[
+                    {"type":"text","text":"Go "},
+                    {"type":"text","text":"Leafs","marks":[{"type":"bold"}]},
+                    {"type":"text","text":" "},
+                    {"type":"text","text":"go","marks":[{"type":"link","attrs":{"href":"https://nhl.com","title":"NHL"}}]},
+                    {"type":"text","text":" "},
+                    {"type":"text","text":"now","marks":[{"type":"code"}]},
+                    {"type":"hardBreak"},
+                    {"type":"text","text":"again x"}
+                ]},
+                {"type":"taskList","content":[
+                    {"type":"taskItem","attrs":{"checked":true},"content":[{"type":"paragraph","content":[{"type":"text","text":"Win"}]}]},
+                    {"type":"taskItem","attrs":{"checked":false},"content":[{"type":"paragraph","content":[{"type":"text","text":"Cup"}]}]}
+                ]},
+                {"type":"codeBlock","attrs":{"language":"rust"},"content":[{"type":"text","text":"let x = 1;\n"}]},
+                {"type":"paragraph","content":[{"type":"image","attrs":{"alt":"Rink","src":"/rink.png"}}]}
+            ]})
+        );
+    }
+
+    #[test]
+    fn it_round_trips_through_both_renderers() {
+        let markdown = "## Standings\n\n| Team | Points |\n| --- | --- |\n| Leafs | 111 |\n\n1. Matthews\n2. Marner\n\n> Go *Leafs* go";
+        let content = from_markdown(markdown);
+
+        assert_eq!(
+            MarkdownSerializer::with_default_plugins()
+                .serialize(&content)
+                .unwrap(),
+            markdown
+        );
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        register_table_plugins(&mut prose_mirror);
+        register_task_list_plugins(&mut prose_mirror);
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div><h2>Standings</h2><table><tbody><tr><th><p>Team</p></th><th><p>Points</p></th></tr><tr><td><p>Leafs</p></td><td><p>111</p></td></tr></tbody></table><ol><li><p>Matthews</p></li><li><p>Marner</p></li></ol><blockquote><p>Go <em>Leafs</em> go</p></blockquote></div>"
+        );
+    }
+
+    #[test]
+    fn it_flattens_blocks_past_the_depth_limit() {
+        let content = from_markdown(&format!("{} x\n\n---", ">".repeat(100_000)));
+
+        let mut node = &content;
+        let mut depth = 0;
+        while let Some(child) = node.get("content").and_then(|c| c.get(0)) {
+            if child["type"] != "blockquote" {
+                break;
+            }
+            node = child;
+            depth += 1;
+        }
+        assert_eq!(depth, MAX_FRAMES - 1);
+        assert_eq!(
+            node["content"],
+            json!([{"type":"paragraph","content":[{"type":"text","text":"x"}]}])
+        );
+        assert!(ProseMirror::with_default_plugins().render(&content).is_ok());
+
+        let content = from_markdown(&format!("{}---", "> ".repeat(200)));
+        assert!(content
+            .to_string()
+            .contains("{\"type\":\"horizontalRule\"}"));
+    }
+}
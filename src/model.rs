@@ -0,0 +1,221 @@
+use std::ops::{Deref, DerefMut};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+macro_rules! define_type_enum {
+    ($enum_name:ident { $($variant:ident => $name:expr,)* }) => {
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+        #[serde(from = "String", into = "String")]
+        pub enum $enum_name {
+            $($variant,)*
+            // Anything without a dedicated variant, e.g. custom extensions
+            Other(String),
+        }
+
+        impl $enum_name {
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($enum_name::$variant => $name,)*
+                    $enum_name::Other(name) => name,
+                }
+            }
+        }
+
+        impl From<String> for $enum_name {
+            fn from(name: String) -> Self {
+                match name.as_str() {
+                    $($name => $enum_name::$variant,)*
+                    _ => $enum_name::Other(name),
+                }
+            }
+        }
+
+        impl From<&str> for $enum_name {
+            fn from(name: &str) -> Self {
+                $enum_name::from(name.to_string())
+            }
+        }
+
+        impl From<$enum_name> for String {
+            fn from(value: $enum_name) -> Self {
+                value.as_str().to_string()
+            }
+        }
+    };
+}
+
+define_type_enum!(NodeType {
+    Doc => "doc",
+    Paragraph => "paragraph",
+    Text => "text",
+    Heading => "heading",
+    Blockquote => "blockquote",
+    BulletList => "bulletList",
+    OrderedList => "orderedList",
+    ListItem => "listItem",
+    CodeBlock => "codeBlock",
+    HorizontalRule => "horizontalRule",
+    HardBreak => "hardBreak",
+    Image => "image",
+    Table => "table",
+    TableRow => "tableRow",
+    TableCell => "tableCell",
+    TableHeader => "tableHeader",
+    TaskList => "taskList",
+    TaskItem => "taskItem",
+    Mention => "mention",
+});
+
+define_type_enum!(MarkType {
+    Bold => "bold",
+    Italic => "italic",
+    Strike => "strike",
+    Underline => "underline",
+    Code => "code",
+    Link => "link",
+});
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Attrs(pub Map<String, Value>);
+
+impl Attrs {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Deref for Attrs {
+    type Target = Map<String, Value>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Attrs {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Mark {
+    #[serde(rename = "type")]
+    pub mark_type: MarkType,
+    #[serde(default, skip_serializing_if = "Attrs::is_empty")]
+    pub attrs: Attrs,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Node {
+    #[serde(rename = "type")]
+    pub node_type: NodeType,
+    #[serde(default, skip_serializing_if = "Attrs::is_empty")]
+    pub attrs: Attrs,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub content: Vec<Node>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub marks: Vec<Mark>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+impl Node {
+    pub fn new(node_type: impl Into<NodeType>) -> Self {
+        Self {
+            node_type: node_type.into(),
+            attrs: Attrs::default(),
+            content: vec![],
+            marks: vec![],
+            text: None,
+        }
+    }
+
+    pub fn text(text: &str) -> Self {
+        Self {
+            text: Some(text.to_string()),
+            ..Self::new(NodeType::Text)
+        }
+    }
+
+    pub fn with_content(mut self, content: Vec<Node>) -> Self {
+        self.content = content;
+        self
+    }
+
+    pub fn with_attr(mut self, key: &str, value: Value) -> Self {
+        self.attrs.insert(key.to_string(), value);
+        self
+    }
+
+    pub fn with_mark(mut self, mark_type: impl Into<MarkType>) -> Self {
+        self.marks.push(Mark {
+            mark_type: mark_type.into(),
+            attrs: Attrs::default(),
+        });
+        self
+    }
+
+    // The text of this node and everything below it
+    pub fn text_content(&self) -> String {
+        let mut text = self.text.clone().unwrap_or_default();
+        for child in &self.content {
+            text.push_str(&child.text_content());
+        }
+        text
+    }
+
+    pub fn from_value(value: &Value) -> Result<Self, serde_json::Error> {
+        Node::deserialize(value)
+    }
+
+    pub fn to_value(&self) -> Value {
+        // Every field serializes to plain JSON, so this can't fail
+        serde_json::to_value(self).unwrap_or(Value::Null)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ProseMirror;
+    use serde_json::json;
+
+    #[test]
+    fn it_round_trips_tiptap_json() {
+        let content = json!({"type":"doc","content":[
+            {"type":"heading","attrs":{"level":2},"content":[{"type":"text","text":"Leafs"}]},
+            {"type":"callout","content":[{"type":"text","text":"Go","marks":[{"type":"bold"},{"type":"sparkle","attrs":{"x":1}}]}]}
+        ]});
+
+        let node = Node::from_value(&content).unwrap();
+        assert_eq!(node.node_type, NodeType::Doc);
+        assert_eq!(node.content[0].node_type, NodeType::Heading);
+        assert_eq!(
+            node.content[1].node_type,
+            NodeType::Other("callout".to_string())
+        );
+        assert_eq!(
+            node.content[1].content[0].marks[0].mark_type,
+            MarkType::Bold
+        );
+        assert_eq!(node.to_value(), content);
+    }
+
+    #[test]
+    fn it_renders_typed_nodes() {
+        let node = Node::new(NodeType::Doc).with_content(vec![Node::new(NodeType::Paragraph)
+            .with_content(vec![
+                Node::text("Go "),
+                Node::text("Leafs").with_mark(MarkType::Bold),
+            ])]);
+        let prose_mirror = ProseMirror::with_default_plugins();
+
+        assert_eq!(
+            prose_mirror.render_node(&node).unwrap(),
+            "<div><p>Go <strong>Leafs</strong></p></div>".to_string()
+        );
+    }
+}
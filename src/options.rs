@@ -0,0 +1,528 @@
+use std::collections::HashMap;
+
+use serde_json::{Map, Value};
+
+use crate::attrs::{AttrFilter, AttrMapping};
+use crate::limits::DEFAULT_MAX_DEPTH;
+use crate::sanitize::UrlPolicy;
+use crate::slug::HeadingAnchors;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NullAttrs {
+    #[default]
+    Skip,
+    Empty,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AttrPolicy {
+    pub null_attrs: NullAttrs,
+    // Attributes that still render as `name=""` when null, e.g. `alt` on images
+    pub keep_empty: Vec<String>,
+}
+
+impl AttrPolicy {
+    pub fn keep_empty(attrs: &[&str]) -> Self {
+        Self {
+            null_attrs: NullAttrs::Skip,
+            keep_empty: attrs.iter().map(|attr| attr.to_string()).collect(),
+        }
+    }
+
+    pub fn renders_null(&self, attr: &str) -> bool {
+        self.null_attrs == NullAttrs::Empty || self.keep_empty.iter().any(|keep| keep == attr)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum UnknownNodeStrategy {
+    Error,
+    #[default]
+    Skip,
+    // Drop the unknown wrapper but keep whatever its children render to
+    RenderChildren,
+    // Emit an HTML comment, `{type}` is replaced with the node type
+    Placeholder(String),
+}
+
+// What to do with attrs that aren't HTML attributes, like a custom extension's
+// `{"blockId": "a1"}`, once the plugins have taken the ones they use
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnknownAttrStrategy {
+    // Written as they are, `blockId="a1"`
+    #[default]
+    Keep,
+    // `data-block-id="a1"`
+    DataPrefix,
+}
+
+// What becomes of an attr whose value is a JSON object
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ObjectAttrs {
+    // `attr="{&quot;a&quot;:1}"`
+    #[default]
+    Json,
+    Drop,
+    // JSON in `data-*` attrs, where scripts may read it back, dropped elsewhere
+    JsonInDataAttrs,
+}
+
+// How attr values that aren't strings are written. Numbers are always bare and
+// booleans outside `boolean_attrs` are `"true"`/`"false"`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct AttrCoercion {
+    // Arrays are JSON when unset, e.g. `colwidth="[100,200]"`
+    pub array_separator: Option<String>,
+    pub objects: ObjectAttrs,
+}
+
+impl AttrCoercion {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // `["a", 1]` with `" "` gives `"a 1"`
+    pub fn with_array_separator(mut self, separator: &str) -> Self {
+        self.array_separator = Some(separator.to_string());
+        self
+    }
+
+    pub fn with_objects(mut self, objects: ObjectAttrs) -> Self {
+        self.objects = objects;
+        self
+    }
+
+    // The attr's text, or `None` when it's left off
+    pub fn coerce(&self, attr: &str, value: &Value) -> Option<String> {
+        match value {
+            Value::Null => Some(String::new()),
+            Value::String(value) => Some(value.clone()),
+            Value::Array(items) => match &self.array_separator {
+                Some(separator) => Some(
+                    items
+                        .iter()
+                        .filter(|item| !item.is_null())
+                        .map(|item| match item {
+                            Value::String(item) => item.clone(),
+                            _ => item.to_string(),
+                        })
+                        .collect::<Vec<_>>()
+                        .join(separator),
+                ),
+                None => Some(value.to_string()),
+            },
+            Value::Object(_) => match self.objects {
+                ObjectAttrs::Json => Some(value.to_string()),
+                ObjectAttrs::Drop => None,
+                ObjectAttrs::JsonInDataAttrs => {
+                    attr.starts_with("data-").then(|| value.to_string())
+                }
+            },
+            _ => Some(value.to_string()),
+        }
+    }
+}
+
+// How TextAlign's `attrs.textAlign` shows up in the HTML
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum TextAlignOutput {
+    // `style="text-align: center"`
+    #[default]
+    Style,
+    // A class with this prefix, `Class("text-".into())` gives `class="text-center"`
+    Class(String),
+    // The style plus the legacy `align="center"`, which email clients like Outlook need
+    StyleAndAlign,
+}
+
+// Whether presentational attrs come out as inline styles or as classes
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum StyleStrategy {
+    // `text_align` decides for alignment, highlights get `style="background-color: ..."`
+    #[default]
+    Inline,
+    // For utility-CSS pipelines and a CSP without `'unsafe-inline'` styles
+    Classes(StyleClasses),
+}
+
+// The class names `StyleStrategy::Classes` uses. Values without a class of their
+// own get the prefix, e.g. `text-center` and `hl-yellow`, and highlight colors
+// that can't make a class name (`#ff0`, `rgb(...)`) are left unstyled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StyleClasses {
+    text_align_prefix: String,
+    highlight_prefix: String,
+    text_align: HashMap<String, String>,
+    highlight: HashMap<String, String>,
+}
+
+impl Default for StyleClasses {
+    fn default() -> Self {
+        Self {
+            text_align_prefix: "text-".to_string(),
+            highlight_prefix: "hl-".to_string(),
+            text_align: HashMap::new(),
+            highlight: HashMap::new(),
+        }
+    }
+}
+
+impl StyleClasses {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_text_align_prefix(mut self, prefix: &str) -> Self {
+        self.text_align_prefix = prefix.to_string();
+        self
+    }
+
+    pub fn with_highlight_prefix(mut self, prefix: &str) -> Self {
+        self.highlight_prefix = prefix.to_string();
+        self
+    }
+
+    pub fn with_text_align_class(mut self, align: &str, class: &str) -> Self {
+        self.text_align.insert(align.to_string(), class.to_string());
+        self
+    }
+
+    // `color` as it's stored in the document, e.g. `#fef08a`
+    pub fn with_highlight_class(mut self, color: &str, class: &str) -> Self {
+        self.highlight
+            .insert(color.to_ascii_lowercase(), class.to_string());
+        self
+    }
+
+    pub fn text_align_class(&self, align: &str) -> String {
+        match self.text_align.get(align) {
+            Some(class) => class.clone(),
+            None => format!("{}{}", self.text_align_prefix, align),
+        }
+    }
+
+    pub fn highlight_class(&self, color: &str) -> Option<String> {
+        let color = color.to_ascii_lowercase();
+        if let Some(class) = self.highlight.get(&color) {
+            return Some(class.clone());
+        }
+        let is_name = color.starts_with(|c: char| c.is_ascii_alphabetic())
+            && color.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+        is_name.then(|| format!("{}{}", self.highlight_prefix, color))
+    }
+}
+
+// Rendered as a bare `checked` when true and left off when false
+pub const DEFAULT_BOOLEAN_ATTRS: [&str; 17] = [
+    "allowfullscreen",
+    "async",
+    "autoplay",
+    "checked",
+    "controls",
+    "defer",
+    "disabled",
+    "hidden",
+    "loop",
+    "multiple",
+    "muted",
+    "open",
+    "playsinline",
+    "readonly",
+    "required",
+    "reversed",
+    "selected",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum AttrOrder {
+    // Stable no matter how `serde_json::Map` iterates, good for snapshot tests
+    #[default]
+    Alphabetical,
+    // Whatever order the attrs map yields
+    Source,
+    // Per HTML tag, the listed attrs first in that order, the rest alphabetically
+    Explicit(HashMap<String, Vec<String>>),
+}
+
+// How void elements like <img> and <br> are closed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SerializationStyle {
+    // `<img src="x">`
+    Html5,
+    // `<img src="x" />`
+    #[default]
+    Xhtml,
+}
+
+// What happens to a paragraph or heading without any content. Tiptap renders
+// `<p></p>`, which browsers collapse even though the editor shows a blank line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EmptyNodePolicy {
+    #[default]
+    Keep,
+    // `<p><br /></p>`, keeps the blank line
+    BrPlaceholder,
+    Drop,
+}
+
+// Per node or mark type, the tag its plugin renders in place of the default one.
+// Only the outer element changes, so `codeBlock` keeps its inner <code>.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TagOverrides {
+    tags: HashMap<String, Option<String>>,
+}
+
+impl TagOverrides {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_tag(mut self, node_type: &str, tag: &str) -> Self {
+        self.tags
+            .insert(node_type.to_string(), Some(tag.to_string()));
+        self
+    }
+
+    // Renders the content with no element around it, e.g. for `doc`
+    pub fn without_tag(mut self, node_type: &str) -> Self {
+        self.tags.insert(node_type.to_string(), None);
+        self
+    }
+
+    // `Some(None)` when the type renders without a tag
+    pub fn get(&self, node_type: &str) -> Option<Option<&str>> {
+        self.tags.get(node_type).map(|tag| tag.as_deref())
+    }
+}
+
+pub const DEFAULT_EMPTY_NODE_TYPES: [&str; 2] = ["heading", "paragraph"];
+
+pub type UrlRewriter = Box<dyn Fn(&str) -> String + Send + Sync>;
+
+// Resolving relative links against a base, dropping tracking parameters, routing
+// external links through a redirect...
+pub type LinkRewriter = UrlRewriter;
+
+// Links off the site open in a new window and don't pass on reputation, the
+// usual treatment for links in user comments
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalLinks {
+    site_host: String,
+    rel: Vec<String>,
+    class: Option<String>,
+}
+
+impl ExternalLinks {
+    // `origin` like `https://example.com`, links to any other host are external
+    pub fn new(origin: &str) -> Self {
+        let site_host = match url_host(origin) {
+            Some(host) => host,
+            None => strip_port(origin.trim().trim_end_matches('/')).to_ascii_lowercase(),
+        };
+        Self {
+            site_host,
+            rel: ["nofollow", "ugc", "noopener"]
+                .iter()
+                .map(|rel| rel.to_string())
+                .collect(),
+            class: None,
+        }
+    }
+
+    // Replaces the default `nofollow ugc noopener`
+    pub fn with_rel(mut self, rel: &str) -> Self {
+        self.rel = rel.split_whitespace().map(|rel| rel.to_string()).collect();
+        self
+    }
+
+    pub fn with_class(mut self, class: &str) -> Self {
+        self.class = Some(class.to_string());
+        self
+    }
+
+    pub fn rel(&self) -> &[String] {
+        &self.rel
+    }
+
+    pub fn class(&self) -> Option<&str> {
+        self.class.as_deref()
+    }
+
+    // Relative links and other schemes like `mailto:` never are
+    pub fn is_external(&self, href: &str) -> bool {
+        url_host(href).is_some_and(|host| host != self.site_host)
+    }
+}
+
+// The lowercased host of an `http(s)://` or protocol-relative URL
+fn url_host(url: &str) -> Option<String> {
+    let url = url.trim();
+    let rest = match url.find("//") {
+        Some(0) => &url[2..],
+        Some(index) => {
+            let scheme = url[..index].strip_suffix(':')?;
+            if !scheme.eq_ignore_ascii_case("http") && !scheme.eq_ignore_ascii_case("https") {
+                return None;
+            }
+            &url[index + 2..]
+        }
+        None => return None,
+    };
+    let authority = &rest[..rest.find(['/', '?', '#']).unwrap_or(rest.len())];
+    let host = strip_port(authority.rsplit('@').next().unwrap_or(authority));
+    (!host.is_empty()).then(|| host.to_ascii_lowercase())
+}
+
+fn strip_port(authority: &str) -> &str {
+    match authority.rfind(':') {
+        Some(index) if authority[index + 1..].bytes().all(|b| b.is_ascii_digit()) => {
+            &authority[..index]
+        }
+        _ => authority,
+    }
+}
+
+pub struct RenderOptions {
+    // Fail on any node or mark without a registered plugin, regardless of `unknown_nodes`
+    pub strict: bool,
+    pub unknown_nodes: UnknownNodeStrategy,
+    pub html_attributes: HashMap<String, Map<String, Value>>,
+    // Only turn this off for content that is already trusted HTML
+    pub escape_html: bool,
+    pub url_policy: UrlPolicy,
+    pub attr_policy: AttrPolicy,
+    // Per node type overrides of `attr_policy`
+    pub attr_policies: HashMap<String, AttrPolicy>,
+    // Documents nested deeper than this are rejected before rendering starts
+    pub max_depth: Option<usize>,
+    // Documents with more nodes than this, text nodes included, are rejected too
+    pub max_nodes: Option<usize>,
+    // Rendering stops with `OutputLimitExceeded` once the HTML grows past this
+    pub max_output_bytes: Option<usize>,
+    // Walk the whole document with `Plugin::collect` before rendering it
+    pub collect_pass: bool,
+    // Give headings without an `id` one generated from their text
+    pub heading_anchors: Option<HeadingAnchors>,
+    // Maps every (already sanitized) image `src`, e.g. through an image proxy
+    pub image_src_rewriter: Option<UrlRewriter>,
+    // Maps every (already sanitized) `href` the link mark writes
+    pub link_rewriter: Option<LinkRewriter>,
+    // Checked against the rewritten `href`
+    pub external_links: Option<ExternalLinks>,
+    pub text_align: TextAlignOutput,
+    pub style_strategy: StyleStrategy,
+    // Per node type renames/drops/transforms applied to attrs before rendering
+    pub attr_mappings: HashMap<String, AttrMapping>,
+    // Restricts which attrs from the document each node type may emit
+    pub attr_filters: HashMap<String, AttrFilter>,
+    // Drop `on*` attrs from the document everywhere
+    pub strip_event_handlers: bool,
+    pub unknown_attrs: UnknownAttrStrategy,
+    pub attr_values: AttrCoercion,
+    pub boolean_attrs: Vec<String>,
+    pub attr_order: AttrOrder,
+    pub serialization: SerializationStyle,
+    // Put block-level tags on their own lines, indented by this many spaces
+    pub indent: Option<usize>,
+    // Collapse whitespace in text and drop it between block-level tags
+    pub minify: bool,
+    pub empty_nodes: EmptyNodePolicy,
+    // The node types `empty_nodes` applies to
+    pub empty_node_types: Vec<String>,
+    pub tag_overrides: TagOverrides,
+    // Wrapped in `<mark class="search-hit">` wherever they're a whole word of a
+    // text node, outside code
+    pub highlight_terms: Vec<String>,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            strict: false,
+            unknown_nodes: UnknownNodeStrategy::default(),
+            html_attributes: HashMap::new(),
+            escape_html: true,
+            url_policy: UrlPolicy::default(),
+            attr_policy: AttrPolicy::default(),
+            attr_policies: HashMap::new(),
+            max_depth: Some(DEFAULT_MAX_DEPTH),
+            max_nodes: None,
+            max_output_bytes: None,
+            collect_pass: false,
+            heading_anchors: None,
+            image_src_rewriter: None,
+            link_rewriter: None,
+            external_links: None,
+            text_align: TextAlignOutput::default(),
+            style_strategy: StyleStrategy::default(),
+            attr_mappings: HashMap::new(),
+            attr_filters: HashMap::new(),
+            strip_event_handlers: true,
+            unknown_attrs: UnknownAttrStrategy::default(),
+            attr_values: AttrCoercion::default(),
+            boolean_attrs: DEFAULT_BOOLEAN_ATTRS
+                .iter()
+                .map(|attr| attr.to_string())
+                .collect(),
+            attr_order: AttrOrder::default(),
+            serialization: SerializationStyle::default(),
+            indent: None,
+            minify: false,
+            empty_nodes: EmptyNodePolicy::default(),
+            empty_node_types: DEFAULT_EMPTY_NODE_TYPES
+                .iter()
+                .map(|node_type| node_type.to_string())
+                .collect(),
+            tag_overrides: TagOverrides::default(),
+            highlight_terms: Vec::new(),
+        }
+    }
+}
+
+impl RenderOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Readable markup for debugging and emails, at the cost of extra whitespace
+    pub fn pretty(indent: usize) -> Self {
+        Self {
+            indent: Some(indent),
+            ..Self::default()
+        }
+    }
+
+    // For bandwidth-sensitive output like feeds, whitespace in <pre> is kept
+    pub fn minified() -> Self {
+        Self {
+            minify: true,
+            ..Self::default()
+        }
+    }
+
+    // For search results, the query's terms
+    pub fn highlight_terms(mut self, terms: &[String]) -> Self {
+        self.highlight_terms = terms.to_vec();
+        self
+    }
+
+    pub fn unknown_node_strategy(&self) -> &UnknownNodeStrategy {
+        if self.strict {
+            &UnknownNodeStrategy::Error
+        } else {
+            &self.unknown_nodes
+        }
+    }
+
+    pub fn is_empty_node_type(&self, node_type: &str) -> bool {
+        self.empty_node_types
+            .iter()
+            .any(|empty_node_type| empty_node_type == node_type)
+    }
+
+    pub fn attr_policy_for(&self, node_type: &str) -> &AttrPolicy {
+        self.attr_policies
+            .get(node_type)
+            .unwrap_or(&self.attr_policy)
+    }
+}
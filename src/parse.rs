@@ -0,0 +1,510 @@
+use std::cell::RefCell;
+
+use html5ever::tokenizer::{
+    BufferQueue, Tag, TagKind, Token, TokenSink, TokenSinkResult, Tokenizer, TokenizerOpts,
+};
+use serde_json::{json, Map, Value};
+
+use crate::limits::DEFAULT_MAX_DEPTH;
+
+// Elements dropped together with everything inside them
+const SKIPPED_TAGS: [&str; 8] = [
+    "head", "iframe", "label", "noscript", "object", "script", "style", "template",
+];
+
+// Node types whose content has to be blocks, stray text gets a paragraph around it
+const BLOCK_CONTAINERS: [&str; 9] = [
+    "blockquote",
+    "bulletList",
+    "doc",
+    "listItem",
+    "orderedList",
+    "table",
+    "tableCell",
+    "tableHeader",
+    "taskItem",
+];
+
+const TEXTBLOCKS: [&str; 3] = ["codeBlock", "heading", "paragraph"];
+
+// Blocks nested deeper than this are flattened into their parent, which leaves
+// room for an implicit paragraph and its text within the renderer's depth limit
+// and keeps the returned tree shallow enough to drop without overflowing
+const MAX_FRAMES: usize = DEFAULT_MAX_DEPTH - 1;
+
+struct Frame {
+    tag: String,
+    node_type: &'static str,
+    attrs: Map<String, Value>,
+    content: Vec<Value>,
+    // Opened for stray text rather than by a tag
+    implicit: bool,
+}
+
+impl Frame {
+    fn new(tag: &str, node_type: &'static str, attrs: Map<String, Value>) -> Self {
+        Self {
+            tag: tag.to_string(),
+            node_type,
+            attrs,
+            content: vec![],
+            implicit: false,
+        }
+    }
+
+    fn into_node(mut self) -> Value {
+        if self.node_type != "codeBlock" {
+            trim_text(&mut self.content);
+        }
+        let mut node = Map::new();
+        node.insert("type".to_string(), Value::from(self.node_type));
+        if !self.attrs.is_empty() {
+            node.insert("attrs".to_string(), Value::Object(self.attrs));
+        }
+        if !self.content.is_empty() || self.node_type == "doc" {
+            node.insert("content".to_string(), Value::Array(self.content));
+        }
+        Value::Object(node)
+    }
+}
+
+// Whitespace at the edges of a block doesn't render, so it isn't kept either
+fn trim_text(content: &mut Vec<Value>) {
+    let text = |node: &mut Value| -> Option<String> {
+        node.get("text")
+            .and_then(|text| text.as_str())
+            .map(String::from)
+    };
+    if let Some(first) = content.first_mut() {
+        if let Some(value) = text(first) {
+            first["text"] = Value::from(value.trim_start());
+        }
+    }
+    if let Some(last) = content.last_mut() {
+        if let Some(value) = text(last) {
+            last["text"] = Value::from(value.trim_end());
+        }
+    }
+    content.retain(|node| node.get("text").and_then(|text| text.as_str()) != Some(""));
+}
+
+fn attr<'t>(tag: &'t Tag, name: &str) -> Option<&'t str> {
+    tag.attrs
+        .iter()
+        .find(|attr| &*attr.name.local == name)
+        .map(|attr| &*attr.value)
+}
+
+fn copy_attrs(tag: &Tag, names: &[&str]) -> Map<String, Value> {
+    let mut attrs = Map::new();
+    for name in names {
+        if let Some(value) = attr(tag, name) {
+            let value = match value.parse::<u64>() {
+                Ok(number) if name != &"src" && name != &"alt" && name != &"title" => {
+                    Value::from(number)
+                }
+                _ => Value::from(value),
+            };
+            attrs.insert(name.to_string(), value);
+        }
+    }
+    attrs
+}
+
+fn mark(tag: &Tag) -> Option<Value> {
+    let mark_type = match &*tag.name {
+        "strong" | "b" => "bold",
+        "em" | "i" => "italic",
+        "s" | "del" | "strike" => "strike",
+        "u" => "underline",
+        "code" => "code",
+        "sub" => "subscript",
+        "sup" => "superscript",
+        "mark" => "highlight",
+        "a" => {
+            return Some(json!({"type":"link","attrs":copy_attrs(tag, &["href", "target", "rel"])}))
+        }
+        _ => return None,
+    };
+    Some(json!({ "type": mark_type }))
+}
+
+#[derive(Default)]
+struct Builder {
+    frames: Vec<Frame>,
+    marks: Vec<(String, Value)>,
+    // The skipped element being ignored and how deeply it nests in itself
+    skipping: Option<(String, usize)>,
+    // Tags that were too deep to open a frame, so their end tags are ignored too
+    flattened: Vec<String>,
+}
+
+impl Builder {
+    fn new() -> Self {
+        Self {
+            frames: vec![Frame::new("", "doc", Map::new())],
+            ..Self::default()
+        }
+    }
+
+    fn top(&mut self) -> &mut Frame {
+        self.frames
+            .last_mut()
+            .expect("the doc frame is never closed")
+    }
+
+    fn in_pre(&self) -> bool {
+        self.frames
+            .iter()
+            .any(|frame| frame.node_type == "codeBlock")
+    }
+
+    fn close_frame(&mut self) {
+        if self.frames.len() == 1 {
+            return;
+        }
+        let Some(frame) = self.frames.pop() else {
+            return;
+        };
+        let is_empty_implicit = frame.implicit && frame.content.is_empty();
+        let node = frame.into_node();
+        if !is_empty_implicit {
+            self.top().content.push(node);
+        }
+    }
+
+    // Closes everything up to and including the innermost frame opened by `tag`
+    fn close_tag(&mut self, tag: &str, boundary: &[&str]) -> bool {
+        let Some(index) = self.frames.iter().rposition(|frame| frame.tag == tag) else {
+            return false;
+        };
+        if self.frames[index..]
+            .iter()
+            .skip(1)
+            .any(|frame| boundary.contains(&frame.tag.as_str()))
+        {
+            return false;
+        }
+        while self.frames.len() > index {
+            self.close_frame();
+        }
+        true
+    }
+
+    fn open_block(&mut self, tag: &str, node_type: &'static str, attrs: Map<String, Value>) {
+        // Like the HTML parser, a new block ends the paragraph and a new item the last one
+        while self.top().implicit || TEXTBLOCKS.contains(&self.top().node_type) {
+            if self.frames.len() == 1 {
+                break;
+            }
+            self.close_frame();
+        }
+        match tag {
+            "li" => {
+                self.close_tag("li", &["ul", "ol"]);
+            }
+            "tr" => {
+                self.close_tag("tr", &["table"]);
+            }
+            "td" | "th" => {
+                let _ = self.close_tag("td", &["tr"]) || self.close_tag("th", &["tr"]);
+            }
+            _ => {}
+        }
+        if self.frames.len() >= MAX_FRAMES {
+            self.flattened.push(tag.to_string());
+            return;
+        }
+        self.frames.push(Frame::new(tag, node_type, attrs));
+    }
+
+    fn push_inline(&mut self, node: Value) {
+        let is_blank = node
+            .get("text")
+            .and_then(|text| text.as_str())
+            .is_some_and(|text| text.trim().is_empty());
+        if BLOCK_CONTAINERS.contains(&self.top().node_type) {
+            if is_blank {
+                return;
+            }
+            let mut paragraph = Frame::new("", "paragraph", Map::new());
+            paragraph.implicit = true;
+            self.frames.push(paragraph);
+        }
+
+        let content = &mut self.top().content;
+        if let (Some(last), Some(text)) = (content.last_mut(), node.get("text")) {
+            if last.get("text").is_some() && last.get("marks") == node.get("marks") {
+                let mut merged = last["text"].as_str().unwrap_or_default().to_string();
+                merged.push_str(text.as_str().unwrap_or_default());
+                last["text"] = Value::from(merged);
+                return;
+            }
+        }
+        content.push(node);
+    }
+
+    fn push_text(&mut self, text: &str) {
+        if self.in_pre() {
+            self.push_inline(json!({"type":"text","text":text}));
+            return;
+        }
+
+        let mut collapsed = String::with_capacity(text.len());
+        for c in text.chars() {
+            if c.is_whitespace() {
+                if !collapsed.ends_with(' ') {
+                    collapsed.push(' ');
+                }
+            } else {
+                collapsed.push(c);
+            }
+        }
+        let mut node = json!({"type":"text","text":collapsed});
+        let mut marks: Vec<Value> = vec![];
+        for (_, mark) in &self.marks {
+            if !marks
+                .iter()
+                .any(|existing| existing["type"] == mark["type"])
+            {
+                marks.push(mark.clone());
+            }
+        }
+        if !marks.is_empty() {
+            node["marks"] = Value::Array(marks);
+        }
+        self.push_inline(node);
+    }
+
+    fn start_tag(&mut self, tag: &Tag) {
+        let name = &*tag.name;
+        let in_task_list = self
+            .frames
+            .last()
+            .is_some_and(|frame| frame.node_type == "taskList");
+        match name {
+            "p" => self.open_block(name, "paragraph", Map::new()),
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level = name[1..].parse::<u64>().unwrap_or(1);
+                let mut attrs = Map::new();
+                attrs.insert("level".to_string(), Value::from(level));
+                self.open_block(name, "heading", attrs);
+            }
+            "ul" if attr(tag, "data-type") == Some("taskList") => {
+                self.open_block(name, "taskList", Map::new())
+            }
+            "ul" => self.open_block(name, "bulletList", Map::new()),
+            "ol" => self.open_block(name, "orderedList", copy_attrs(tag, &["start"])),
+            "li" if in_task_list || attr(tag, "data-type") == Some("taskItem") => {
+                let mut attrs = Map::new();
+                let checked = attr(tag, "data-checked") == Some("true");
+                attrs.insert("checked".to_string(), Value::from(checked));
+                self.open_block(name, "taskItem", attrs);
+            }
+            "li" => self.open_block(name, "listItem", Map::new()),
+            "blockquote" => self.open_block(name, "blockquote", Map::new()),
+            "pre" => self.open_block(name, "codeBlock", Map::new()),
+            "table" => self.open_block(name, "table", Map::new()),
+            "tr" => self.open_block(name, "tableRow", Map::new()),
+            "td" => self.open_block(name, "tableCell", copy_attrs(tag, &["colspan", "rowspan"])),
+            "th" => self.open_block(
+                name,
+                "tableHeader",
+                copy_attrs(tag, &["colspan", "rowspan"]),
+            ),
+            "hr" => {
+                self.open_block(name, "horizontalRule", Map::new());
+                if self.top().node_type == "horizontalRule" {
+                    self.close_frame();
+                } else {
+                    self.flattened.pop();
+                    self.top().content.push(json!({"type":"horizontalRule"}));
+                }
+            }
+            "br" => self.push_inline(json!({"type":"hardBreak"})),
+            "img" => {
+                let attrs = copy_attrs(tag, &["src", "alt", "title", "width", "height"]);
+                self.push_inline(json!({"type":"image","attrs":attrs}));
+            }
+            // The language of a code block is on its <code>, e.g. `class="language-rust"`
+            "code" if self.in_pre() => {
+                let language = attr(tag, "class").and_then(|class| {
+                    class
+                        .split_whitespace()
+                        .find_map(|class| class.strip_prefix("language-"))
+                });
+                if let Some(language) = language {
+                    let language = Value::from(language);
+                    self.top().attrs.insert("language".to_string(), language);
+                }
+            }
+            _ => {
+                if let Some(mark) = mark(tag) {
+                    self.marks.push((name.to_string(), mark));
+                }
+            }
+        }
+    }
+
+    fn end_tag(&mut self, tag: &Tag) {
+        let name = &*tag.name;
+        if let Some(index) = self
+            .marks
+            .iter()
+            .rposition(|(mark_tag, _)| mark_tag == name)
+        {
+            self.marks.remove(index);
+            return;
+        }
+        if let Some(index) = self.flattened.iter().rposition(|flat| flat == name) {
+            self.flattened.remove(index);
+            return;
+        }
+        self.close_tag(name, &[]);
+    }
+
+    fn process(&mut self, token: Token) {
+        match token {
+            Token::TagToken(tag) => {
+                let name = &*tag.name;
+                if let Some((skipped, depth)) = &mut self.skipping {
+                    if skipped == name {
+                        match tag.kind {
+                            TagKind::StartTag => *depth += 1,
+                            TagKind::EndTag if *depth == 0 => self.skipping = None,
+                            TagKind::EndTag => *depth -= 1,
+                        }
+                    }
+                    return;
+                }
+                match tag.kind {
+                    TagKind::StartTag if SKIPPED_TAGS.contains(&name) => {
+                        if !tag.self_closing {
+                            self.skipping = Some((name.to_string(), 0));
+                        }
+                    }
+                    TagKind::StartTag => self.start_tag(&tag),
+                    TagKind::EndTag => self.end_tag(&tag),
+                }
+            }
+            Token::CharacterTokens(text) if self.skipping.is_none() => self.push_text(&text),
+            _ => {}
+        }
+    }
+
+    fn finish(mut self) -> Value {
+        while self.frames.len() > 1 {
+            self.close_frame();
+        }
+        self.frames.pop().map(Frame::into_node).unwrap_or_default()
+    }
+}
+
+struct Sink(RefCell<Builder>);
+
+impl TokenSink for Sink {
+    type Handle = ();
+
+    fn process_token(&self, token: Token, _line_number: u64) -> TokenSinkResult<()> {
+        self.0.borrow_mut().process(token);
+        TokenSinkResult::Continue
+    }
+}
+
+// Converts HTML, like what the renderer produces, back into a Tiptap document.
+// Tags without a Tiptap counterpart are unwrapped and scripts are dropped.
+pub fn parse_html(html: &str) -> Value {
+    let tokenizer = Tokenizer::new(Sink(RefCell::new(Builder::new())), TokenizerOpts::default());
+    let input = BufferQueue::default();
+    input.push_back(html.into());
+    let _ = tokenizer.feed(&input);
+    tokenizer.end();
+    tokenizer.sink.0.into_inner().finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::register_task_list_plugins;
+    use crate::ProseMirror;
+
+    #[test]
+    fn it_parses_html() {
+        let html = "<h2>Leafs &amp; Habs</h2>\n<p>Go <strong>Leafs</strong>  <a href=\"https://nhl.com\">go</a><br>now</p><script>alert(1)</script>\n<ul><li>One<li><p>Two</p></ul><pre><code class=\"language-rust\">let x = 1;\n</code></pre>stray";
+
+        assert_eq!(
+            parse_html(html),
+            json!({"type":"doc","content":[
+                {"type":"heading","attrs":{"level":2},"content":[{"type":"text","text":"Leafs & Habs"}]},
+                {"type":"paragraph","content":[
+                    {"type":"text","text":"Go "},
+                    {"type":"text","text":"Leafs","marks":[{"type":"bold"}]},
+                    {"type":"text","text":" "},
+                    {"type":"text","text":"go","marks":[{"type":"link","attrs":{"href":"https://nhl.com"}}]},
+                    {"type":"hardBreak"},
+                    {"type":"text","text":"now"}
+                ]},
+                {"type":"bulletList","content":[
+                    {"type":"listItem","content":[{"type":"paragraph","content":[{"type":"text","text":"One"}]}]},
+                    {"type":"listItem","content":[{"type":"paragraph","content":[{"type":"text","text":"Two"}]}]}
+                ]},
+                {"type":"codeBlock","attrs":{"language":"rust"},"content":[{"type":"text","text":"let x = 1;\n"}]},
+                {"type":"paragraph","content":[{"type":"text","text":"stray"}]}
+            ]})
+        );
+    }
+
+    #[test]
+    fn it_round_trips_rendered_html() {
+        let content = json!({"type":"doc","content":[
+            {"type":"blockquote","content":[{"type":"paragraph","content":[
+                {"type":"text","text":"Go","marks":[{"type":"italic"}]}
+            ]}]},
+            {"type":"taskList","content":[
+                {"type":"taskItem","attrs":{"checked":true},"content":[{"type":"paragraph","content":[{"type":"text","text":"Win"}]}]}
+            ]},
+            {"type":"orderedList","attrs":{"start":3},"content":[
+                {"type":"listItem","content":[{"type":"paragraph","content":[{"type":"text","text":"Cup"}]}]}
+            ]},
+            {"type":"horizontalRule"}
+        ]});
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        register_task_list_plugins(&mut prose_mirror);
+        let html = prose_mirror.render(&content).unwrap();
+
+        assert_eq!(parse_html(&html), content);
+    }
+
+    #[test]
+    fn it_flattens_blocks_past_the_depth_limit() {
+        let html = format!(
+            "{}<p>Leafs</p><hr>{}<p>Habs</p>",
+            "<blockquote>".repeat(100_000),
+            "</blockquote>".repeat(100_000)
+        );
+        let content = parse_html(&html);
+
+        let mut node = &content;
+        let mut depth = 0;
+        while let Some(child) = node.get("content").and_then(|c| c.get(0)) {
+            if child["type"] != "blockquote" {
+                break;
+            }
+            node = child;
+            depth += 1;
+        }
+        assert_eq!(depth, MAX_FRAMES - 1);
+        assert_eq!(
+            node["content"],
+            json!([
+                {"type":"paragraph","content":[{"type":"text","text":"Leafs"}]},
+                {"type":"horizontalRule"}
+            ])
+        );
+        assert_eq!(
+            content["content"][1],
+            json!({"type":"paragraph","content":[{"type":"text","text":"Habs"}]})
+        );
+        assert!(ProseMirror::with_default_plugins().render(&content).is_ok());
+    }
+}
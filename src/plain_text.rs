@@ -0,0 +1,207 @@
+use serde_json::Value;
+
+// Nodes that flow with the surrounding text rather than starting a block
+const INLINE_TYPES: [&str; 5] = ["emoji", "footnoteReference", "hardBreak", "mention", "text"];
+
+fn attr<'v>(node: &'v Value, key: &str) -> Option<&'v str> {
+    node.get("attrs")
+        .and_then(|attrs| attrs.get(key))
+        .and_then(|value| value.as_str())
+}
+
+fn children(node: &Value) -> &[Value] {
+    node.get("content")
+        .and_then(|content| content.as_array())
+        .map(|content| content.as_slice())
+        .unwrap_or_default()
+}
+
+fn node_type(node: &Value) -> &str {
+    node.get("type")
+        .and_then(|t| t.as_str())
+        .unwrap_or_default()
+}
+
+fn link_href(node: &Value) -> Option<&str> {
+    node.get("marks")?
+        .as_array()?
+        .iter()
+        .find(|mark| mark.get("type").and_then(|t| t.as_str()) == Some("link"))
+        .and_then(|link| attr(link, "href"))
+}
+
+fn inline_text(node: &Value) -> String {
+    match node_type(node) {
+        "text" => node
+            .get("text")
+            .and_then(|text| text.as_str())
+            .unwrap_or_default()
+            .to_string(),
+        "hardBreak" => "\n".to_string(),
+        "mention" => format!(
+            "@{}",
+            attr(node, "label").or(attr(node, "id")).unwrap_or_default()
+        ),
+        "emoji" => format!(":{}:", attr(node, "name").unwrap_or_default()),
+        _ => String::new(),
+    }
+}
+
+// A run of inline nodes, a link's URL follows in parentheses unless it's the text itself
+fn render_inline(nodes: &[Value]) -> String {
+    let mut output = String::new();
+    let mut link: Option<(&str, String)> = None;
+    let close_link = |output: &mut String, link: Option<(&str, String)>| {
+        if let Some((href, text)) = link {
+            output.push_str(&text);
+            if text != href {
+                output.push_str(&format!(" ({})", href));
+            }
+        }
+    };
+
+    for node in nodes {
+        let text = inline_text(node);
+        match (link_href(node), link.as_mut()) {
+            (Some(href), Some((current, linked))) if href == *current => linked.push_str(&text),
+            (Some(href), _) => {
+                close_link(&mut output, link.take());
+                link = Some((href, text));
+            }
+            (None, _) => {
+                close_link(&mut output, link.take());
+                output.push_str(&text);
+            }
+        }
+    }
+    close_link(&mut output, link);
+    output
+}
+
+fn indent(text: &str, first: &str, rest: &str) -> String {
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| match (index, line.is_empty()) {
+            (0, _) => format!("{}{}", first, line),
+            (_, true) => String::new(),
+            _ => format!("{}{}", rest, line),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Runs of inline children become paragraphs of their own, blocks are separated
+// by a blank line or, inside list items, a single newline
+fn render_blocks(nodes: &[Value], separator: &str) -> String {
+    let mut blocks = vec![];
+    let mut run_start = None;
+    for (index, node) in nodes.iter().enumerate() {
+        if INLINE_TYPES.contains(&node_type(node)) {
+            run_start.get_or_insert(index);
+            continue;
+        }
+        if let Some(start) = run_start.take() {
+            blocks.push(render_inline(&nodes[start..index]));
+        }
+        blocks.push(render_block(node));
+    }
+    if let Some(start) = run_start {
+        blocks.push(render_inline(&nodes[start..]));
+    }
+    blocks.retain(|block| !block.is_empty());
+    blocks.join(separator)
+}
+
+fn render_list(node: &Value) -> String {
+    let start = node
+        .get("attrs")
+        .and_then(|attrs| attrs.get("start"))
+        .and_then(|start| start.as_u64())
+        .unwrap_or(1);
+    children(node)
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let marker = match node_type(node) {
+                "orderedList" => format!("{}. ", start + index as u64),
+                "taskList" => {
+                    let checked = item
+                        .get("attrs")
+                        .and_then(|attrs| attrs.get("checked"))
+                        .and_then(|checked| checked.as_bool())
+                        .unwrap_or(false);
+                    if checked { "[x] " } else { "[ ] " }.to_string()
+                }
+                _ => "- ".to_string(),
+            };
+            let text = render_blocks(children(item), "\n");
+            indent(&text, &marker, &" ".repeat(marker.chars().count()))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn render_block(node: &Value) -> String {
+    match node_type(node) {
+        "bulletList" | "orderedList" | "taskList" => render_list(node),
+        "blockquote" => indent(&render_blocks(children(node), "\n\n"), "> ", "> "),
+        "horizontalRule" => "---".to_string(),
+        "image" => attr(node, "alt").unwrap_or_default().to_string(),
+        "table" => children(node)
+            .iter()
+            .map(|row| {
+                children(row)
+                    .iter()
+                    .map(|cell| render_blocks(children(cell), " "))
+                    .collect::<Vec<_>>()
+                    .join(" | ")
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => render_blocks(children(node), "\n\n"),
+    }
+}
+
+pub fn render_text(content: &Value) -> String {
+    if INLINE_TYPES.contains(&node_type(content)) {
+        return render_inline(std::slice::from_ref(content));
+    }
+    render_block(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_renders_plain_text() {
+        let content = json!({"type":"doc","content":[
+            {"type":"heading","attrs":{"level":1},"content":[{"type":"text","text":"Leafs"}]},
+            {"type":"paragraph","content":[
+                {"type":"text","text":"Tickets at "},
+                {"type":"text","text":"the ","marks":[{"type":"link","attrs":{"href":"https://nhl.com"}}]},
+                {"type":"text","text":"box office","marks":[{"type":"link","attrs":{"href":"https://nhl.com"}},{"type":"bold"}]},
+                {"type":"hardBreak"},
+                {"type":"text","text":"https://nhl.com","marks":[{"type":"link","attrs":{"href":"https://nhl.com"}}]}
+            ]},
+            {"type":"bulletList","content":[
+                {"type":"listItem","content":[
+                    {"type":"paragraph","content":[{"type":"text","text":"Matthews"}]},
+                    {"type":"orderedList","attrs":{"start":34},"content":[
+                        {"type":"listItem","content":[{"type":"paragraph","content":[{"type":"text","text":"Goals"}]}]},
+                        {"type":"listItem","content":[{"type":"paragraph","content":[{"type":"text","text":"Assists"}]}]}
+                    ]}
+                ]},
+                {"type":"listItem","content":[{"type":"paragraph","content":[{"type":"text","text":"Marner"}]}]}
+            ]},
+            {"type":"blockquote","content":[{"type":"paragraph","content":[{"type":"text","text":"Go Leafs Go"}]}]},
+            {"type":"horizontalRule"}
+        ]});
+
+        assert_eq!(
+            render_text(&content),
+            "Leafs\n\nTickets at the box office (https://nhl.com)\nhttps://nhl.com\n\n- Matthews\n  34. Goals\n  35. Assists\n- Marner\n\n> Go Leafs Go\n\n---"
+        );
+    }
+}
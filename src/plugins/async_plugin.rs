@@ -0,0 +1,110 @@
+use std::future::Future;
+use std::pin::Pin;
+
+use serde_json::Value;
+
+use crate::error::ProseMirrorError;
+
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+// Renders a whole node (children included) from something that has to be awaited,
+// like a DB lookup for a mention or an oEmbed request for an embed
+pub trait AsyncPlugin: Send + Sync {
+    fn render<'a>(&'a self, node: &'a Value) -> BoxFuture<'a, Result<String, ProseMirrorError>>;
+}
+
+impl<F, Fut> AsyncPlugin for F
+where
+    F: Fn(Value) -> Fut + Send + Sync,
+    Fut: Future<Output = Result<String, ProseMirrorError>> + Send + 'static,
+{
+    fn render<'a>(&'a self, node: &'a Value) -> BoxFuture<'a, Result<String, ProseMirrorError>> {
+        Box::pin(self(node.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ProseMirror;
+    use serde_json::json;
+    use std::task::{Context, Poll, Waker};
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = Box::pin(future);
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    async fn lookup_user(node: Value) -> Result<String, ProseMirrorError> {
+        match node["attrs"]["id"].as_str() {
+            Some("34") => Ok("<a href=\"/u/34\">@Auston</a>".to_string()),
+            _ => Err(ProseMirrorError::invalid_shape("unknown user")),
+        }
+    }
+
+    #[test]
+    fn it_renders_async_plugins() {
+        let content = json!({"type":"doc","content":[{"type":"paragraph","content":[
+            {"type":"text","text":"Nice goal "},
+            {"type":"mention","attrs":{"id":"34"}}
+        ]}]});
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        prose_mirror.add_async_plugin("mention", Box::new(lookup_user));
+
+        assert_eq!(
+            block_on(prose_mirror.render_async(&content)).unwrap(),
+            "<div><p>Nice goal <a href=\"/u/34\">@Auston</a></p></div>".to_string()
+        );
+    }
+
+    #[test]
+    fn it_reports_async_errors_with_path() {
+        let content = json!({"type":"doc","content":[{"type":"paragraph","content":[
+            {"type":"mention","attrs":{"id":"16"}}
+        ]}]});
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        prose_mirror.add_async_plugin("mention", Box::new(lookup_user));
+
+        let err = block_on(prose_mirror.render_async(&content)).unwrap_err();
+        assert_eq!(err.path(), "/content/0/content/0");
+    }
+
+    #[test]
+    fn it_can_be_sent_across_threads() {
+        fn assert_send<T: Send>(_: T) {}
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        prose_mirror.add_async_plugin("mention", Box::new(lookup_user));
+        assert_send(prose_mirror.render_async(&json!({"type":"doc"})));
+    }
+
+    #[test]
+    fn it_leaves_sync_renders_to_the_regular_plugins() {
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        prose_mirror.add_async_plugin(
+            "paragraph",
+            Box::new(|_| async { Ok("<p>awaited</p>".to_string()) }),
+        );
+
+        let content = json!({"type":"doc","content":[
+            {"type":"paragraph","content":[{"type":"text","text":"<b>"}]},
+            {"type":"blockquote","content":[{"type":"paragraph"}]}
+        ]});
+        assert_eq!(
+            block_on(prose_mirror.render_async(&content)).unwrap(),
+            "<div><p>awaited</p><blockquote><p>awaited</p></blockquote></div>".to_string()
+        );
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div><p>&lt;b&gt;</p><blockquote><p></p></blockquote></div>".to_string()
+        );
+        assert_eq!(
+            block_on(prose_mirror.render_async(&json!({"type":"doc"}))).unwrap(),
+            prose_mirror.render(&json!({"type":"doc"})).unwrap()
+        );
+    }
+}
@@ -0,0 +1,132 @@
+use std::fmt;
+
+use serde_json::{Map, Value};
+
+use crate::error::ProseMirrorError;
+use crate::utils::merge_attributes;
+use crate::{ProseMirror, RenderContext};
+
+use super::Plugin;
+
+// The usual custom `callout` node, `{"type":"callout","attrs":{"variant":"warning"}}`,
+// rendered as `<div class="callout callout-warning" role="note">`
+pub struct CalloutPlugin {
+    class_name: String,
+    variant_prefix: String,
+}
+
+impl Default for CalloutPlugin {
+    fn default() -> Self {
+        Self {
+            class_name: "callout".to_string(),
+            variant_prefix: "callout-".to_string(),
+        }
+    }
+}
+
+impl Plugin for CalloutPlugin {
+    fn render(&self, node: &Value, ctx: &mut RenderContext) -> Result<String, ProseMirrorError> {
+        let mut output = String::new();
+        self.render_to(node, ctx, &mut output)?;
+        Ok(output)
+    }
+
+    fn render_to(
+        &self,
+        node: &Value,
+        ctx: &mut RenderContext,
+        writer: &mut dyn fmt::Write,
+    ) -> Result<(), ProseMirrorError> {
+        let mut attrs = ctx.node_attrs("callout", node).unwrap_or_default();
+        let variant = attrs.remove("variant");
+        let mut classes = vec![self.class_name.clone()];
+        // The variant ends up in a class name, so anything but a plain word is ignored
+        if let Some(variant) = variant
+            .as_ref()
+            .and_then(|variant| variant.as_str())
+            .filter(|variant| {
+                !variant.is_empty()
+                    && variant
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            })
+        {
+            classes.push(format!("{}{}", self.variant_prefix, variant));
+        }
+        let mut extra = Map::new();
+        extra.insert("class".to_string(), Value::from(classes.join(" ")));
+        extra.insert("role".to_string(), Value::from("note"));
+        merge_attributes(&mut attrs, &extra);
+
+        let tag = ctx.tag_for("callout", "div", false);
+        tag.write_opening(Some(&attrs), writer)?;
+        ctx.render_content_to(node, writer)?;
+        tag.write_closing(writer)?;
+        Ok(())
+    }
+}
+
+impl CalloutPlugin {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // The class every callout gets, `callout` by default
+    pub fn with_class_name(mut self, class_name: &str) -> Self {
+        self.class_name = class_name.to_string();
+        self
+    }
+
+    // Prepended to the variant for its class, `callout-` by default
+    pub fn with_variant_prefix(mut self, prefix: &str) -> Self {
+        self.variant_prefix = prefix.to_string();
+        self
+    }
+
+    pub fn type_name() -> &'static str {
+        "callout"
+    }
+
+    pub fn register(prosemirror: &mut ProseMirror) {
+        prosemirror.add_plugin("callout", Box::new(CalloutPlugin::new()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_renders_callouts() {
+        let content = json!({"type":"callout","attrs":{"variant":"warning"},"content":[
+            {"type":"paragraph","content":[{"type":"text","text":"Icing"}]}
+        ]});
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        CalloutPlugin::register(&mut prose_mirror);
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div class=\"callout callout-warning\" role=\"note\"><p>Icing</p></div>"
+        );
+
+        prose_mirror.add_plugin(
+            "callout",
+            Box::new(
+                CalloutPlugin::new()
+                    .with_class_name("alert")
+                    .with_variant_prefix("alert--"),
+            ),
+        );
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div class=\"alert alert--warning\" role=\"note\"><p>Icing</p></div>"
+        );
+        assert_eq!(
+            prose_mirror
+                .render(&json!({"type":"callout","attrs":{"variant":"x\" onclick"}}))
+                .unwrap(),
+            "<div class=\"alert\" role=\"note\"></div>"
+        );
+    }
+}
@@ -0,0 +1,231 @@
+use serde_json::{Map, Value};
+
+use crate::error::ProseMirrorError;
+use crate::utils::{escape_html, merge_attributes};
+use crate::{ProseMirror, RenderContext};
+
+#[cfg(feature = "syntax-highlight")]
+use super::highlight;
+use super::Plugin;
+
+type LanguageRenderer = Box<dyn Fn(&str) -> String + Send + Sync>;
+
+pub struct CodeBlockPlugin {
+    language_class_prefix: String,
+    // Checked before highlighting, e.g. to turn mermaid source into an SVG
+    language_renderers: Vec<(String, LanguageRenderer)>,
+    // Rendered as `<pre class="mermaid">`, which diagram libraries pick up client-side
+    diagram_languages: Vec<String>,
+    #[cfg(feature = "syntax-highlight")]
+    highlight: bool,
+}
+
+impl Default for CodeBlockPlugin {
+    fn default() -> Self {
+        Self {
+            language_class_prefix: "language-".to_string(),
+            language_renderers: vec![],
+            diagram_languages: vec![],
+            #[cfg(feature = "syntax-highlight")]
+            highlight: true,
+        }
+    }
+}
+
+impl Plugin for CodeBlockPlugin {
+    fn render(&self, node: &Value, ctx: &mut RenderContext) -> Result<String, ProseMirrorError> {
+        let mut code = String::new();
+        if let Some(content) = node.get("content").and_then(|c| c.as_array()) {
+            for child_node in content {
+                if let Some(text) = child_node.get("text").and_then(|t| t.as_str()) {
+                    code.push_str(text);
+                }
+            }
+        }
+
+        let language = node
+            .get("attrs")
+            .and_then(|attrs| attrs.get("language"))
+            .and_then(|language| language.as_str());
+        if let Some(language) = language {
+            if let Some((_, render)) = self
+                .language_renderers
+                .iter()
+                .find(|(name, _)| name == language)
+            {
+                return Ok(render(&code));
+            }
+            if self.diagram_languages.iter().any(|name| name == language) {
+                let mut pre_attrs = ctx.node_attrs("codeBlock", node).unwrap_or_default();
+                pre_attrs.remove("language");
+                let mut class = Map::new();
+                class.insert("class".to_string(), Value::from(language));
+                merge_attributes(&mut pre_attrs, &class);
+                return Ok(ctx
+                    .tag_for("codeBlock", "pre", false)
+                    .render_with_attrs(escape_html(&code), Some(&pre_attrs)));
+            }
+        }
+
+        let mut code_attrs = Map::new();
+        if let Some(language) = language {
+            code_attrs.insert(
+                "class".to_string(),
+                Value::String(format!("{}{}", self.language_class_prefix, language)),
+            );
+        }
+        let html = self.highlighted(&code, language);
+
+        let mut pre_attrs = ctx.node_attrs("codeBlock", node).unwrap_or_default();
+        pre_attrs.remove("language");
+
+        let code = ctx
+            .tag("code", false)
+            .render_with_attrs(html, Some(&code_attrs));
+        Ok(ctx
+            .tag_for("codeBlock", "pre", false)
+            .render_with_attrs(code, Some(&pre_attrs)))
+    }
+}
+
+impl CodeBlockPlugin {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_language_class_prefix(mut self, prefix: &str) -> Self {
+        self.language_class_prefix = prefix.to_string();
+        self
+    }
+
+    // Renders the whole block for one language, the callback gets the raw source
+    // and its output is used as is
+    pub fn with_language_renderer<F>(mut self, language: &str, render: F) -> Self
+    where
+        F: Fn(&str) -> String + Send + Sync + 'static,
+    {
+        self.language_renderers
+            .push((language.to_string(), Box::new(render)));
+        self
+    }
+
+    // Languages like `mermaid` whose blocks are left for a diagram library
+    pub fn with_diagram_language(mut self, language: &str) -> Self {
+        self.diagram_languages.push(language.to_string());
+        self
+    }
+
+    #[cfg(feature = "syntax-highlight")]
+    pub fn with_highlighting(mut self, highlight: bool) -> Self {
+        self.highlight = highlight;
+        self
+    }
+
+    #[cfg(feature = "syntax-highlight")]
+    fn highlighted(&self, code: &str, language: Option<&str>) -> String {
+        language
+            .filter(|_| self.highlight)
+            .and_then(|language| highlight::highlight(code, language))
+            .unwrap_or_else(|| escape_html(code))
+    }
+
+    #[cfg(not(feature = "syntax-highlight"))]
+    fn highlighted(&self, code: &str, _language: Option<&str>) -> String {
+        escape_html(code)
+    }
+
+    pub fn type_name() -> &'static str {
+        "codeBlock"
+    }
+
+    pub fn register(prosemirror: &mut ProseMirror) {
+        prosemirror.add_plugin("codeBlock", Box::new(CodeBlockPlugin::new()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    // Highlighting wraps tokens in spans, see `it_highlights_with_hljs_classes`
+    #[cfg(not(feature = "syntax-highlight"))]
+    #[test]
+    fn it_renders_code_block_with_language() {
+        let content = json!({"type":"codeBlock","attrs":{"language":"rust"},"content":[{"type":"text","text":"fn main() {\n    println!(\"<hi>\");\n}"}]});
+        let prose_mirror = ProseMirror::with_default_plugins();
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<pre><code class=\"language-rust\">fn main() {\n    println!(&quot;&lt;hi&gt;&quot;);\n}</code></pre>"
+                .to_string()
+        );
+    }
+
+    #[cfg(feature = "syntax-highlight")]
+    #[test]
+    fn it_highlights_with_hljs_classes() {
+        let content = json!({"type":"codeBlock","attrs":{"language":"rust"},"content":[{"type":"text","text":"let x = \"<a>\"; // hi"}]});
+        let prose_mirror = ProseMirror::with_default_plugins();
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<pre><code class=\"language-rust\"><span class=\"hljs-keyword\">let</span> x <span class=\"hljs-operator\">=</span> <span class=\"hljs-string\">&quot;&lt;a&gt;&quot;</span>; <span class=\"hljs-comment\">// hi</span></code></pre>"
+                .to_string()
+        );
+
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        prose_mirror.add_plugin(
+            "codeBlock",
+            Box::new(CodeBlockPlugin::new().with_highlighting(false)),
+        );
+        assert!(!prose_mirror.render(&content).unwrap().contains("<span"));
+    }
+
+    #[test]
+    fn it_renders_diagram_languages() {
+        let content = json!({"type":"codeBlock","attrs":{"language":"mermaid"},"content":[{"type":"text","text":"graph TD; A-->B"}]});
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        prose_mirror.add_plugin(
+            "codeBlock",
+            Box::new(CodeBlockPlugin::new().with_diagram_language("mermaid")),
+        );
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<pre class=\"mermaid\">graph TD; A--&gt;B</pre>".to_string()
+        );
+
+        prose_mirror.add_plugin(
+            "codeBlock",
+            Box::new(
+                CodeBlockPlugin::new()
+                    .with_diagram_language("mermaid")
+                    .with_language_renderer("mermaid", |code| format!("<svg>{}</svg>", code.len())),
+            ),
+        );
+        assert_eq!(prose_mirror.render(&content).unwrap(), "<svg>15</svg>");
+    }
+
+    #[test]
+    fn it_renders_code_block_without_language() {
+        let content = json!({"type":"codeBlock","attrs":{"language":null},"content":[{"type":"text","text":"a && b"}]});
+        let prose_mirror = ProseMirror::with_default_plugins();
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<pre><code>a &amp;&amp; b</code></pre>".to_string()
+        );
+    }
+
+    #[test]
+    fn it_renders_inline_code_mark() {
+        let content = json!({"type":"text","text":"cargo test","marks":[{"type":"code"}]});
+        let prose_mirror = ProseMirror::with_default_plugins();
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<code>cargo test</code>".to_string()
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::error::ProseMirrorError;
+use crate::{ProseMirror, RenderContext};
+
+use super::{DetailsSummaryPlugin, Plugin};
+
+// Tiptap's Details extension, as a native <details> so it still collapses
+// without any JavaScript
+#[derive(Default)]
+pub struct DetailsPlugin;
+
+impl Plugin for DetailsPlugin {
+    fn render(&self, node: &Value, ctx: &mut RenderContext) -> Result<String, ProseMirrorError> {
+        let mut output = String::new();
+        self.render_to(node, ctx, &mut output)?;
+        Ok(output)
+    }
+
+    fn render_to(
+        &self,
+        node: &Value,
+        ctx: &mut RenderContext,
+        writer: &mut dyn fmt::Write,
+    ) -> Result<(), ProseMirrorError> {
+        let mut attrs = ctx.node_attrs("details", node).unwrap_or_default();
+        if attrs.remove("open").and_then(|open| open.as_bool()) == Some(true) {
+            attrs.insert("open".to_string(), Value::Bool(true));
+        }
+
+        let tag = ctx.tag_for("details", "details", false);
+        tag.write_opening(Some(&attrs), writer)?;
+        ctx.render_content_to(node, writer)?;
+        tag.write_closing(writer)?;
+        Ok(())
+    }
+}
+
+impl DetailsPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn type_name() -> &'static str {
+        "details"
+    }
+
+    pub fn register(prosemirror: &mut ProseMirror) {
+        prosemirror.add_plugin("details", Box::new(DetailsPlugin::new()));
+    }
+}
+
+#[derive(Default)]
+pub struct DetailsContentPlugin;
+
+impl Plugin for DetailsContentPlugin {
+    fn render(&self, node: &Value, ctx: &mut RenderContext) -> Result<String, ProseMirrorError> {
+        let mut output = String::new();
+        self.render_to(node, ctx, &mut output)?;
+        Ok(output)
+    }
+
+    fn render_to(
+        &self,
+        node: &Value,
+        ctx: &mut RenderContext,
+        writer: &mut dyn fmt::Write,
+    ) -> Result<(), ProseMirrorError> {
+        let mut attrs = ctx.node_attrs("detailsContent", node).unwrap_or_default();
+        attrs.insert("data-type".to_string(), Value::from("detailsContent"));
+
+        let tag = ctx.tag_for("detailsContent", "div", false);
+        tag.write_opening(Some(&attrs), writer)?;
+        ctx.render_content_to(node, writer)?;
+        tag.write_closing(writer)?;
+        Ok(())
+    }
+}
+
+impl DetailsContentPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn type_name() -> &'static str {
+        "detailsContent"
+    }
+
+    pub fn register(prosemirror: &mut ProseMirror) {
+        prosemirror.add_plugin("detailsContent", Box::new(DetailsContentPlugin::new()));
+    }
+}
+
+pub fn register_details_plugins(prosemirror: &mut ProseMirror) {
+    DetailsPlugin::register(prosemirror);
+    DetailsSummaryPlugin::register(prosemirror);
+    DetailsContentPlugin::register(prosemirror);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_renders_details() {
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        register_details_plugins(&mut prose_mirror);
+
+        let details = |open: Value| {
+            json!({"type":"details","attrs":{"open":open},"content":[
+                {"type":"detailsSummary","content":[{"type":"text","text":"Lineup"}]},
+                {"type":"detailsContent","content":[{"type":"paragraph","content":[{"type":"text","text":"Matthews"}]}]}
+            ]})
+        };
+
+        assert_eq!(
+            prose_mirror.render(&details(json!(true))).unwrap(),
+            "<details open><summary>Lineup</summary><div data-type=\"detailsContent\"><p>Matthews</p></div></details>"
+                .to_string()
+        );
+        assert!(prose_mirror
+            .render(&details(json!(false)))
+            .unwrap()
+            .starts_with("<details><summary>"));
+    }
+}
@@ -0,0 +1,224 @@
+use std::collections::HashMap;
+
+use serde_json::{Map, Value};
+
+use crate::error::ProseMirrorError;
+use crate::{ProseMirror, RenderContext};
+
+use super::Plugin;
+
+pub type EmojiImageResolver = Box<dyn Fn(&str) -> Option<String> + Send + Sync>;
+
+const DEFAULT_SHORTCODES: [(&str, &str); 40] = [
+    ("smile", "😄"),
+    ("smiley", "😃"),
+    ("grin", "😁"),
+    ("joy", "😂"),
+    ("laughing", "😆"),
+    ("wink", "😉"),
+    ("blush", "😊"),
+    ("heart_eyes", "😍"),
+    ("smirk", "😏"),
+    ("thinking", "🤔"),
+    ("neutral_face", "😐"),
+    ("unamused", "😒"),
+    ("sweat_smile", "😅"),
+    ("cry", "😢"),
+    ("sob", "😭"),
+    ("angry", "😠"),
+    ("rage", "😡"),
+    ("scream", "😱"),
+    ("sunglasses", "😎"),
+    ("sleeping", "😴"),
+    ("sweat", "😓"),
+    ("innocent", "😇"),
+    ("upside_down_face", "🙃"),
+    ("slightly_smiling_face", "🙂"),
+    ("+1", "👍"),
+    ("thumbsup", "👍"),
+    ("-1", "👎"),
+    ("thumbsdown", "👎"),
+    ("clap", "👏"),
+    ("wave", "👋"),
+    ("pray", "🙏"),
+    ("muscle", "💪"),
+    ("eyes", "👀"),
+    ("heart", "❤️"),
+    ("broken_heart", "💔"),
+    ("fire", "🔥"),
+    ("100", "💯"),
+    ("tada", "🎉"),
+    ("rocket", "🚀"),
+    ("hockey", "🏒"),
+];
+
+// Tiptap's Emoji node. Shortcodes map to Unicode, anything not in the table can
+// come from a custom image set, and otherwise stays as `:name:`.
+pub struct EmojiPlugin {
+    shortcodes: HashMap<String, String>,
+    image_resolver: Option<EmojiImageResolver>,
+}
+
+impl Default for EmojiPlugin {
+    fn default() -> Self {
+        Self {
+            shortcodes: DEFAULT_SHORTCODES
+                .iter()
+                .map(|(name, emoji)| (name.to_string(), emoji.to_string()))
+                .collect(),
+            image_resolver: None,
+        }
+    }
+}
+
+impl Plugin for EmojiPlugin {
+    fn render(&self, node: &Value, ctx: &mut RenderContext) -> Result<String, ProseMirrorError> {
+        let name = node
+            .get("attrs")
+            .and_then(|attrs| attrs.get("name"))
+            .and_then(|name| name.as_str())
+            .ok_or_else(|| ProseMirrorError::invalid_shape("emoji needs a `name` attr"))?;
+
+        let mut attrs = Map::new();
+        attrs.insert("data-name".to_string(), Value::from(name));
+        attrs.insert("data-type".to_string(), Value::from("emoji"));
+
+        let shortcode = format!(":{}:", name);
+        let output = match self.shortcodes.get(name) {
+            Some(emoji) => ctx.escape_text(emoji),
+            None => match self
+                .image_resolver
+                .as_ref()
+                .and_then(|resolve| resolve(name))
+            {
+                Some(src) => {
+                    let mut img_attrs = Map::new();
+                    img_attrs.insert("alt".to_string(), Value::from(shortcode));
+                    img_attrs.insert("src".to_string(), Value::from(src));
+                    ctx.sanitize_url_attr(&mut img_attrs, "src")?;
+                    ctx.tag("img", true).render_opening(Some(&img_attrs))
+                }
+                None => ctx.escape_text(&shortcode),
+            },
+        };
+        Ok(ctx
+            .tag_for("emoji", "span", false)
+            .render_with_attrs(output, Some(&attrs)))
+    }
+}
+
+impl EmojiPlugin {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Replaces the built-in table entirely
+    pub fn with_shortcodes(mut self, shortcodes: HashMap<String, String>) -> Self {
+        self.shortcodes = shortcodes;
+        self
+    }
+
+    pub fn with_shortcode(mut self, name: &str, emoji: &str) -> Self {
+        self.shortcodes.insert(name.to_string(), emoji.to_string());
+        self
+    }
+
+    // Returns the image URL for a custom emoji, or `None` to fall back to `:name:`
+    pub fn with_image_resolver<F>(mut self, resolver: F) -> Self
+    where
+        F: Fn(&str) -> Option<String> + Send + Sync + 'static,
+    {
+        self.image_resolver = Some(Box::new(resolver));
+        self
+    }
+
+    pub fn type_name() -> &'static str {
+        "emoji"
+    }
+
+    pub fn register(prosemirror: &mut ProseMirror) {
+        prosemirror.add_plugin("emoji", Box::new(EmojiPlugin::new()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_maps_shortcodes_to_unicode() {
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        EmojiPlugin::register(&mut prose_mirror);
+
+        assert_eq!(
+            prose_mirror
+                .render(&json!({"type":"emoji","attrs":{"name":"fire"}}))
+                .unwrap(),
+            "<span data-name=\"fire\" data-type=\"emoji\">🔥</span>".to_string()
+        );
+        assert_eq!(
+            prose_mirror
+                .render(&json!({"type":"emoji","attrs":{"name":"<leafs>"}}))
+                .unwrap(),
+            "<span data-name=\"&lt;leafs&gt;\" data-type=\"emoji\">:&lt;leafs&gt;:</span>"
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn it_renders_custom_emoji_images() {
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        prose_mirror.add_plugin(
+            "emoji",
+            Box::new(
+                EmojiPlugin::new()
+                    .with_shortcode("goal", "🚨")
+                    .with_image_resolver(|name| {
+                        (name == "leafs").then(|| format!("/emoji/{}.png", name))
+                    }),
+            ),
+        );
+
+        assert!(prose_mirror
+            .render(&json!({"type":"emoji","attrs":{"name":"goal"}}))
+            .unwrap()
+            .contains(">🚨<"));
+        assert_eq!(
+            prose_mirror.render(&json!({"type":"emoji","attrs":{"name":"leafs"}})).unwrap(),
+            "<span data-name=\"leafs\" data-type=\"emoji\"><img alt=\":leafs:\" src=\"/emoji/leafs.png\" /></span>"
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn it_rejects_missing_names_and_unsafe_images() {
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        prose_mirror.add_plugin(
+            "emoji",
+            Box::new(
+                EmojiPlugin::new().with_image_resolver(|name| Some(format!("javascript:{}", name))),
+            ),
+        );
+
+        for attrs in [
+            json!({}),
+            json!({"name":null}),
+            json!({"name":7}),
+            json!(null),
+        ] {
+            assert!(matches!(
+                prose_mirror
+                    .render(&json!({"type":"emoji","attrs":attrs}))
+                    .unwrap_err(),
+                ProseMirrorError::InvalidNodeShape { .. }
+            ));
+        }
+        assert_eq!(
+            prose_mirror
+                .render(&json!({"type":"emoji","attrs":{"name":"leafs"}}))
+                .unwrap(),
+            "<span data-name=\"leafs\" data-type=\"emoji\"><img alt=\":leafs:\" /></span>"
+        );
+    }
+}
@@ -0,0 +1,60 @@
+use serde_json::Value;
+
+use crate::error::ProseMirrorError;
+use crate::RenderContext;
+
+use super::Plugin;
+
+// Lets a closure stand in for a one-off plugin without a struct + trait impl
+pub struct FnPlugin<F> {
+    render: F,
+}
+
+impl<F> FnPlugin<F>
+where
+    F: Fn(&Value, &mut RenderContext) -> Result<String, ProseMirrorError> + Send + Sync + 'static,
+{
+    pub fn new(render: F) -> Self {
+        Self { render }
+    }
+}
+
+impl<F> Plugin for FnPlugin<F>
+where
+    F: Fn(&Value, &mut RenderContext) -> Result<String, ProseMirrorError> + Send + Sync + 'static,
+{
+    fn render(&self, node: &Value, ctx: &mut RenderContext) -> Result<String, ProseMirrorError> {
+        (self.render)(node, ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ProseMirror;
+    use serde_json::json;
+
+    #[test]
+    fn it_renders_with_a_closure() {
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        prose_mirror.add_fn_plugin("callout", |node, ctx| {
+            let kind = node
+                .get("attrs")
+                .and_then(|attrs| attrs.get("kind"))
+                .and_then(|kind| kind.as_str())
+                .unwrap_or("note");
+            Ok(format!(
+                "<aside class=\"callout-{}\">{}</aside>",
+                ctx.escape_text(kind),
+                ctx.render_content(node)?
+            ))
+        });
+
+        let content = json!({"type":"doc","content":[
+            {"type":"callout","attrs":{"kind":"warning"},"content":[{"type":"paragraph","content":[{"type":"text","text":"Icing"}]}]}
+        ]});
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div><aside class=\"callout-warning\"><p>Icing</p></aside></div>".to_string()
+        );
+    }
+}
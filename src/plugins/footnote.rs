@@ -0,0 +1,293 @@
+use std::fmt;
+
+use serde_json::{Map, Value};
+
+use crate::error::ProseMirrorError;
+use crate::model::Node;
+use crate::{ProseMirror, RenderContext, RenderState};
+
+use super::Plugin;
+
+// RenderState keys: id -> number, id -> references rendered so far, and the
+// definitions waiting for the end of the document as [id, html] pairs
+const NUMBERS_STATE: &str = "footnoteNumbers";
+const REFERENCES_STATE: &str = "footnoteReferences";
+const DEFINITIONS_STATE: &str = "footnoteDefinitions";
+
+fn footnote_id(attrs: Option<&Map<String, Value>>) -> Option<String> {
+    match attrs?.get("id")? {
+        Value::String(id) if !id.is_empty() => Some(id.clone()),
+        Value::Number(id) => Some(id.to_string()),
+        _ => None,
+    }
+}
+
+// Footnotes are numbered in the order they're first referenced
+fn number_for(id: &str, state: &mut RenderState) -> u64 {
+    let numbers = state.get_or_insert(NUMBERS_STATE, Value::Object(Map::new()));
+    let Some(numbers) = numbers.as_object_mut() else {
+        return 0;
+    };
+    if let Some(number) = numbers.get(id).and_then(|n| n.as_u64()) {
+        return number;
+    }
+    let number = numbers.len() as u64 + 1;
+    numbers.insert(id.to_string(), Value::from(number));
+    number
+}
+
+fn attrs(pairs: &[(&str, String)]) -> Map<String, Value> {
+    pairs
+        .iter()
+        .map(|(key, value)| (key.to_string(), Value::String(value.clone())))
+        .collect()
+}
+
+// `<sup><a href="#fn-id">n</a></sup>` pointing at the definition
+#[derive(Default)]
+pub struct FootnoteReferencePlugin;
+
+impl Plugin for FootnoteReferencePlugin {
+    fn render(&self, node: &Value, ctx: &mut RenderContext) -> Result<String, ProseMirrorError> {
+        let id = footnote_id(node.get("attrs").and_then(|attrs| attrs.as_object())).ok_or_else(
+            || ProseMirrorError::invalid_shape("footnoteReference needs an `id` attr"),
+        )?;
+        let number = number_for(&id, &mut ctx.state);
+
+        // Only the first reference gets the plain id the back-link points to
+        let references = ctx
+            .state
+            .get_or_insert(REFERENCES_STATE, Value::Object(Map::new()));
+        let seen = references
+            .get(&id)
+            .and_then(|seen| seen.as_u64())
+            .unwrap_or(0);
+        if let Some(references) = references.as_object_mut() {
+            references.insert(id.clone(), Value::from(seen + 1));
+        }
+        let reference_id = match seen {
+            0 => format!("fnref-{}", id),
+            seen => format!("fnref-{}-{}", id, seen + 1),
+        };
+
+        let link = ctx.tag("a", false).render_with_attrs(
+            number.to_string(),
+            Some(&attrs(&[
+                ("href", format!("#fn-{}", id)),
+                ("id", reference_id),
+            ])),
+        );
+        Ok(ctx
+            .tag_for("footnoteReference", "sup", false)
+            .render_with_attrs(link, Some(&attrs(&[("class", "footnote-ref".to_string())]))))
+    }
+
+    fn uses_state(&self) -> bool {
+        true
+    }
+
+    fn collect(&self, node: &Node, state: &mut RenderState) {
+        if let Some(id) = footnote_id(Some(&node.attrs)) {
+            number_for(&id, state);
+        }
+    }
+}
+
+impl FootnoteReferencePlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn type_name() -> &'static str {
+        "footnoteReference"
+    }
+
+    pub fn register(prosemirror: &mut ProseMirror) {
+        prosemirror.add_plugin(
+            "footnoteReference",
+            Box::new(FootnoteReferencePlugin::new()),
+        );
+    }
+}
+
+// Renders nothing in place, definitions end up in a `<section class="footnotes">`
+// at the end of the document
+#[derive(Default)]
+pub struct FootnotePlugin;
+
+impl Plugin for FootnotePlugin {
+    fn render(&self, node: &Value, ctx: &mut RenderContext) -> Result<String, ProseMirrorError> {
+        let id = footnote_id(node.get("attrs").and_then(|attrs| attrs.as_object()))
+            .ok_or_else(|| ProseMirrorError::invalid_shape("footnote needs an `id` attr"))?;
+        let html = ctx.render_content(node)?;
+        if let Some(definitions) = ctx
+            .state
+            .get_or_insert(DEFINITIONS_STATE, Value::Array(vec![]))
+            .as_array_mut()
+        {
+            definitions.push(Value::from(vec![id, html]));
+        }
+        Ok(String::new())
+    }
+
+    fn uses_state(&self) -> bool {
+        true
+    }
+
+    fn finish(
+        &self,
+        ctx: &mut RenderContext,
+        writer: &mut dyn fmt::Write,
+    ) -> Result<(), ProseMirrorError> {
+        let definitions = match ctx.state.remove(DEFINITIONS_STATE) {
+            Some(Value::Array(definitions)) if !definitions.is_empty() => definitions,
+            _ => return Ok(()),
+        };
+        let mut definitions: Vec<(u64, String, String)> = definitions
+            .into_iter()
+            .filter_map(|definition| {
+                let id = definition.get(0)?.as_str()?.to_string();
+                let html = definition.get(1)?.as_str()?.to_string();
+                Some((id, html))
+            })
+            .map(|(id, html)| (number_for(&id, &mut ctx.state), id, html))
+            .collect();
+        definitions.sort_by_key(|(number, _, _)| *number);
+
+        // Definitions nothing referenced have no `fnref-` anchor to link back to
+        let references = ctx.state.get(REFERENCES_STATE);
+        let mut items = String::new();
+        for (_, id, mut html) in definitions {
+            if references.is_some_and(|references| references.get(&id).is_some()) {
+                html.push_str(&ctx.tag("a", false).render_with_attrs(
+                    "↩".to_string(),
+                    Some(&attrs(&[
+                        ("class", "footnote-backref".to_string()),
+                        ("href", format!("#fnref-{}", id)),
+                    ])),
+                ));
+            }
+            items.push_str(
+                &ctx.tag("li", false)
+                    .render_with_attrs(html, Some(&attrs(&[("id", format!("fn-{}", id))]))),
+            );
+        }
+        let list = ctx.tag("ol", false).render_with_attrs(items, None);
+        writer.write_str(
+            &ctx.tag_for("footnote", "section", false)
+                .render_with_attrs(list, Some(&attrs(&[("class", "footnotes".to_string())]))),
+        )?;
+        Ok(())
+    }
+}
+
+impl FootnotePlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn type_name() -> &'static str {
+        "footnote"
+    }
+
+    pub fn register(prosemirror: &mut ProseMirror) {
+        prosemirror.add_plugin("footnote", Box::new(FootnotePlugin::new()));
+    }
+}
+
+// Numbering comes from the collect pass, so this turns it on
+pub fn register_footnote_plugins(prosemirror: &mut ProseMirror) {
+    FootnoteReferencePlugin::register(prosemirror);
+    FootnotePlugin::register(prosemirror);
+    prosemirror.options_mut().collect_pass = true;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_renders_footnotes_at_the_end() {
+        let content = json!({"type":"doc","content":[
+            {"type":"footnote","attrs":{"id":"cup"},"content":[{"type":"paragraph","content":[{"type":"text","text":"1967"}]}]},
+            {"type":"paragraph","content":[
+                {"type":"text","text":"Last cup"},
+                {"type":"footnoteReference","attrs":{"id":"cup"}},
+                {"type":"text","text":" and first pick"},
+                {"type":"footnoteReference","attrs":{"id":"pick"}},
+                {"type":"footnoteReference","attrs":{"id":"cup"}}
+            ]},
+            {"type":"footnote","attrs":{"id":"pick"},"content":[{"type":"paragraph","content":[{"type":"text","text":"Matthews"}]}]}
+        ]});
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        register_footnote_plugins(&mut prose_mirror);
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            concat!(
+                "<div><p>Last cup",
+                "<sup class=\"footnote-ref\"><a href=\"#fn-cup\" id=\"fnref-cup\">1</a></sup>",
+                " and first pick",
+                "<sup class=\"footnote-ref\"><a href=\"#fn-pick\" id=\"fnref-pick\">2</a></sup>",
+                "<sup class=\"footnote-ref\"><a href=\"#fn-cup\" id=\"fnref-cup-2\">1</a></sup>",
+                "</p>",
+                "<section class=\"footnotes\"><ol>",
+                "<li id=\"fn-cup\"><p>1967</p><a class=\"footnote-backref\" href=\"#fnref-cup\">↩</a></li>",
+                "<li id=\"fn-pick\"><p>Matthews</p><a class=\"footnote-backref\" href=\"#fnref-pick\">↩</a></li>",
+                "</ol></section></div>"
+            )
+        );
+    }
+
+    #[test]
+    fn it_skips_the_section_without_definitions() {
+        let content = json!({"type":"paragraph","content":[{"type":"text","text":"No notes"}]});
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        register_footnote_plugins(&mut prose_mirror);
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<p>No notes</p>".to_string()
+        );
+    }
+
+    #[test]
+    fn it_handles_odd_ids_and_unmatched_footnotes() {
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        register_footnote_plugins(&mut prose_mirror);
+
+        // Unreferenced definitions are numbered after the referenced ones and
+        // have no back-link, references without a definition don't link anywhere
+        let content = json!({"type":"doc","content":[
+            {"type":"footnote","attrs":{"id":"orphan"},"content":[{"type":"paragraph"}]},
+            {"type":"paragraph","content":[
+                {"type":"footnoteReference","attrs":{"id":"a\"b"}},
+                {"type":"footnoteReference","attrs":{"id":7}}
+            ]},
+            {"type":"footnote","attrs":{"id":7},"content":[{"type":"paragraph"}]}
+        ]});
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            concat!(
+                "<div><p>",
+                "<sup class=\"footnote-ref\"><a href=\"#fn-a&quot;b\" id=\"fnref-a&quot;b\">1</a></sup>",
+                "<sup class=\"footnote-ref\"><a href=\"#fn-7\" id=\"fnref-7\">2</a></sup>",
+                "</p>",
+                "<section class=\"footnotes\"><ol>",
+                "<li id=\"fn-7\"><p></p><a class=\"footnote-backref\" href=\"#fnref-7\">↩</a></li>",
+                "<li id=\"fn-orphan\"><p></p></li>",
+                "</ol></section></div>"
+            )
+        );
+
+        for attrs in [json!({"id":""}), json!({"id":null}), json!(null)] {
+            let content =
+                json!({"type":"paragraph","content":[{"type":"footnoteReference","attrs":attrs}]});
+            assert!(matches!(
+                prose_mirror.render(&content).unwrap_err(),
+                ProseMirrorError::InvalidNodeShape { .. }
+            ));
+        }
+    }
+}
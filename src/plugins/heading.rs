@@ -0,0 +1,209 @@
+use std::fmt;
+
+use serde_json::{Map, Value};
+
+use crate::error::ProseMirrorError;
+use crate::model::Node;
+use crate::slug::HeadingAnchors;
+use crate::utils::node_text;
+use crate::{ProseMirror, RenderContext, RenderState};
+
+use super::table_of_contents::collect_heading;
+use super::Plugin;
+
+// RenderState key holding the anchors handed out so far
+const ANCHORS_STATE: &str = "headingAnchors";
+
+const HEADING_TAGS: [&str; 6] = ["h1", "h2", "h3", "h4", "h5", "h6"];
+
+#[derive(Default)]
+pub struct HeadingPlugin;
+
+impl Plugin for HeadingPlugin {
+    fn render(&self, node: &Value, ctx: &mut RenderContext) -> Result<String, ProseMirrorError> {
+        let mut output = String::new();
+        self.render_to(node, ctx, &mut output)?;
+        Ok(output)
+    }
+
+    fn render_to(
+        &self,
+        node: &Value,
+        ctx: &mut RenderContext,
+        writer: &mut dyn fmt::Write,
+    ) -> Result<(), ProseMirrorError> {
+        let mut attrs = ctx.node_attrs("heading", node).unwrap_or_default();
+        let level = attrs.remove("level");
+        if let Some(anchors) = &ctx.options().heading_anchors {
+            Self::assign_anchor(anchors, node, &mut attrs, ctx);
+        }
+
+        let tag = ctx.tag_for(
+            "heading",
+            HEADING_TAGS[Self::level(level.as_ref()) - 1],
+            false,
+        );
+        tag.write_opening(Some(&attrs), writer)?;
+        ctx.render_content_to(node, writer)?;
+        tag.write_closing(writer)?;
+        Ok(())
+    }
+
+    fn collect(&self, node: &Node, state: &mut RenderState) {
+        collect_heading(node, state);
+        if let Some(id) = node.attrs.get("id").and_then(|id| id.as_str()) {
+            if let Some(used) = state
+                .get_or_insert(ANCHORS_STATE, Value::Object(Map::new()))
+                .as_object_mut()
+            {
+                used.insert(id.to_string(), Value::Bool(true));
+            }
+        }
+    }
+}
+
+impl HeadingPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    // Out of range levels are clamped into h1..h6, anything non-numeric becomes h1
+    pub fn level(level: Option<&Value>) -> usize {
+        level
+            .and_then(|level| level.as_f64())
+            .map(|level| level.clamp(1.0, 6.0) as usize)
+            .unwrap_or(1)
+    }
+
+    // Ids already set on the node are kept, and were reserved by `collect` so no
+    // generated anchor takes one
+    fn assign_anchor(
+        anchors: &HeadingAnchors,
+        node: &Value,
+        attrs: &mut Map<String, Value>,
+        ctx: &mut RenderContext,
+    ) {
+        let used = ctx
+            .state
+            .get_or_insert(ANCHORS_STATE, Value::Object(Map::new()));
+        let Some(used) = used.as_object_mut() else {
+            return;
+        };
+        match attrs.get("id").and_then(|id| id.as_str()) {
+            Some(id) => {
+                used.insert(id.to_string(), Value::Bool(true));
+            }
+            None => {
+                let anchor = anchors.anchor(&node_text(node), used);
+                attrs.insert("id".to_string(), Value::String(anchor));
+            }
+        }
+    }
+
+    pub fn type_name() -> &'static str {
+        "heading"
+    }
+
+    pub fn register(prosemirror: &mut ProseMirror) {
+        prosemirror.add_plugin("heading", Box::new(HeadingPlugin::new()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_renders_each_level() {
+        let prose_mirror = ProseMirror::with_default_plugins();
+
+        for level in 1..=6 {
+            assert_eq!(
+                prose_mirror.render(&json!({"type":"heading","attrs":{"level":level},"content":[{"type":"text","text":"Leafs"}]})).unwrap(),
+                format!("<h{level}>Leafs</h{level}>")
+            );
+        }
+    }
+
+    #[test]
+    fn it_clamps_out_of_range_levels() {
+        let prose_mirror = ProseMirror::with_default_plugins();
+
+        assert_eq!(
+            prose_mirror.render(&json!({"type":"heading","attrs":{"level":0},"content":[{"type":"text","text":"Leafs"}]})).unwrap(),
+            "<h1>Leafs</h1>".to_string()
+        );
+        assert_eq!(
+            prose_mirror.render(&json!({"type":"heading","attrs":{"level":12},"content":[{"type":"text","text":"Leafs"}]})).unwrap(),
+            "<h6>Leafs</h6>".to_string()
+        );
+        assert_eq!(
+            prose_mirror.render(&json!({"type":"heading","attrs":{"level":"big"},"content":[{"type":"text","text":"Leafs"}]})).unwrap(),
+            "<h1>Leafs</h1>".to_string()
+        );
+    }
+
+    #[test]
+    fn it_generates_unique_anchors() {
+        let prose_mirror = ProseMirror::builder()
+            .with_defaults()
+            .heading_anchors(HeadingAnchors::new())
+            .build();
+        let content = json!({"type":"doc","content":[
+            {"type":"heading","attrs":{"level":1},"content":[{"type":"text","text":"Game "},{"type":"text","text":"Recap","marks":[{"type":"bold"}]}]},
+            {"type":"heading","attrs":{"level":2,"id":"stats"},"content":[{"type":"text","text":"Stats"}]},
+            {"type":"heading","attrs":{"level":2},"content":[{"type":"text","text":"Stats"}]},
+            {"type":"heading","attrs":{"level":2},"content":[{"type":"text","text":"Game recap"}]}
+        ]});
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div><h1 id=\"game-recap\">Game <strong>Recap</strong></h1><h2 id=\"stats\">Stats</h2><h2 id=\"stats-1\">Stats</h2><h2 id=\"game-recap-1\">Game recap</h2></div>"
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn it_reserves_explicit_ids_set_further_down() {
+        let prose_mirror = ProseMirror::builder()
+            .with_defaults()
+            .heading_anchors(HeadingAnchors::new())
+            .build();
+        let content = json!({"type":"doc","content":[
+            {"type":"heading","attrs":{"level":1},"content":[{"type":"text","text":"Intro"}]},
+            {"type":"heading","attrs":{"level":2,"id":"intro"},"content":[{"type":"text","text":"Welcome"}]},
+            {"type":"heading","attrs":{"level":2},"content":[{"type":"text","text":"Intro"}]}
+        ]});
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div><h1 id=\"intro-1\">Intro</h1><h2 id=\"intro\">Welcome</h2><h2 id=\"intro-2\">Intro</h2></div>"
+        );
+        let slugs: Vec<String> = prose_mirror
+            .table_of_contents(&content)
+            .into_iter()
+            .map(|entry| entry.slug)
+            .collect();
+        assert_eq!(slugs, vec!["intro-1", "intro", "intro-2"]);
+    }
+
+    #[test]
+    fn it_handles_missing_attrs_and_empty_headings() {
+        let prose_mirror = ProseMirror::builder()
+            .with_defaults()
+            .heading_anchors(HeadingAnchors::new())
+            .build();
+        let content = json!({"type":"doc","content":[
+            {"type":"heading"},
+            {"type":"heading","attrs":{"level":2},"content":[{"type":"text","text":"<Leafs> & \"Habs\""}]},
+            {"type":"heading","attrs":{"level":3,"id":"a\"b"},"content":[{"type":"text","text":"!!"}]}
+        ]});
+
+        // Attr values are escaped, the slug falls back to `heading`
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div><h1 id=\"heading\"></h1><h2 id=\"leafs-habs\">&lt;Leafs&gt; &amp; &quot;Habs&quot;</h2><h3 id=\"a&quot;b\">!!</h3></div>"
+        );
+    }
+}
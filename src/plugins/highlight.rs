@@ -0,0 +1,99 @@
+use std::sync::OnceLock;
+
+use syntect::parsing::{ParseState, ScopeStackOp, SyntaxSet};
+use syntect::util::LinesWithEndings;
+
+use crate::utils::escape_html;
+
+// Scope prefixes to the classes highlight.js (and so lowlight, which Tiptap's
+// CodeBlockLowlight uses) gives the same tokens, more specific prefixes first
+const HLJS_CLASSES: [(&str, &str); 25] = [
+    ("comment", "hljs-comment"),
+    ("constant.character.escape", "hljs-char escape_"),
+    ("constant.numeric", "hljs-number"),
+    ("constant.language", "hljs-literal"),
+    ("constant.other", "hljs-variable constant_"),
+    ("entity.name.function", "hljs-title function_"),
+    ("entity.name.tag", "hljs-name"),
+    ("entity.name", "hljs-title class_"),
+    ("entity.other.attribute-name", "hljs-attr"),
+    (
+        "entity.other.inherited-class",
+        "hljs-title class_ inherited__",
+    ),
+    ("keyword.operator", "hljs-operator"),
+    ("keyword", "hljs-keyword"),
+    ("markup.bold", "hljs-strong"),
+    ("markup.heading", "hljs-section"),
+    ("markup.italic", "hljs-emphasis"),
+    ("markup.quote", "hljs-quote"),
+    ("meta.annotation", "hljs-meta"),
+    ("storage", "hljs-keyword"),
+    ("string.regexp", "hljs-regexp"),
+    ("string", "hljs-string"),
+    ("support.function", "hljs-built_in"),
+    ("support.macro", "hljs-built_in"),
+    ("support", "hljs-type"),
+    ("variable.language", "hljs-variable language_"),
+    ("variable.parameter", "hljs-params"),
+];
+
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAX_SET: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAX_SET.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn hljs_class(scope: &str) -> Option<&'static str> {
+    HLJS_CLASSES
+        .iter()
+        .find(|(prefix, _)| {
+            scope.starts_with(prefix)
+                && matches!(scope.as_bytes().get(prefix.len()), None | Some(b'.'))
+        })
+        .map(|(_, class)| *class)
+}
+
+// Escaped code with highlight.js-style spans, `None` when syntect doesn't know
+// the language so the caller can fall back to plain code
+pub(crate) fn highlight(code: &str, language: &str) -> Option<String> {
+    let syntax_set = syntax_set();
+    let syntax = syntax_set.find_syntax_by_token(language)?;
+    let mut state = ParseState::new(syntax);
+    let mut output = String::with_capacity(code.len() * 2);
+    // One entry per scope on the stack, the class of the span it opened if any
+    let mut spans: Vec<Option<&str>> = vec![];
+
+    for line in LinesWithEndings::from(code) {
+        let ops = state.parse_line(line, syntax_set).ok()?;
+        let mut position = 0;
+        for (index, op) in ops {
+            output.push_str(&escape_html(&line[position..index]));
+            position = index;
+            match op {
+                ScopeStackOp::Push(scope) => {
+                    let class = hljs_class(&scope.build_string());
+                    // Nested scopes of the same kind don't need another span
+                    let innermost = spans.iter().rev().find_map(|class| *class);
+                    let class = class.filter(|class| Some(*class) != innermost);
+                    if let Some(class) = class {
+                        output.push_str(&format!("<span class=\"{}\">", class));
+                    }
+                    spans.push(class);
+                }
+                ScopeStackOp::Pop(count) => {
+                    for _ in 0..count {
+                        if let Some(Some(_)) = spans.pop() {
+                            output.push_str("</span>");
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        output.push_str(&escape_html(&line[position..]));
+    }
+    for _ in spans.into_iter().flatten() {
+        output.push_str("</span>");
+    }
+    Some(output)
+}
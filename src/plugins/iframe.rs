@@ -0,0 +1,172 @@
+use serde_json::{Map, Value};
+
+use crate::error::ProseMirrorError;
+use crate::{ProseMirror, RenderContext};
+
+use super::Plugin;
+
+// Everything else on the node (`srcdoc`, event handlers...) is dropped
+const IFRAME_ATTRS: [&str; 8] = [
+    "src",
+    "width",
+    "height",
+    "title",
+    "allow",
+    "allowfullscreen",
+    "loading",
+    "referrerpolicy",
+];
+
+// `https://www.youtube.com/embed/x?t=1` -> `https://www.youtube.com`. URLs with
+// credentials or a non-http scheme have no origin worth trusting.
+pub(crate) fn url_origin(url: &str) -> Option<String> {
+    let (scheme, rest) = url.trim().split_once("://")?;
+    let scheme = scheme.to_ascii_lowercase();
+    if scheme != "https" && scheme != "http" {
+        return None;
+    }
+    let authority = rest.split(['/', '?', '#', '\\']).next()?;
+    if authority.is_empty() || authority.contains('@') {
+        return None;
+    }
+    Some(format!("{}://{}", scheme, authority.to_ascii_lowercase()))
+}
+
+// Only renders iframes whose `src` origin is allowlisted, anything else becomes an
+// empty placeholder, overridden as the `blockedEmbed` type
+#[derive(Default)]
+pub struct IframePlugin {
+    allowed_origins: Vec<String>,
+}
+
+impl Plugin for IframePlugin {
+    fn render(&self, node: &Value, ctx: &mut RenderContext) -> Result<String, ProseMirrorError> {
+        let mut attrs = ctx.node_attrs("iframe", node).unwrap_or_default();
+        attrs.retain(|key, _| IFRAME_ATTRS.contains(&key.as_str()));
+        ctx.sanitize_url_attr(&mut attrs, "src")?;
+
+        let allowed = attrs
+            .get("src")
+            .and_then(|src| src.as_str())
+            .and_then(url_origin)
+            .is_some_and(|origin| self.allowed_origins.contains(&origin));
+        if !allowed {
+            let mut placeholder = Map::new();
+            placeholder.insert("data-type".to_string(), Value::from("blockedEmbed"));
+            return Ok(ctx
+                .tag_for("blockedEmbed", "div", false)
+                .render_with_attrs(String::new(), Some(&placeholder)));
+        }
+
+        Ok(ctx
+            .tag_for("iframe", "iframe", false)
+            .render_with_attrs(String::new(), Some(&attrs)))
+    }
+}
+
+impl IframePlugin {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Origins are compared as `scheme://host[:port]`, e.g. `https://www.youtube.com`
+    pub fn with_allowed_origins(mut self, origins: &[&str]) -> Self {
+        self.allowed_origins = origins
+            .iter()
+            .map(|origin| origin.trim_end_matches('/').to_ascii_lowercase())
+            .collect();
+        self
+    }
+
+    pub fn type_name() -> &'static str {
+        "iframe"
+    }
+
+    pub fn register(prosemirror: &mut ProseMirror) {
+        prosemirror.add_plugin("iframe", Box::new(IframePlugin::new()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sanitize::UnsafeUrlAction;
+    use serde_json::json;
+
+    #[test]
+    fn it_parses_origins() {
+        assert_eq!(
+            url_origin("HTTPS://WWW.YouTube.com/embed/x?t=1"),
+            Some("https://www.youtube.com".to_string())
+        );
+        assert_eq!(
+            url_origin("https://localhost:8080"),
+            Some("https://localhost:8080".to_string())
+        );
+        assert_eq!(url_origin("https://www.youtube.com@evil.example/"), None);
+        assert_eq!(url_origin("javascript://www.youtube.com/%0aalert(1)"), None);
+        assert_eq!(url_origin("//www.youtube.com/embed/x"), None);
+    }
+
+    #[test]
+    fn it_renders_allowed_iframes() {
+        let content = json!({"type":"iframe","attrs":{"src":"https://www.youtube.com/embed/abc","width":560,"onload":"alert(1)","srcdoc":"<script>"}});
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        prose_mirror.add_plugin(
+            "iframe",
+            Box::new(IframePlugin::new().with_allowed_origins(&["https://www.youtube.com/"])),
+        );
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<iframe src=\"https://www.youtube.com/embed/abc\" width=\"560\"></iframe>".to_string()
+        );
+    }
+
+    #[test]
+    fn it_blocks_other_origins() {
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        prose_mirror.add_plugin(
+            "iframe",
+            Box::new(IframePlugin::new().with_allowed_origins(&["https://www.youtube.com/"])),
+        );
+
+        for attrs in [
+            json!({"src":"https://www.youtube.com.evil.example/embed"}),
+            json!({"src":"http://www.youtube.com/embed/abc"}),
+            json!({"src":null}),
+            json!({"src":42}),
+            json!(null),
+        ] {
+            let content = json!({"type":"iframe","attrs":attrs});
+            assert_eq!(
+                prose_mirror.render(&content).unwrap(),
+                "<div data-type=\"blockedEmbed\"></div>".to_string()
+            );
+        }
+    }
+
+    #[test]
+    fn it_follows_the_url_policy_before_the_allowlist() {
+        let content = json!({"type":"iframe","attrs":{"src":"javascript:alert(1)"}});
+        let mut prose_mirror = ProseMirror::new();
+        prose_mirror.add_plugin(
+            "iframe",
+            Box::new(IframePlugin::new().with_allowed_origins(&["https://www.youtube.com"])),
+        );
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div data-type=\"blockedEmbed\"></div>"
+        );
+
+        prose_mirror.options_mut().url_policy.action = UnsafeUrlAction::Reject;
+        assert_eq!(
+            prose_mirror.render(&content).unwrap_err(),
+            ProseMirrorError::UnsafeUrl {
+                path: String::new(),
+                url: "javascript:alert(1)".to_owned()
+            }
+        );
+    }
+}
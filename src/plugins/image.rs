@@ -0,0 +1,391 @@
+use serde_json::{Map, Value};
+
+use crate::error::ProseMirrorError;
+use crate::options::UrlRewriter;
+use crate::{ProseMirror, RenderContext};
+
+use super::Plugin;
+
+// Supplies the candidates of a responsive image's `srcset`, e.g. the widths an
+// image resizer can produce for `src`
+pub trait SrcsetProvider: Send + Sync {
+    fn srcset(&self, src: &str) -> Vec<(String, u32)>;
+
+    fn sizes(&self, _src: &str) -> Option<String> {
+        None
+    }
+}
+
+// The common case: one URL per width from a template closure
+pub struct SrcsetWidths<F> {
+    widths: Vec<u32>,
+    url: F,
+    sizes: Option<String>,
+}
+
+impl<F> SrcsetWidths<F>
+where
+    F: Fn(&str, u32) -> String + Send + Sync,
+{
+    pub fn new(widths: &[u32], url: F) -> Self {
+        Self {
+            widths: widths.to_vec(),
+            url,
+            sizes: None,
+        }
+    }
+
+    pub fn with_sizes(mut self, sizes: &str) -> Self {
+        self.sizes = Some(sizes.to_string());
+        self
+    }
+}
+
+impl<F> SrcsetProvider for SrcsetWidths<F>
+where
+    F: Fn(&str, u32) -> String + Send + Sync,
+{
+    fn srcset(&self, src: &str) -> Vec<(String, u32)> {
+        self.widths
+            .iter()
+            .map(|width| ((self.url)(src, *width), *width))
+            .collect()
+    }
+
+    fn sizes(&self, _src: &str) -> Option<String> {
+        self.sizes.clone()
+    }
+}
+
+pub struct ImagePlugin {
+    // Set on every image that doesn't specify its own, `None` leaves them off
+    loading: Option<String>,
+    decoding: Option<String>,
+    // Takes precedence over the renderer's `image_src_rewriter`
+    src_rewriter: Option<UrlRewriter>,
+    srcset_provider: Option<Box<dyn SrcsetProvider>>,
+    // Captions go in a <figure>, otherwise in a presentation table for email clients
+    figure: bool,
+    // Caption images by their `title` too, turned off it stays a tooltip
+    title_caption: bool,
+}
+
+impl Default for ImagePlugin {
+    fn default() -> Self {
+        Self {
+            loading: Some("lazy".to_string()),
+            decoding: Some("async".to_string()),
+            src_rewriter: None,
+            srcset_provider: None,
+            figure: true,
+            title_caption: true,
+        }
+    }
+}
+
+impl Plugin for ImagePlugin {
+    fn render(&self, node: &Value, ctx: &mut RenderContext) -> Result<String, ProseMirrorError> {
+        let mut attrs = ctx.node_attrs("image", node).unwrap_or_default();
+        ctx.sanitize_url_attr(&mut attrs, "src")?;
+        // The provider sees the original src, before any rewriting
+        if let Some(provider) = &self.srcset_provider {
+            Self::add_srcset(provider.as_ref(), &mut attrs);
+        }
+        let rewriter = self
+            .src_rewriter
+            .as_ref()
+            .or(ctx.options().image_src_rewriter.as_ref());
+        if let (Some(rewriter), Some(Value::String(src))) = (rewriter, attrs.get_mut("src")) {
+            *src = rewriter(src);
+        }
+
+        for dimension in ["width", "height"] {
+            if attrs
+                .get(dimension)
+                .is_some_and(|value| !Self::is_dimension(value))
+            {
+                attrs.remove(dimension);
+            }
+        }
+        for (key, default) in [("loading", &self.loading), ("decoding", &self.decoding)] {
+            if let Some(default) = default {
+                attrs
+                    .entry(key)
+                    .or_insert_with(|| Value::String(default.clone()));
+            }
+        }
+
+        let caption = self.take_caption(&mut attrs);
+        let img = ctx
+            .tag_for("image", "img", true)
+            .render_opening(Some(&attrs));
+        Ok(match caption {
+            Some(caption) if self.figure => {
+                let figcaption = ctx
+                    .tag("figcaption", false)
+                    .render_with_attrs(ctx.escape_text(&caption), None);
+                ctx.tag("figure", false)
+                    .render_with_attrs(img + &figcaption, None)
+            }
+            Some(caption) => {
+                let row = |content: String| {
+                    let td = ctx.tag("td", false).render_with_attrs(content, None);
+                    ctx.tag("tr", false).render_with_attrs(td, None)
+                };
+                let mut attrs = Map::new();
+                attrs.insert("role".to_string(), Value::from("presentation"));
+                ctx.tag("table", false)
+                    .render_with_attrs(row(img) + &row(ctx.escape_text(&caption)), Some(&attrs))
+            }
+            None => img,
+        })
+    }
+}
+
+impl ImagePlugin {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_loading(mut self, loading: Option<&str>) -> Self {
+        self.loading = loading.map(|loading| loading.to_string());
+        self
+    }
+
+    pub fn with_decoding(mut self, decoding: Option<&str>) -> Self {
+        self.decoding = decoding.map(|decoding| decoding.to_string());
+        self
+    }
+
+    pub fn with_src_rewriter<F>(mut self, rewriter: F) -> Self
+    where
+        F: Fn(&str) -> String + Send + Sync + 'static,
+    {
+        self.src_rewriter = Some(Box::new(rewriter));
+        self
+    }
+
+    pub fn with_figure(mut self, figure: bool) -> Self {
+        self.figure = figure;
+        self
+    }
+
+    pub fn with_title_caption(mut self, title_caption: bool) -> Self {
+        self.title_caption = title_caption;
+        self
+    }
+
+    pub fn with_srcset_provider(mut self, provider: Box<dyn SrcsetProvider>) -> Self {
+        self.srcset_provider = Some(provider);
+        self
+    }
+
+    // Leaves images that already come with their own srcset alone
+    fn add_srcset(provider: &dyn SrcsetProvider, attrs: &mut Map<String, Value>) {
+        if attrs.contains_key("srcset") {
+            return;
+        }
+        let Some(src) = attrs.get("src").and_then(|src| src.as_str()) else {
+            return;
+        };
+        let candidates = provider.srcset(src);
+        if candidates.is_empty() {
+            return;
+        }
+        let sizes = provider.sizes(src);
+        let srcset = candidates
+            .iter()
+            .map(|(url, width)| format!("{} {}w", url, width))
+            .collect::<Vec<_>>()
+            .join(", ");
+        attrs.insert("srcset".to_string(), Value::String(srcset));
+        if let Some(sizes) = sizes {
+            attrs.insert("sizes".to_string(), Value::String(sizes));
+        }
+    }
+
+    // Plain pixel counts, either as numbers or numeric strings
+    fn is_dimension(value: &Value) -> bool {
+        match value {
+            Value::Number(number) => number.as_u64().is_some(),
+            Value::String(value) => !value.is_empty() && value.chars().all(|c| c.is_ascii_digit()),
+            _ => false,
+        }
+    }
+
+    // A `caption` always goes in a <figcaption>, `title` unless `title_caption` is off
+    fn take_caption(&self, attrs: &mut Map<String, Value>) -> Option<String> {
+        let caption = attrs.remove("caption");
+        let title = match self.title_caption {
+            true => attrs.remove("title"),
+            false => None,
+        };
+        [caption, title]
+            .into_iter()
+            .flatten()
+            .filter_map(|caption| caption.as_str().map(|caption| caption.to_string()))
+            .find(|caption| !caption.is_empty())
+    }
+
+    pub fn type_name() -> &'static str {
+        "image"
+    }
+
+    pub fn register(prosemirror: &mut ProseMirror) {
+        prosemirror.add_plugin("image", Box::new(ImagePlugin::new()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sanitize::UnsafeUrlAction;
+    use crate::TagOverrides;
+    use serde_json::json;
+
+    #[test]
+    fn it_strips_unsafe_src() {
+        let content = json!({"type":"image","attrs":{"alt":"x","src":"javascript:alert(1)"}});
+        let prose_mirror = ProseMirror::with_default_plugins();
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<img alt=\"x\" decoding=\"async\" loading=\"lazy\" />".to_string()
+        );
+    }
+
+    #[test]
+    fn it_rejects_unsafe_src() {
+        let content = json!({"type":"image","attrs":{"src":"data:text/html;base64,AAAA"}});
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        prose_mirror.options_mut().url_policy.action = UnsafeUrlAction::Reject;
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap_err(),
+            ProseMirrorError::UnsafeUrl {
+                path: String::new(),
+                url: "data:text/html;base64,AAAA".to_owned()
+            }
+        );
+    }
+
+    #[test]
+    fn it_keeps_valid_dimensions() {
+        let content =
+            json!({"type":"image","attrs":{"src":"/rink.png","width":640,"height":"100%"}});
+        let prose_mirror = ProseMirror::with_default_plugins();
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<img decoding=\"async\" loading=\"lazy\" src=\"/rink.png\" width=\"640\" />"
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn it_wraps_captioned_images_in_a_figure() {
+        let content =
+            json!({"type":"image","attrs":{"src":"/rink.png","title":"Scotiabank <Arena>"}});
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        prose_mirror.add_plugin(
+            "image",
+            Box::new(
+                ImagePlugin::new()
+                    .with_loading(None)
+                    .with_decoding(Some("sync")),
+            ),
+        );
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<figure><img decoding=\"sync\" src=\"/rink.png\" /><figcaption>Scotiabank &lt;Arena&gt;</figcaption></figure>".to_string()
+        );
+
+        // Without title captions only `caption` makes a figure, titles stay tooltips
+        prose_mirror.add_plugin(
+            "image",
+            Box::new(
+                ImagePlugin::new()
+                    .with_loading(None)
+                    .with_decoding(None)
+                    .with_title_caption(false),
+            ),
+        );
+        let content = json!({"type":"doc","content":[
+            {"type":"image","attrs":{"src":"/rink.png","title":"Scotiabank <Arena>"}},
+            {"type":"image","attrs":{"src":"/rink.png","caption":"Rink","title":"Home"}}
+        ]});
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div><img src=\"/rink.png\" title=\"Scotiabank &lt;Arena&gt;\" />\
+             <figure><img src=\"/rink.png\" title=\"Home\" /><figcaption>Rink</figcaption></figure></div>"
+        );
+    }
+
+    #[test]
+    fn it_follows_tag_overrides() {
+        let content = json!({"type":"doc","content":[
+            {"type":"image","attrs":{"src":"/rink.png"}},
+            {"type":"image","attrs":{"src":"/rink.png","title":"Rink"}}
+        ]});
+        let prose_mirror = ProseMirror::builder()
+            .with_defaults()
+            .tag_overrides(TagOverrides::new().with_tag("image", "amp-img"))
+            .build();
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div><amp-img decoding=\"async\" loading=\"lazy\" src=\"/rink.png\" />\
+             <figure><amp-img decoding=\"async\" loading=\"lazy\" src=\"/rink.png\" />\
+             <figcaption>Rink</figcaption></figure></div>"
+        );
+    }
+
+    #[test]
+    fn it_rewrites_src() {
+        let content = json!({"type":"doc","content":[
+            {"type":"image","attrs":{"src":"/rink.png"}},
+            {"type":"image","attrs":{"src":"javascript:alert(1)"}}
+        ]});
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        prose_mirror.set_src_rewriter(|src| format!("https://cdn.example.com/w_800{}", src));
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div><img decoding=\"async\" loading=\"lazy\" src=\"https://cdn.example.com/w_800/rink.png\" /><img decoding=\"async\" loading=\"lazy\" /></div>".to_string()
+        );
+
+        prose_mirror.add_plugin(
+            "image",
+            Box::new(ImagePlugin::new().with_src_rewriter(|src| src.replace(".png", ".webp"))),
+        );
+        assert!(prose_mirror
+            .render(&content)
+            .unwrap()
+            .contains("src=\"/rink.webp\""));
+    }
+
+    #[test]
+    fn it_adds_srcset_and_sizes() {
+        let content =
+            json!({"type":"image","attrs":{"src":"https://pbs.twimg.com/media/F4Pr.jpg"}});
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        prose_mirror.add_plugin(
+            "image",
+            Box::new(
+                ImagePlugin::new()
+                    .with_loading(None)
+                    .with_decoding(None)
+                    .with_srcset_provider(Box::new(
+                        SrcsetWidths::new(&[320, 640], |src, width| format!("{}?w={}", src, width))
+                            .with_sizes("(max-width: 640px) 100vw, 640px"),
+                    )),
+            ),
+        );
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<img sizes=\"(max-width: 640px) 100vw, 640px\" src=\"https://pbs.twimg.com/media/F4Pr.jpg\" srcset=\"https://pbs.twimg.com/media/F4Pr.jpg?w=320 320w, https://pbs.twimg.com/media/F4Pr.jpg?w=640 640w\" />".to_string()
+        );
+    }
+}
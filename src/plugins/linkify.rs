@@ -0,0 +1,213 @@
+// Finds the bare URLs, email addresses and `#tag`/`@handle` style patterns in
+// plain text, for `TextPlugin`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Link {
+    pub start: usize,
+    pub end: usize,
+    pub href: String,
+    pub class: Option<String>,
+}
+
+const URL_PREFIXES: [&str; 3] = ["https://", "http://", "www."];
+
+// Gets the name after the prefix, `None` leaves it as plain text, e.g. for a
+// handle that isn't a user
+pub type LinkBuilder = Box<dyn Fn(&str) -> Option<String> + Send + Sync>;
+
+// A prefix followed by letters, digits and underscores, like `#topic` or `@user`
+pub struct LinkPattern {
+    prefix: char,
+    url: LinkBuilder,
+    class: Option<String>,
+}
+
+impl LinkPattern {
+    pub fn new<F>(prefix: char, url: F) -> Self
+    where
+        F: Fn(&str) -> Option<String> + Send + Sync + 'static,
+    {
+        Self {
+            prefix,
+            url: Box::new(url),
+            class: None,
+        }
+    }
+
+    pub fn with_class(mut self, class: &str) -> Self {
+        self.class = Some(class.to_string());
+        self
+    }
+
+    // The length of the match at the start of `text` and the link it makes.
+    // Names that are only digits don't count, `#1` is rarely a tag.
+    fn link_at(&self, text: &str) -> Option<(usize, String)> {
+        let name = text.strip_prefix(self.prefix)?;
+        let name = &name[..name
+            .find(|c: char| !c.is_alphanumeric() && c != '_')
+            .unwrap_or(name.len())];
+        if name.is_empty() || name.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let href = (self.url)(name)?;
+        Some((self.prefix.len_utf8() + name.len(), href))
+    }
+}
+
+pub(crate) fn find_links(text: &str, urls: bool, patterns: &[LinkPattern]) -> Vec<Link> {
+    let mut links = Vec::new();
+    let mut offset = 0;
+    for word in text.split_inclusive(char::is_whitespace) {
+        find_in_word(word.trim_end(), offset, urls, patterns, &mut links);
+        offset += word.len();
+    }
+    links
+}
+
+fn find_in_word(
+    word: &str,
+    offset: usize,
+    urls: bool,
+    patterns: &[LinkPattern],
+    links: &mut Vec<Link>,
+) {
+    // Where the last link ended, an email can't start inside a URL
+    let mut covered = 0;
+    let mut chars = word.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        let at_boundary = word[..index]
+            .chars()
+            .next_back()
+            .is_none_or(|prev| !prev.is_alphanumeric() && !"./@-_".contains(prev));
+        if at_boundary {
+            let matched = patterns
+                .iter()
+                .find_map(|pattern| Some((pattern.link_at(&word[index..])?, pattern)));
+            if let Some(((len, href), pattern)) = matched {
+                links.push(Link {
+                    start: offset + index,
+                    end: offset + index + len,
+                    href,
+                    class: pattern.class.clone(),
+                });
+                covered = index + len;
+                while chars.peek().is_some_and(|(next, _)| *next < covered) {
+                    chars.next();
+                }
+                continue;
+            }
+        }
+        if at_boundary && urls {
+            if let Some(len) = url_len(&word[index..]) {
+                let url = &word[index..index + len];
+                let href = match url.get(..4) {
+                    Some(prefix) if prefix.eq_ignore_ascii_case("www.") => {
+                        format!("https://{}", url)
+                    }
+                    _ => url.to_string(),
+                };
+                links.push(Link {
+                    start: offset + index,
+                    end: offset + index + len,
+                    href,
+                    class: None,
+                });
+                covered = index + len;
+                while chars.peek().is_some_and(|(next, _)| *next < covered) {
+                    chars.next();
+                }
+                continue;
+            }
+        }
+        if urls && c == '@' {
+            if let Some((start, end)) = email_bounds(word, index, covered) {
+                links.push(Link {
+                    start: offset + start,
+                    end: offset + end,
+                    href: format!("mailto:{}", &word[start..end]),
+                    class: None,
+                });
+                covered = end;
+                while chars.peek().is_some_and(|(next, _)| *next < covered) {
+                    chars.next();
+                }
+            }
+        }
+    }
+}
+
+// The length of the URL at the start of `text`, without the punctuation that
+// usually follows one in a sentence
+fn url_len(text: &str) -> Option<usize> {
+    let prefix = URL_PREFIXES.iter().find(|prefix| {
+        text.get(..prefix.len())
+            .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+    })?;
+    let mut url = &text[..text.find(['<', '>', '"']).unwrap_or(text.len())];
+    loop {
+        let trimmed = url.trim_end_matches(['.', ',', ';', ':', '!', '?', '\'']);
+        let trimmed = match trimmed.strip_suffix(')') {
+            Some(inner) if inner.matches('(').count() < trimmed.matches(')').count() => inner,
+            _ => trimmed,
+        };
+        if trimmed.len() == url.len() {
+            break;
+        }
+        url = trimmed;
+    }
+    let host = url.get(prefix.len()..)?.split(['/', '?', '#']).next()?;
+    let has_host = !host.is_empty() && (*prefix != "www." || host.contains('.'));
+    has_host.then_some(url.len())
+}
+
+// The address around the `@` at `at`, starting no earlier than `min_start`
+fn email_bounds(word: &str, at: usize, min_start: usize) -> Option<(usize, usize)> {
+    let is_local = |c: char| c.is_ascii_alphanumeric() || "._%+-".contains(c);
+    let is_domain = |c: char| c.is_ascii_alphanumeric() || c == '.' || c == '-';
+
+    let local_len: usize = word[min_start..at]
+        .chars()
+        .rev()
+        .take_while(|c| is_local(*c))
+        .map(char::len_utf8)
+        .sum();
+    let start = at - local_len;
+    let local = word[start..at].trim_start_matches('.');
+    let start = at - local.len();
+
+    let domain = &word[at + 1..];
+    let domain = &domain[..domain.find(|c| !is_domain(c)).unwrap_or(domain.len())];
+    let domain = domain.trim_end_matches(['.', '-']);
+    let tld = domain.rsplit('.').next().unwrap_or_default();
+    let valid = !local.is_empty()
+        && domain.contains('.')
+        && !domain.starts_with(['.', '-'])
+        && !domain.contains("..")
+        && tld.len() >= 2
+        && tld.chars().all(|c| c.is_ascii_alphabetic());
+    valid.then_some((start, at + 1 + domain.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hrefs(text: &str) -> Vec<(&str, String)> {
+        find_links(text, true, &[])
+            .into_iter()
+            .map(|link| (&text[link.start..link.end], link.href))
+            .collect()
+    }
+
+    #[test]
+    fn it_finds_urls_and_emails() {
+        assert_eq!(
+            hrefs("go to WWW.nhl.com! or <http://a.io/x_(y)>, me@a.b.co, nope@x foo.www.bar.com"),
+            vec![
+                ("WWW.nhl.com", "https://WWW.nhl.com".to_string()),
+                ("http://a.io/x_(y)", "http://a.io/x_(y)".to_string()),
+                ("me@a.b.co", "mailto:me@a.b.co".to_string()),
+            ]
+        );
+        assert!(hrefs("https:// and www. and a@b and ünï@x.com").is_empty());
+    }
+}
@@ -0,0 +1,109 @@
+use serde_json::{Map, Value};
+
+use crate::error::ProseMirrorError;
+use crate::plugins::MarkPlugin;
+use crate::utils::merge_attributes;
+use crate::{ProseMirror, RenderContext};
+
+const ID_ATTRS: [&str; 2] = ["commentId", "threadId"];
+
+type CommentResolver = Box<dyn Fn(&str) -> bool + Send + Sync>;
+
+// Comment thread marks, `{"type":"comment","attrs":{"commentId":"..."}}`. Different
+// comment extensions name the id differently, so `threadId` works as well.
+#[derive(Default)]
+pub struct CommentMarkPlugin {
+    resolver: Option<CommentResolver>,
+}
+
+impl MarkPlugin for CommentMarkPlugin {
+    fn render(
+        &self,
+        output: String,
+        mark: &Value,
+        ctx: &mut RenderContext,
+    ) -> Result<String, ProseMirrorError> {
+        let mut attrs = ctx.node_attrs("comment", mark).unwrap_or_default();
+        let ids: Vec<Value> = ID_ATTRS
+            .iter()
+            .filter_map(|key| attrs.remove(*key))
+            .collect();
+        let id = ids.into_iter().find_map(|id| match id {
+            Value::String(id) if id.is_empty() => None,
+            Value::String(id) => Some(id),
+            Value::Null => None,
+            id => Some(id.to_string()),
+        });
+        let Some(id) = id else {
+            return Ok(output);
+        };
+        if let Some(resolver) = &self.resolver {
+            if !resolver(&id) {
+                return Ok(output);
+            }
+        }
+
+        let mut extra = Map::new();
+        extra.insert("data-comment-id".to_string(), Value::from(id));
+        merge_attributes(&mut attrs, &extra);
+        Ok(ctx
+            .tag_for("comment", "span", false)
+            .render_with_attrs(output, Some(&attrs)))
+    }
+}
+
+impl CommentMarkPlugin {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Called with each thread id, returning false drops the wrapper and keeps the
+    // text, e.g. for threads that were resolved or deleted
+    pub fn with_resolver<F>(resolver: F) -> Self
+    where
+        F: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        Self {
+            resolver: Some(Box::new(resolver)),
+        }
+    }
+
+    pub fn type_name() -> &'static str {
+        "comment"
+    }
+
+    pub fn register(prosemirror: &mut ProseMirror) {
+        prosemirror.add_mark_plugin("comment", Box::new(CommentMarkPlugin::new()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_renders_comment_threads() {
+        let content = json!({"type":"paragraph","content":[
+            {"type":"text","text":"Go","marks":[{"type":"comment","attrs":{"commentId":"open"}}]},
+            {"type":"text","text":" "},
+            {"type":"text","text":"Leafs","marks":[{"type":"comment","attrs":{"threadId":"resolved"}}]}
+        ]});
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        CommentMarkPlugin::register(&mut prose_mirror);
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<p><span data-comment-id=\"open\">Go</span> <span data-comment-id=\"resolved\">Leafs</span></p>"
+        );
+
+        prose_mirror.add_mark_plugin(
+            "comment",
+            Box::new(CommentMarkPlugin::with_resolver(|id| id == "open")),
+        );
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<p><span data-comment-id=\"open\">Go</span> Leafs</p>"
+        );
+    }
+}
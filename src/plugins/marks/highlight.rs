@@ -0,0 +1,137 @@
+use serde_json::{Map, Value};
+
+use crate::error::ProseMirrorError;
+use crate::options::StyleStrategy;
+use crate::plugins::MarkPlugin;
+use crate::sanitize::is_safe_css_value;
+use crate::utils::merge_attributes;
+use crate::{ProseMirror, RenderContext};
+
+// Tiptap's Highlight mark. With `multicolor` the color is both exposed as
+// `data-color` and applied inline, the same way Tiptap renders it.
+#[derive(Default)]
+pub struct HighlightMarkPlugin;
+
+impl MarkPlugin for HighlightMarkPlugin {
+    fn render(
+        &self,
+        output: String,
+        mark: &Value,
+        ctx: &mut RenderContext,
+    ) -> Result<String, ProseMirrorError> {
+        let mut attrs = ctx.node_attrs("highlight", mark).unwrap_or_default();
+        let color = attrs.remove("color");
+        if let Some(color) = color
+            .as_ref()
+            .and_then(|color| color.as_str())
+            .filter(|color| is_safe_css_value(color))
+        {
+            attrs.insert("data-color".to_string(), Value::from(color));
+            let mut extra = Map::new();
+            match &ctx.options().style_strategy {
+                StyleStrategy::Inline => {
+                    let style = format!("background-color: {}; color: inherit", color);
+                    extra.insert("style".to_string(), Value::from(style));
+                }
+                StyleStrategy::Classes(classes) => {
+                    if let Some(class) = classes.highlight_class(color) {
+                        extra.insert("class".to_string(), Value::from(class));
+                    }
+                }
+            }
+            merge_attributes(&mut attrs, &extra);
+        }
+
+        Ok(ctx
+            .tag_for("highlight", "mark", false)
+            .render_with_attrs(output, Some(&attrs)))
+    }
+}
+
+impl HighlightMarkPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn type_name() -> &'static str {
+        "highlight"
+    }
+
+    pub fn register(prosemirror: &mut ProseMirror) {
+        prosemirror.add_mark_plugin("highlight", Box::new(HighlightMarkPlugin::new()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::StyleClasses;
+    use serde_json::json;
+
+    #[test]
+    fn it_renders_highlight_colors() {
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        HighlightMarkPlugin::register(&mut prose_mirror);
+
+        assert_eq!(
+            prose_mirror
+                .render(&json!({"type":"text","text":"Leafs","marks":[{"type":"highlight","attrs":{"color":null}}]}))
+                .unwrap(),
+            "<mark>Leafs</mark>".to_string()
+        );
+        assert_eq!(
+            prose_mirror.render(&json!({"type":"text","text":"Leafs","marks":[{"type":"highlight","attrs":{"color":"#00205b"}}]})).unwrap(),
+            "<mark data-color=\"#00205b\" style=\"background-color: #00205b; color: inherit\">Leafs</mark>"
+                .to_string()
+        );
+        assert_eq!(
+            prose_mirror
+                .render(&json!({"type":"text","text":"Leafs","marks":[{"type":"highlight","attrs":{"color":"red;position:fixed"}}]}))
+                .unwrap(),
+            "<mark>Leafs</mark>".to_string()
+        );
+    }
+
+    #[test]
+    fn it_renders_highlights_and_alignment_as_classes() {
+        let mut prose_mirror = ProseMirror::builder()
+            .with_defaults()
+            .style_strategy(StyleStrategy::Classes(
+                StyleClasses::new()
+                    .with_highlight_class("#FEF08A", "hl-yellow")
+                    .with_text_align_class("center", "mx-auto"),
+            ))
+            .build();
+        HighlightMarkPlugin::register(&mut prose_mirror);
+
+        let content = json!({"type":"paragraph","attrs":{"textAlign":"center"},"content":[
+            {"type":"text","text":"Leafs","marks":[{"type":"highlight","attrs":{"color":"#fef08a"}}]},
+            {"type":"text","text":"Leafs","marks":[{"type":"highlight","attrs":{"color":"Pink"}}]},
+            {"type":"text","text":"Leafs","marks":[{"type":"highlight","attrs":{"color":"#00205b"}}]}
+        ]});
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<p class=\"mx-auto\"><mark class=\"hl-yellow\" data-color=\"#fef08a\">Leafs</mark><mark class=\"hl-pink\" data-color=\"Pink\">Leafs</mark><mark data-color=\"#00205b\">Leafs</mark></p>"
+        );
+    }
+
+    #[test]
+    fn it_ignores_missing_and_non_string_colors() {
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        HighlightMarkPlugin::register(&mut prose_mirror);
+
+        for mark in [
+            json!({"type":"highlight"}),
+            json!({"type":"highlight","attrs":null}),
+            json!({"type":"highlight","attrs":{"color":7}}),
+            json!({"type":"highlight","attrs":{"color":"\"><script>"}}),
+        ] {
+            assert_eq!(
+                prose_mirror
+                    .render(&json!({"type":"text","text":"<Leafs>","marks":[mark]}))
+                    .unwrap(),
+                "<mark>&lt;Leafs&gt;</mark>"
+            );
+        }
+    }
+}
@@ -0,0 +1,261 @@
+use serde_json::{Map, Value};
+
+use crate::error::ProseMirrorError;
+use crate::plugins::MarkPlugin;
+use crate::{ProseMirror, RenderContext};
+
+const LINK_ATTRS: [&str; 4] = ["href", "target", "rel", "class"];
+
+pub struct LinkMarkPlugin {
+    // Make sure `target="_blank"` links can't reach back into the opener
+    enforce_noopener: bool,
+}
+
+impl Default for LinkMarkPlugin {
+    fn default() -> Self {
+        Self {
+            enforce_noopener: true,
+        }
+    }
+}
+
+impl MarkPlugin for LinkMarkPlugin {
+    fn render(
+        &self,
+        output: String,
+        mark: &Value,
+        ctx: &mut RenderContext,
+    ) -> Result<String, ProseMirrorError> {
+        let mut attrs = Map::new();
+        if let Some(mark_attrs) = ctx.node_attrs("link", mark) {
+            for (key, value) in mark_attrs {
+                if LINK_ATTRS.contains(&key.as_str()) && !value.is_null() {
+                    attrs.insert(key, value);
+                }
+            }
+        }
+
+        ctx.sanitize_url_attr(&mut attrs, "href")?;
+        if let (Some(rewriter), Some(Value::String(href))) =
+            (&ctx.options().link_rewriter, attrs.get_mut("href"))
+        {
+            *href = rewriter(href);
+        }
+
+        if let Some(external_links) = &ctx.options().external_links {
+            let is_external = attrs
+                .get("href")
+                .and_then(|href| href.as_str())
+                .is_some_and(|href| external_links.is_external(href));
+            if is_external {
+                attrs.insert("target".to_string(), Value::String("_blank".to_string()));
+                add_tokens(&mut attrs, "rel", external_links.rel());
+                if let Some(class) = external_links.class() {
+                    add_tokens(&mut attrs, "class", &[class.to_string()]);
+                }
+            }
+        }
+
+        let opens_new_window = attrs.get("target").and_then(|t| t.as_str()) == Some("_blank");
+        if self.enforce_noopener && opens_new_window {
+            add_tokens(
+                &mut attrs,
+                "rel",
+                &["noopener".to_string(), "noreferrer".to_string()],
+            );
+        }
+
+        Ok(ctx
+            .tag_for("link", "a", false)
+            .render_with_attrs(output, Some(&attrs)))
+    }
+}
+
+// Appends whichever of `tokens` the space separated attr doesn't have yet
+fn add_tokens(attrs: &mut Map<String, Value>, attr: &str, tokens: &[String]) {
+    let mut values: Vec<String> = attrs
+        .get(attr)
+        .and_then(|value| value.as_str())
+        .map(|value| value.split_whitespace().map(|v| v.to_string()).collect())
+        .unwrap_or_default();
+    for token in tokens {
+        if !values.iter().any(|value| value.eq_ignore_ascii_case(token)) {
+            values.push(token.clone());
+        }
+    }
+    attrs.insert(attr.to_string(), Value::String(values.join(" ")));
+}
+
+impl LinkMarkPlugin {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_enforce_noopener(mut self, enforce_noopener: bool) -> Self {
+        self.enforce_noopener = enforce_noopener;
+        self
+    }
+
+    pub fn type_name() -> &'static str {
+        "link"
+    }
+
+    pub fn register(prosemirror: &mut ProseMirror) {
+        prosemirror.add_mark_plugin("link", Box::new(LinkMarkPlugin::new()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::TextPlugin;
+    use crate::ExternalLinks;
+    use serde_json::json;
+
+    #[test]
+    fn it_renders_href() {
+        let prose_mirror = ProseMirror::with_default_plugins();
+
+        assert_eq!(
+            prose_mirror
+                .render(&json!({"type":"text","text":"Leafs","marks":[{"type":"link","attrs":{"href":"https://nhl.com","target":null,"rel":null,"class":null}}]}))
+                .unwrap(),
+            "<a href=\"https://nhl.com\">Leafs</a>".to_string()
+        );
+    }
+
+    #[test]
+    fn it_rewrites_hrefs() {
+        let prose_mirror = ProseMirror::builder()
+            .with_defaults()
+            .link_rewriter(|href| match href.strip_prefix('/') {
+                Some(path) => format!("https://leafs.com/{}", path),
+                None => href.split('?').next().unwrap_or(href).to_string(),
+            })
+            .build();
+
+        assert_eq!(
+            prose_mirror
+                .render(&json!({"type":"text","text":"Leafs","marks":[{"type":"link","attrs":{"href":"/roster"}}]}))
+                .unwrap(),
+            "<a href=\"https://leafs.com/roster\">Leafs</a>"
+        );
+        assert_eq!(
+            prose_mirror
+                .render(&json!({"type":"text","text":"Leafs","marks":[{"type":"link","attrs":{"href":"https://nhl.com/?utm_source=x"}}]}))
+                .unwrap(),
+            "<a href=\"https://nhl.com/\">Leafs</a>"
+        );
+        assert_eq!(
+            prose_mirror
+                .render(&json!({"type":"text","text":"Leafs","marks":[{"type":"link","attrs":{"href":"javascript:alert(1)"}}]}))
+                .unwrap(),
+            "<a>Leafs</a>"
+        );
+    }
+
+    #[test]
+    fn it_enforces_noopener_on_blank_targets() {
+        let prose_mirror = ProseMirror::with_default_plugins();
+
+        assert_eq!(
+            prose_mirror
+                .render(&json!({"type":"text","text":"Leafs","marks":[{"type":"link","attrs":{"href":"https://nhl.com","target":"_blank","rel":"nofollow"}}]}))
+                .unwrap(),
+            "<a href=\"https://nhl.com\" rel=\"nofollow noopener noreferrer\" target=\"_blank\">Leafs</a>"
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn it_decorates_external_links() {
+        let prose_mirror = ProseMirror::builder()
+            .with_defaults()
+            .external_links(ExternalLinks::new("https://leafs.com").with_class("external"))
+            .build();
+
+        assert_eq!(
+            prose_mirror
+                .render(&json!({"type":"text","text":"Leafs","marks":[{"type":"link","attrs":{"href":"https://nhl.com/scores","class":"cta"}}]}))
+                .unwrap(),
+            "<a class=\"cta external\" href=\"https://nhl.com/scores\" rel=\"nofollow ugc noopener noreferrer\" target=\"_blank\">Leafs</a>"
+        );
+        for internal in [
+            "/roster",
+            "https://LEAFS.com:443/tickets",
+            "mailto:hi@nhl.com",
+        ] {
+            assert_eq!(
+                prose_mirror
+                    .render(&json!({"type":"text","text":"Leafs","marks":[{"type":"link","attrs":{ "href": internal }}]}))
+                    .unwrap(),
+                format!("<a href=\"{}\">Leafs</a>", internal)
+            );
+        }
+    }
+
+    #[test]
+    fn it_can_disable_noopener_enforcement() {
+        let mut prose_mirror = ProseMirror::new();
+        TextPlugin::register(&mut prose_mirror);
+        prose_mirror.add_mark_plugin(
+            "link",
+            Box::new(LinkMarkPlugin::new().with_enforce_noopener(false)),
+        );
+
+        assert_eq!(
+            prose_mirror
+                .render(&json!({"type":"text","text":"Leafs","marks":[{"type":"link","attrs":{"href":"https://nhl.com","target":"_blank"}}]}))
+                .unwrap(),
+            "<a href=\"https://nhl.com\" target=\"_blank\">Leafs</a>".to_string()
+        );
+    }
+
+    #[test]
+    fn it_strips_javascript_href() {
+        let prose_mirror = ProseMirror::with_default_plugins();
+
+        assert_eq!(
+            prose_mirror
+                .render(&json!({"type":"text","text":"Leafs","marks":[{"type":"link","attrs":{"href":"javascript:alert(document.cookie)"}}]}))
+                .unwrap(),
+            "<a>Leafs</a>".to_string()
+        );
+    }
+
+    #[test]
+    fn it_drops_unknown_attrs() {
+        let prose_mirror = ProseMirror::with_default_plugins();
+
+        assert_eq!(
+            prose_mirror
+                .render(&json!({"type":"text","text":"Leafs","marks":[{"type":"link","attrs":{"href":"https://nhl.com","onclick":"alert(1)"}}]}))
+                .unwrap(),
+            "<a href=\"https://nhl.com\">Leafs</a>".to_string()
+        );
+    }
+
+    #[test]
+    fn it_handles_missing_and_odd_hrefs() {
+        let prose_mirror = ProseMirror::with_default_plugins();
+
+        assert_eq!(
+            prose_mirror
+                .render(&json!({"type":"text","text":"Leafs","marks":[{"type":"link"}]}))
+                .unwrap(),
+            "<a>Leafs</a>"
+        );
+        assert_eq!(
+            prose_mirror
+                .render(&json!({"type":"text","text":"Leafs","marks":[{"type":"link","attrs":{"href":"/a\"b?x=1&y=<2>","target":"_self"}}]}))
+                .unwrap(),
+            "<a href=\"/a&quot;b?x=1&amp;y=&lt;2&gt;\" target=\"_self\">Leafs</a>"
+        );
+        assert_eq!(
+            prose_mirror
+                .render(&json!({"type":"text","text":"Leafs","marks":[{"type":"link","attrs":{"href":"  JaVaScRiPt:alert(1)","target":"_blank"}}]}))
+                .unwrap(),
+            "<a rel=\"noopener noreferrer\" target=\"_blank\">Leafs</a>"
+        );
+    }
+}
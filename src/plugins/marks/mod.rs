@@ -0,0 +1,137 @@
+use serde_json::Value;
+
+use crate::error::ProseMirrorError;
+use crate::plugins::{MarkPlugin, Tag};
+use crate::{ProseMirror, RenderContext};
+
+mod comment;
+mod highlight;
+mod link;
+mod text_style;
+mod ychange;
+
+pub use comment::CommentMarkPlugin;
+pub use highlight::HighlightMarkPlugin;
+pub use link::LinkMarkPlugin;
+pub use text_style::TextStyleMarkPlugin;
+pub use ychange::YChangeMarkPlugin;
+
+macro_rules! define_mark_plugin {
+    ($struct_name:ident, $type_name:expr, $tag_name:expr) => {
+        #[derive(Default)]
+        pub struct $struct_name;
+
+        impl MarkPlugin for $struct_name {
+            fn render(
+                &self,
+                output: String,
+                mark: &Value,
+                ctx: &mut RenderContext,
+            ) -> Result<String, ProseMirrorError> {
+                let attrs = ctx.node_attrs($type_name, mark);
+
+                let tag = self.get_tag(ctx);
+                Ok(tag.render_with_attrs(output, attrs.as_ref()))
+            }
+        }
+
+        impl $struct_name {
+            fn get_tag<'a>(&self, ctx: &RenderContext<'a>) -> Tag<'a> {
+                ctx.tag_for($type_name, $tag_name, false)
+            }
+
+            pub fn new() -> Self {
+                Self
+            }
+
+            pub fn type_name() -> &'static str {
+                $type_name
+            }
+
+            pub fn register(prosemirror: &mut ProseMirror) {
+                prosemirror.add_mark_plugin($type_name, Box::new($struct_name::new()));
+            }
+        }
+    };
+}
+
+define_mark_plugin!(BoldMarkPlugin, "bold", "strong");
+define_mark_plugin!(ItalicMarkPlugin, "italic", "em");
+define_mark_plugin!(StrikeMarkPlugin, "strike", "s");
+define_mark_plugin!(UnderlineMarkPlugin, "underline", "u");
+define_mark_plugin!(CodeMarkPlugin, "code", "code");
+define_mark_plugin!(SubscriptMarkPlugin, "subscript", "sub");
+define_mark_plugin!(SuperscriptMarkPlugin, "superscript", "sup");
+
+pub fn register_defaults(prosemirror: &mut ProseMirror) {
+    BoldMarkPlugin::register(prosemirror);
+    ItalicMarkPlugin::register(prosemirror);
+    StrikeMarkPlugin::register(prosemirror);
+    UnderlineMarkPlugin::register(prosemirror);
+    CodeMarkPlugin::register(prosemirror);
+    LinkMarkPlugin::register(prosemirror);
+}
+
+// Marks from Tiptap extensions outside of StarterKit
+pub fn register_formatting_marks(prosemirror: &mut ProseMirror) {
+    SubscriptMarkPlugin::register(prosemirror);
+    SuperscriptMarkPlugin::register(prosemirror);
+    HighlightMarkPlugin::register(prosemirror);
+    TextStyleMarkPlugin::register(prosemirror);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_renders_each_mark() {
+        let prose_mirror = ProseMirror::with_default_plugins();
+
+        for (mark, tag) in [
+            ("bold", "strong"),
+            ("italic", "em"),
+            ("strike", "s"),
+            ("underline", "u"),
+        ] {
+            let content = json!({"type":"text","text":"Leafs","marks":[{"type":mark}]});
+            assert_eq!(
+                prose_mirror.render(&content).unwrap(),
+                format!("<{tag}>Leafs</{tag}>")
+            );
+        }
+    }
+
+    #[test]
+    fn it_renders_stacked_marks() {
+        let content = json!({"type":"doc","content":[{"type":"paragraph","content":[
+            {"type":"text","text":"Go ","marks":[{"type":"bold"}]},
+            {"type":"text","text":"Leafs","marks":[{"type":"bold"},{"type":"italic"},{"type":"underline"},{"type":"strike"}]}
+        ]}]});
+        let prose_mirror = ProseMirror::with_default_plugins();
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div><p><strong>Go </strong><strong><em><u><s>Leafs</s></u></em></strong></p></div>"
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn it_renders_formatting_marks() {
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        register_formatting_marks(&mut prose_mirror);
+        let content = json!({"type":"paragraph","content":[
+            {"type":"text","text":"H"},
+            {"type":"text","text":"2","marks":[{"type":"subscript"}]},
+            {"type":"text","text":"O and 1"},
+            {"type":"text","text":"st","marks":[{"type":"superscript"}]}
+        ]});
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<p>H<sub>2</sub>O and 1<sup>st</sup></p>".to_string()
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use serde_json::{Map, Value};
+
+use crate::error::ProseMirrorError;
+use crate::plugins::MarkPlugin;
+use crate::sanitize::is_safe_css_value;
+use crate::utils::merge_attributes;
+use crate::{ProseMirror, RenderContext};
+
+// The attrs TextStyle based extensions (Color, FontFamily, FontSize...) add, and
+// the CSS property each one becomes
+const STYLE_ATTRS: [(&str, &str); 5] = [
+    ("color", "color"),
+    ("backgroundColor", "background-color"),
+    ("fontFamily", "font-family"),
+    ("fontSize", "font-size"),
+    ("lineHeight", "line-height"),
+];
+
+// Font names are commonly quoted, which is fine inside an escaped attribute
+fn is_safe_style_value(property: &str, value: &str) -> bool {
+    if property == "font-family" {
+        return is_safe_css_value(&value.replace(['"', '\''], ""));
+    }
+    is_safe_css_value(value)
+}
+
+// All of a textStyle mark's attrs end up in a single `style` on one <span>
+#[derive(Default)]
+pub struct TextStyleMarkPlugin;
+
+impl MarkPlugin for TextStyleMarkPlugin {
+    fn render(
+        &self,
+        output: String,
+        mark: &Value,
+        ctx: &mut RenderContext,
+    ) -> Result<String, ProseMirrorError> {
+        let mark_attrs = ctx.node_attrs("textStyle", mark).unwrap_or_default();
+        let declarations: Vec<String> = STYLE_ATTRS
+            .iter()
+            .filter_map(|(attr, property)| {
+                let value = mark_attrs.get(*attr)?.as_str()?.trim();
+                is_safe_style_value(property, value).then(|| format!("{}: {}", property, value))
+            })
+            .collect();
+
+        // Only the configured HTMLAttributes come along, the mark's own attrs are
+        // all styles
+        let mut attrs = ctx
+            .options()
+            .html_attributes
+            .get("textStyle")
+            .cloned()
+            .unwrap_or_default();
+        if !declarations.is_empty() {
+            let mut extra = Map::new();
+            extra.insert("style".to_string(), Value::from(declarations.join("; ")));
+            merge_attributes(&mut attrs, &extra);
+        }
+        Ok(ctx
+            .tag_for("textStyle", "span", false)
+            .render_with_attrs(output, Some(&attrs)))
+    }
+}
+
+impl TextStyleMarkPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn type_name() -> &'static str {
+        "textStyle"
+    }
+
+    pub fn register(prosemirror: &mut ProseMirror) {
+        prosemirror.add_mark_plugin("textStyle", Box::new(TextStyleMarkPlugin::new()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_merges_styles_into_one_span() {
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        TextStyleMarkPlugin::register(&mut prose_mirror);
+
+        assert_eq!(
+            prose_mirror
+                .render(&json!({"type":"text","text":"Leafs","marks":[{"type":"textStyle","attrs":{"color":"#00205b","fontFamily":"\"Comic Sans MS\", cursive","fontSize":"18px","unknown":"x"}}]}))
+                .unwrap(),
+            "<span style=\"color: #00205b; font-family: &quot;Comic Sans MS&quot;, cursive; font-size: 18px\">Leafs</span>"
+                .to_string()
+        );
+        assert_eq!(
+            prose_mirror
+                .render(&json!({"type":"text","text":"Leafs","marks":[{"type":"textStyle","attrs":{"color":"red; background: url(x)","fontSize":null}}]}))
+                .unwrap(),
+            "<span>Leafs</span>".to_string()
+        );
+    }
+
+    #[test]
+    fn it_renders_a_plain_span_without_attrs() {
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        TextStyleMarkPlugin::register(&mut prose_mirror);
+
+        for mark in [
+            json!({"type":"textStyle"}),
+            json!({"type":"textStyle","attrs":null}),
+            json!({"type":"textStyle","attrs":{"color":7,"fontSize":""}}),
+        ] {
+            assert_eq!(
+                prose_mirror
+                    .render(&json!({"type":"text","text":"<Leafs>","marks":[mark]}))
+                    .unwrap(),
+                "<span>&lt;Leafs&gt;</span>"
+            );
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use serde_json::{Map, Value};
+
+use crate::error::ProseMirrorError;
+use crate::plugins::MarkPlugin;
+use crate::sanitize::is_safe_css_value;
+use crate::utils::merge_attributes;
+use crate::{ProseMirror, RenderContext};
+
+// The `ychange` mark y-prosemirror adds when rendering a snapshot diff, with
+// `type` set to "added" or "removed" and the user's color as `{light, dark}`
+#[derive(Default)]
+pub struct YChangeMarkPlugin;
+
+impl MarkPlugin for YChangeMarkPlugin {
+    fn render(
+        &self,
+        output: String,
+        mark: &Value,
+        ctx: &mut RenderContext,
+    ) -> Result<String, ProseMirrorError> {
+        let mut attrs = ctx.node_attrs("ychange", mark).unwrap_or_default();
+        let change: Map<String, Value> = ["type", "user", "color"]
+            .iter()
+            .filter_map(|key| Some((key.to_string(), attrs.remove(*key)?)))
+            .collect();
+        let tag = match change.get("type").and_then(|t| t.as_str()) {
+            Some("added") => "ins",
+            Some("removed") => "del",
+            _ => return Ok(output),
+        };
+
+        let mut extra = Map::new();
+        if let Some(user) = change
+            .get("user")
+            .filter(|user| !user.is_null() && *user != "")
+        {
+            let user = match user {
+                Value::String(user) => user.clone(),
+                _ => user.to_string(),
+            };
+            extra.insert("data-user".to_string(), Value::from(user));
+        }
+        let color = change.get("color").and_then(|color| match color {
+            Value::Object(color) => color.get("light").and_then(|light| light.as_str()),
+            _ => color.as_str(),
+        });
+        if let Some(color) = color.filter(|color| is_safe_css_value(color)) {
+            extra.insert("data-color".to_string(), Value::from(color));
+        }
+        merge_attributes(&mut attrs, &extra);
+
+        Ok(ctx
+            .tag_for("ychange", tag, false)
+            .render_with_attrs(output, Some(&attrs)))
+    }
+}
+
+impl YChangeMarkPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn type_name() -> &'static str {
+        "ychange"
+    }
+
+    pub fn register(prosemirror: &mut ProseMirror) {
+        prosemirror.add_mark_plugin("ychange", Box::new(YChangeMarkPlugin::new()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_renders_tracked_changes() {
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        YChangeMarkPlugin::register(&mut prose_mirror);
+        let content = json!({"type":"paragraph","content":[
+            {"type":"text","text":"Go ","marks":[{"type":"ychange","attrs":{"user":"timfeid","type":"added","color":{"light":"#ecd44433","dark":"#ecd444"}}}]},
+            {"type":"text","text":"Habs","marks":[{"type":"ychange","attrs":{"user":7,"type":"removed","color":"red;x"}}]},
+            {"type":"text","text":"Leafs","marks":[{"type":"ychange","attrs":{"user":null,"type":null}}]}
+        ]});
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<p><ins data-color=\"#ecd44433\" data-user=\"timfeid\">Go </ins><del data-user=\"7\">Habs</del>Leafs</p>"
+        );
+    }
+}
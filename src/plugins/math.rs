@@ -0,0 +1,450 @@
+use std::iter::Peekable;
+use std::str::Chars;
+
+use serde_json::{Map, Value};
+
+use crate::error::ProseMirrorError;
+use crate::limits::DEFAULT_MAX_DEPTH;
+use crate::utils::escape_html;
+use crate::{ProseMirror, RenderContext};
+
+use super::Plugin;
+
+const MATHML_NAMESPACE: &str = "http://www.w3.org/1998/Math/MathML";
+
+// `\name` -> (element, text), for the commands that are just a symbol
+const SYMBOLS: [(&str, &str, &str); 64] = [
+    ("alpha", "mi", "α"),
+    ("beta", "mi", "β"),
+    ("gamma", "mi", "γ"),
+    ("delta", "mi", "δ"),
+    ("epsilon", "mi", "ϵ"),
+    ("varepsilon", "mi", "ε"),
+    ("zeta", "mi", "ζ"),
+    ("eta", "mi", "η"),
+    ("theta", "mi", "θ"),
+    ("iota", "mi", "ι"),
+    ("kappa", "mi", "κ"),
+    ("lambda", "mi", "λ"),
+    ("mu", "mi", "μ"),
+    ("nu", "mi", "ν"),
+    ("xi", "mi", "ξ"),
+    ("pi", "mi", "π"),
+    ("rho", "mi", "ρ"),
+    ("sigma", "mi", "σ"),
+    ("tau", "mi", "τ"),
+    ("phi", "mi", "ϕ"),
+    ("varphi", "mi", "φ"),
+    ("chi", "mi", "χ"),
+    ("psi", "mi", "ψ"),
+    ("omega", "mi", "ω"),
+    ("Gamma", "mi", "Γ"),
+    ("Delta", "mi", "Δ"),
+    ("Theta", "mi", "Θ"),
+    ("Lambda", "mi", "Λ"),
+    ("Xi", "mi", "Ξ"),
+    ("Pi", "mi", "Π"),
+    ("Sigma", "mi", "Σ"),
+    ("Phi", "mi", "Φ"),
+    ("Psi", "mi", "Ψ"),
+    ("Omega", "mi", "Ω"),
+    ("infty", "mi", "∞"),
+    ("partial", "mi", "∂"),
+    ("nabla", "mi", "∇"),
+    ("times", "mo", "×"),
+    ("cdot", "mo", "⋅"),
+    ("pm", "mo", "±"),
+    ("mp", "mo", "∓"),
+    ("div", "mo", "÷"),
+    ("leq", "mo", "≤"),
+    ("geq", "mo", "≥"),
+    ("neq", "mo", "≠"),
+    ("approx", "mo", "≈"),
+    ("equiv", "mo", "≡"),
+    ("sum", "mo", "∑"),
+    ("prod", "mo", "∏"),
+    ("int", "mo", "∫"),
+    ("to", "mo", "→"),
+    ("rightarrow", "mo", "→"),
+    ("leftarrow", "mo", "←"),
+    ("Rightarrow", "mo", "⇒"),
+    ("in", "mo", "∈"),
+    ("notin", "mo", "∉"),
+    ("subset", "mo", "⊂"),
+    ("subseteq", "mo", "⊆"),
+    ("cup", "mo", "∪"),
+    ("cap", "mo", "∩"),
+    ("forall", "mo", "∀"),
+    ("exists", "mo", "∃"),
+    ("ldots", "mo", "…"),
+    ("cdots", "mo", "⋯"),
+];
+
+// Rendered upright as a single identifier, like LaTeX does
+const FUNCTIONS: [&str; 14] = [
+    "sin", "cos", "tan", "cot", "sec", "csc", "log", "ln", "exp", "lim", "max", "min", "det", "gcd",
+];
+
+// A small LaTeX to MathML converter covering what editors usually produce:
+// fractions, roots, scripts, greek letters and common operators
+struct MathMl<'a> {
+    chars: Peekable<Chars<'a>>,
+    // How many atoms are being parsed right now, every group, script and
+    // command argument recurses through `atom`
+    depth: usize,
+}
+
+impl<'a> MathMl<'a> {
+    fn new(latex: &'a str) -> Self {
+        Self {
+            chars: latex.chars().peekable(),
+            depth: 0,
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+
+    fn command(&mut self) -> String {
+        let mut name = String::new();
+        while let Some(c) = self.chars.next_if(|c| c.is_ascii_alphabetic()) {
+            name.push(c);
+        }
+        // Single character commands like `\{` or `\,`
+        if name.is_empty() {
+            if let Some(c) = self.chars.next() {
+                name.push(c);
+            }
+        }
+        name
+    }
+
+    // Raw text up to the closing brace, for `\text{...}`
+    fn braced_text(&mut self) -> String {
+        self.skip_whitespace();
+        if self.chars.next_if_eq(&'{').is_none() {
+            return String::new();
+        }
+        let mut depth = 0;
+        let mut text = String::new();
+        for c in self.chars.by_ref() {
+            match c {
+                '{' => depth += 1,
+                '}' if depth == 0 => break,
+                '}' => depth -= 1,
+                _ => {}
+            }
+            text.push(c);
+        }
+        text
+    }
+
+    fn row(&mut self) -> String {
+        let mut items = vec![];
+        loop {
+            self.skip_whitespace();
+            match self.chars.peek() {
+                None | Some('}') => break,
+                Some(_) => {
+                    if let Some(item) = self.scripted() {
+                        items.push(item);
+                    }
+                }
+            }
+        }
+        match items.len() {
+            1 => items.remove(0),
+            _ => format!("<mrow>{}</mrow>", items.concat()),
+        }
+    }
+
+    fn scripted(&mut self) -> Option<String> {
+        let base = self.atom()?;
+        let mut sub = None;
+        let mut sup = None;
+        loop {
+            self.skip_whitespace();
+            match self.chars.peek() {
+                Some('_') if sub.is_none() => {
+                    self.chars.next();
+                    sub = Some(self.argument());
+                }
+                Some('^') if sup.is_none() => {
+                    self.chars.next();
+                    sup = Some(self.argument());
+                }
+                _ => break,
+            }
+        }
+        Some(match (sub, sup) {
+            (None, None) => base,
+            (Some(sub), None) => format!("<msub>{}{}</msub>", base, sub),
+            (None, Some(sup)) => format!("<msup>{}{}</msup>", base, sup),
+            (Some(sub), Some(sup)) => format!("<msubsup>{}{}{}</msubsup>", base, sub, sup),
+        })
+    }
+
+    fn argument(&mut self) -> String {
+        self.skip_whitespace();
+        self.atom().unwrap_or_else(|| "<mrow></mrow>".to_string())
+    }
+
+    // Past the depth limit the rest of the formula becomes an error instead of
+    // recursing until the stack overflows
+    fn atom(&mut self) -> Option<String> {
+        self.chars.peek()?;
+        if self.depth >= DEFAULT_MAX_DEPTH {
+            self.chars.by_ref().for_each(drop);
+            return Some("<merror><mtext>nested too deeply</mtext></merror>".to_string());
+        }
+        self.depth += 1;
+        let atom = self.nested_atom();
+        self.depth -= 1;
+        atom
+    }
+
+    fn nested_atom(&mut self) -> Option<String> {
+        let c = self.chars.next()?;
+        Some(match c {
+            '{' => {
+                let row = self.row();
+                self.chars.next_if_eq(&'}');
+                row
+            }
+            '\\' => self.command_atom(),
+            c if c.is_ascii_digit() || c == '.' => {
+                let mut number = c.to_string();
+                while let Some(c) = self.chars.next_if(|c| c.is_ascii_digit() || *c == '.') {
+                    number.push(c);
+                }
+                format!("<mn>{}</mn>", number)
+            }
+            c if c.is_alphabetic() => format!("<mi>{}</mi>", c),
+            // Alignment and spacing don't change what the formula means
+            '&' | '~' => return Some(String::new()),
+            c => format!("<mo>{}</mo>", escape_html(&c.to_string())),
+        })
+    }
+
+    fn command_atom(&mut self) -> String {
+        let name = self.command();
+        if let Some((_, element, text)) = SYMBOLS.iter().find(|(symbol, ..)| *symbol == name) {
+            return format!("<{0}>{1}</{0}>", element, text);
+        }
+        if FUNCTIONS.contains(&name.as_str()) {
+            return format!("<mi>{}</mi>", name);
+        }
+        match name.as_str() {
+            "frac" | "dfrac" | "tfrac" => {
+                let numerator = self.argument();
+                let denominator = self.argument();
+                format!("<mfrac>{}{}</mfrac>", numerator, denominator)
+            }
+            "sqrt" => {
+                self.skip_whitespace();
+                if self.chars.next_if_eq(&'[').is_some() {
+                    let mut index = String::new();
+                    while let Some(c) = self.chars.next_if(|c| *c != ']') {
+                        index.push(c);
+                    }
+                    self.chars.next();
+                    let radicand = self.argument();
+                    let mut index_parser = MathMl::new(&index);
+                    index_parser.depth = self.depth;
+                    let index = index_parser.row();
+                    return format!("<mroot>{}{}</mroot>", radicand, index);
+                }
+                format!("<msqrt>{}</msqrt>", self.argument())
+            }
+            "text" | "mathrm" | "operatorname" => {
+                format!("<mtext>{}</mtext>", escape_html(&self.braced_text()))
+            }
+            "mathbf" => format!("<mstyle mathvariant=\"bold\">{}</mstyle>", self.argument()),
+            "left" | "right" => {
+                self.skip_whitespace();
+                match self.atom() {
+                    // `\left.` is an invisible delimiter
+                    Some(delimiter) if delimiter == "<mn>.</mn>" => String::new(),
+                    Some(delimiter) => delimiter,
+                    None => String::new(),
+                }
+            }
+            "{" | "}" | "|" | "%" | "$" | "#" | "_" => format!("<mo>{}</mo>", name),
+            "," | ";" | ":" | "!" | "quad" | "qquad" | "\\" => String::new(),
+            _ => format!("<merror><mtext>\\{}</mtext></merror>", escape_html(&name)),
+        }
+    }
+}
+
+fn latex_to_mathml(latex: &str, display: bool) -> String {
+    let mut parser = MathMl::new(latex);
+    let mut row = parser.row();
+    // A stray `}` ends the row early, whatever follows still gets rendered
+    while parser.chars.next().is_some() {
+        row.push_str(&parser.row());
+    }
+    format!(
+        "<math xmlns=\"{}\"{}><semantics>{}<annotation encoding=\"application/x-tex\">{}</annotation></semantics></math>",
+        MATHML_NAMESPACE,
+        if display { " display=\"block\"" } else { "" },
+        row,
+        escape_html(latex)
+    )
+}
+
+// Renders LaTeX some other way, e.g. through KaTeX, `None` falls back to MathML
+type MathRenderer = Box<dyn Fn(&str, bool) -> Option<String> + Send + Sync>;
+
+// Tiptap's Mathematics extension (`inlineMath` and `blockMath` with the LaTeX
+// in `attrs.latex`), rendered to MathML so it doesn't need client-side JS
+#[derive(Default)]
+pub struct MathPlugin {
+    renderer: Option<MathRenderer>,
+}
+
+impl Plugin for MathPlugin {
+    fn render(&self, node: &Value, ctx: &mut RenderContext) -> Result<String, ProseMirrorError> {
+        let attrs = node.get("attrs");
+        let latex = ["latex", "content"]
+            .iter()
+            .find_map(|key| attrs.and_then(|attrs| attrs.get(*key)))
+            .and_then(|latex| latex.as_str())
+            .unwrap_or_default();
+        let node_type = node
+            .get("type")
+            .and_then(|t| t.as_str())
+            .unwrap_or("inlineMath");
+        let display = node_type == "blockMath"
+            || attrs
+                .and_then(|attrs| attrs.get("display"))
+                .is_some_and(|display| display == true || display == "block");
+
+        let math = self
+            .renderer
+            .as_ref()
+            .and_then(|render| render(latex, display))
+            .unwrap_or_else(|| latex_to_mathml(latex, display));
+        let mut span_attrs = Map::new();
+        let data_type = if display { "blockMath" } else { "inlineMath" };
+        span_attrs.insert("data-type".to_string(), Value::from(data_type));
+        let tag = if display { "div" } else { "span" };
+        Ok(ctx
+            .tag_for(node_type, tag, false)
+            .render_with_attrs(math, Some(&span_attrs)))
+    }
+}
+
+impl MathPlugin {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_renderer<F>(mut self, renderer: F) -> Self
+    where
+        F: Fn(&str, bool) -> Option<String> + Send + Sync + 'static,
+    {
+        self.renderer = Some(Box::new(renderer));
+        self
+    }
+
+    pub fn type_name() -> &'static str {
+        "inlineMath"
+    }
+
+    // Older extensions use a single `math` node for both
+    pub fn register(prosemirror: &mut ProseMirror) {
+        for node_type in ["inlineMath", "blockMath", "math"] {
+            prosemirror.add_plugin(node_type, Box::new(MathPlugin::new()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TagOverrides;
+    use serde_json::json;
+
+    #[test]
+    fn it_converts_latex_to_mathml() {
+        assert_eq!(
+            latex_to_mathml("x^2 + \\frac{a_1}{\\sqrt{b}} \\leq \\alpha", false),
+            "<math xmlns=\"http://www.w3.org/1998/Math/MathML\"><semantics><mrow><msup><mi>x</mi><mn>2</mn></msup><mo>+</mo><mfrac><msub><mi>a</mi><mn>1</mn></msub><msqrt><mi>b</mi></msqrt></mfrac><mo>≤</mo><mi>α</mi></mrow><annotation encoding=\"application/x-tex\">x^2 + \\frac{a_1}{\\sqrt{b}} \\leq \\alpha</annotation></semantics></math>"
+        );
+    }
+
+    #[test]
+    fn it_renders_math_nodes() {
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        MathPlugin::register(&mut prose_mirror);
+        let content = json!({"type":"paragraph","content":[
+            {"type":"inlineMath","attrs":{"latex":"a<b"}},
+            {"type":"blockMath","attrs":{"latex":"\\unknown"}}
+        ]});
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<p><span data-type=\"inlineMath\"><math xmlns=\"http://www.w3.org/1998/Math/MathML\"><semantics><mrow><mi>a</mi><mo>&lt;</mo><mi>b</mi></mrow><annotation encoding=\"application/x-tex\">a&lt;b</annotation></semantics></math></span><div data-type=\"blockMath\"><math xmlns=\"http://www.w3.org/1998/Math/MathML\" display=\"block\"><semantics><merror><mtext>\\unknown</mtext></merror><annotation encoding=\"application/x-tex\">\\unknown</annotation></semantics></math></div></p>"
+        );
+
+        prose_mirror.add_plugin(
+            "inlineMath",
+            Box::new(MathPlugin::new().with_renderer(|latex, _| Some(format!("[{}]", latex)))),
+        );
+        assert_eq!(
+            prose_mirror
+                .render(&json!({"type":"inlineMath","attrs":{"latex":"x"}}))
+                .unwrap(),
+            "<span data-type=\"inlineMath\">[x]</span>"
+        );
+    }
+
+    #[test]
+    fn it_follows_tag_overrides() {
+        let mut prose_mirror = ProseMirror::builder()
+            .with_defaults()
+            .tag_overrides(
+                TagOverrides::new()
+                    .with_tag("inlineMath", "m-inline")
+                    .with_tag("blockMath", "figure"),
+            )
+            .build();
+        for node_type in ["inlineMath", "blockMath"] {
+            prose_mirror.add_plugin(
+                node_type,
+                Box::new(MathPlugin::new().with_renderer(|latex, _| Some(latex.to_string()))),
+            );
+        }
+        let content = json!({"type":"doc","content":[
+            {"type":"paragraph","content":[{"type":"inlineMath","attrs":{"latex":"x"}}]},
+            {"type":"blockMath","attrs":{"latex":"y"}}
+        ]});
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div><p><m-inline data-type=\"inlineMath\">x</m-inline></p><figure data-type=\"blockMath\">y</figure></div>"
+        );
+    }
+
+    #[test]
+    fn it_stops_at_deeply_nested_groups() {
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        MathPlugin::register(&mut prose_mirror);
+
+        for latex in [
+            "{".repeat(200_000),
+            "x^{".repeat(200_000),
+            "\\frac".repeat(200_000),
+            format!("\\sqrt[{}]{{x}}", "{".repeat(200_000)),
+        ] {
+            let output = prose_mirror
+                .render(&json!({"type":"inlineMath","attrs":{"latex":latex}}))
+                .unwrap();
+            assert!(output.contains("<merror><mtext>nested too deeply</mtext></merror>"));
+        }
+
+        let latex = format!("{}x{}", "{".repeat(100), "}".repeat(100));
+        assert!(latex_to_mathml(&latex, false).contains("<semantics><mi>x</mi>"));
+    }
+}
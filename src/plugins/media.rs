@@ -0,0 +1,175 @@
+use serde_json::{Map, Value};
+
+use crate::error::ProseMirrorError;
+use crate::{ProseMirror, RenderContext};
+
+use super::Plugin;
+
+// Attrs that only matter when they're true, rendered bare
+const MEDIA_FLAGS: [&str; 4] = ["controls", "muted", "loop", "autoplay"];
+
+fn render_media(
+    tag: &'static str,
+    type_name: &str,
+    allowed_attrs: &[&str],
+    node: &Value,
+    ctx: &mut RenderContext,
+) -> Result<String, ProseMirrorError> {
+    let node_attrs = ctx.node_attrs(type_name, node).unwrap_or_default();
+    let mut attrs = Map::new();
+    for (key, value) in &node_attrs {
+        if MEDIA_FLAGS.contains(&key.as_str()) {
+            if value.as_bool() == Some(true) {
+                attrs.insert(key.clone(), Value::Bool(true));
+            }
+        } else if allowed_attrs.contains(&key.as_str()) {
+            attrs.insert(key.clone(), value.clone());
+        }
+    }
+    // Without controls there'd be no way to play it
+    if node_attrs.get("controls").and_then(|c| c.as_bool()) != Some(false) {
+        attrs.insert("controls".to_string(), Value::Bool(true));
+    }
+    ctx.sanitize_url_attr(&mut attrs, "src")?;
+    ctx.sanitize_url_attr(&mut attrs, "poster")?;
+
+    let mut sources = String::new();
+    let candidates = node_attrs
+        .get("sources")
+        .and_then(|sources| sources.as_array());
+    for source in candidates.into_iter().flatten() {
+        let mut source_attrs = Map::new();
+        for key in ["src", "type"] {
+            if let Some(value) = source.get(key).and_then(|value| value.as_str()) {
+                source_attrs.insert(key.to_string(), Value::from(value));
+            }
+        }
+        ctx.sanitize_url_attr(&mut source_attrs, "src")?;
+        if source_attrs.contains_key("src") {
+            sources.push_str(&ctx.tag("source", true).render_opening(Some(&source_attrs)));
+        }
+    }
+
+    Ok(ctx
+        .tag_for(type_name, tag, false)
+        .render_with_attrs(sources, Some(&attrs)))
+}
+
+#[derive(Default)]
+pub struct AudioPlugin;
+
+impl Plugin for AudioPlugin {
+    fn render(&self, node: &Value, ctx: &mut RenderContext) -> Result<String, ProseMirrorError> {
+        render_media("audio", "audio", &["src", "preload"], node, ctx)
+    }
+}
+
+impl AudioPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn type_name() -> &'static str {
+        "audio"
+    }
+
+    pub fn register(prosemirror: &mut ProseMirror) {
+        prosemirror.add_plugin("audio", Box::new(AudioPlugin::new()));
+    }
+}
+
+#[derive(Default)]
+pub struct VideoPlugin;
+
+impl Plugin for VideoPlugin {
+    fn render(&self, node: &Value, ctx: &mut RenderContext) -> Result<String, ProseMirrorError> {
+        render_media(
+            "video",
+            "video",
+            &["src", "preload", "poster", "width", "height"],
+            node,
+            ctx,
+        )
+    }
+}
+
+impl VideoPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn type_name() -> &'static str {
+        "video"
+    }
+
+    pub fn register(prosemirror: &mut ProseMirror) {
+        prosemirror.add_plugin("video", Box::new(VideoPlugin::new()));
+    }
+}
+
+pub fn register_media_plugins(prosemirror: &mut ProseMirror) {
+    AudioPlugin::register(prosemirror);
+    VideoPlugin::register(prosemirror);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_renders_video_with_sources() {
+        let content = json!({"type":"video","attrs":{
+            "poster":"/goal.jpg","muted":true,"loop":false,"onplay":"alert(1)",
+            "sources":[
+                {"src":"/goal.webm","type":"video/webm"},
+                {"src":"javascript:alert(1)","type":"video/mp4"}
+            ]
+        }});
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        register_media_plugins(&mut prose_mirror);
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<video controls muted poster=\"/goal.jpg\"><source src=\"/goal.webm\" type=\"video/webm\" /></video>"
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn it_renders_audio() {
+        let content =
+            json!({"type":"audio","attrs":{"src":"/anthem.mp3","controls":false,"autoplay":true}});
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        register_media_plugins(&mut prose_mirror);
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<audio autoplay src=\"/anthem.mp3\"></audio>".to_string()
+        );
+    }
+
+    #[test]
+    fn it_ignores_malformed_media_attrs() {
+        let mut prose_mirror = ProseMirror::new();
+        register_media_plugins(&mut prose_mirror);
+
+        assert_eq!(
+            prose_mirror.render(&json!({"type":"video"})).unwrap(),
+            "<video controls></video>"
+        );
+        let content = json!({"type":"audio","attrs":{
+            "src":"/a\"b.mp3","muted":"true","autoplay":1,
+            "sources":[{"type":"audio/ogg"}, "/x.ogg", {"src":7}]
+        }});
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<audio controls src=\"/a&quot;b.mp3\"></audio>"
+        );
+        let content = json!({"type":"video","attrs":{"sources":{"src":"/goal.webm"}}});
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<video controls></video>"
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use serde_json::{Map, Value};
+
+use crate::error::ProseMirrorError;
+use crate::{ProseMirror, RenderContext};
+
+use super::Plugin;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MentionAttrs {
+    pub id: Option<String>,
+    pub label: Option<String>,
+}
+
+impl MentionAttrs {
+    pub fn from_node(node: &Value) -> Self {
+        let attr = |name: &str| {
+            node.get("attrs")
+                .and_then(|attrs| attrs.get(name))
+                .and_then(|value| match value {
+                    Value::String(s) => Some(s.clone()),
+                    Value::Null => None,
+                    _ => Some(value.to_string()),
+                })
+        };
+        Self {
+            id: attr("id"),
+            label: attr("label"),
+        }
+    }
+}
+
+type MentionResolver = Box<dyn Fn(&MentionAttrs) -> String + Send + Sync>;
+
+#[derive(Default)]
+pub struct MentionPlugin {
+    resolver: Option<MentionResolver>,
+}
+
+impl Plugin for MentionPlugin {
+    fn render(&self, node: &Value, ctx: &mut RenderContext) -> Result<String, ProseMirrorError> {
+        let mention = MentionAttrs::from_node(node);
+        if let Some(resolver) = &self.resolver {
+            return Ok(resolver(&mention));
+        }
+
+        let mut attrs: Map<String, Value> = ctx.node_attrs("mention", node).unwrap_or_default();
+        attrs.remove("id");
+        attrs.remove("label");
+        attrs.insert("data-type".to_string(), Value::from("mention"));
+        if let Some(id) = &mention.id {
+            attrs.insert("data-id".to_string(), Value::from(id.as_str()));
+        }
+
+        let label = mention.label.or(mention.id).unwrap_or_default();
+        Ok(ctx
+            .tag_for("mention", "span", false)
+            .render_with_attrs(format!("@{}", ctx.escape_text(&label)), Some(&attrs)))
+    }
+}
+
+impl MentionPlugin {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // The resolver's output is used verbatim in place of the default <span>
+    pub fn with_resolver<F>(resolver: F) -> Self
+    where
+        F: Fn(&MentionAttrs) -> String + Send + Sync + 'static,
+    {
+        Self {
+            resolver: Some(Box::new(resolver)),
+        }
+    }
+
+    pub fn type_name() -> &'static str {
+        "mention"
+    }
+
+    pub fn register(prosemirror: &mut ProseMirror) {
+        prosemirror.add_plugin("mention", Box::new(MentionPlugin::new()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_renders_default_mention() {
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        MentionPlugin::register(&mut prose_mirror);
+        let content = json!({"type":"paragraph","content":[
+            {"type":"text","text":"Nice goal "},
+            {"type":"mention","attrs":{"id":"34","label":"Auston"}}
+        ]});
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<p>Nice goal <span data-id=\"34\" data-type=\"mention\">@Auston</span></p>"
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn it_falls_back_to_id_without_label() {
+        let mut prose_mirror = ProseMirror::new();
+        MentionPlugin::register(&mut prose_mirror);
+
+        assert_eq!(
+            prose_mirror
+                .render(&json!({"type":"mention","attrs":{"id":"34","label":null}}))
+                .unwrap(),
+            "<span data-id=\"34\" data-type=\"mention\">@34</span>".to_string()
+        );
+    }
+
+    #[test]
+    fn it_uses_resolver() {
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        prose_mirror.add_plugin(
+            "mention",
+            Box::new(MentionPlugin::with_resolver(|mention| {
+                format!(
+                    "<a href=\"/users/{}\">@{}</a>",
+                    mention.id.as_deref().unwrap_or_default(),
+                    mention.label.as_deref().unwrap_or_default()
+                )
+            })),
+        );
+        let content = json!({"type":"paragraph","content":[
+            {"type":"text","text":"Nice goal "},
+            {"type":"mention","attrs":{"id":"34","label":"Auston"}}
+        ]});
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<p>Nice goal <a href=\"/users/34\">@Auston</a></p>".to_string()
+        );
+    }
+
+    #[test]
+    fn it_handles_missing_and_unsafe_attrs() {
+        let mut prose_mirror = ProseMirror::new();
+        MentionPlugin::register(&mut prose_mirror);
+
+        assert_eq!(
+            prose_mirror.render(&json!({"type":"mention"})).unwrap(),
+            "<span data-type=\"mention\">@</span>"
+        );
+        assert_eq!(
+            prose_mirror
+                .render(&json!({"type":"mention","attrs":{"id":"a\"b","label":"<Mitch>"}}))
+                .unwrap(),
+            "<span data-id=\"a&quot;b\" data-type=\"mention\">@&lt;Mitch&gt;</span>"
+        );
+    }
+}
@@ -1,120 +1,341 @@
-use std::collections::HashMap;
+use std::fmt;
 
 use serde_json::{Map, Value};
 
+use crate::attrs::{data_attr_name, is_valid_attr_name};
 use crate::error::ProseMirrorError;
-use crate::utils::push_front;
-use crate::ProseMirror;
-
+use crate::model::Node;
+use crate::options::{
+    AttrOrder, RenderOptions, SerializationStyle, UnknownAttrStrategy, DEFAULT_BOOLEAN_ATTRS,
+};
+use crate::utils::write_escaped;
+use crate::{ProseMirror, RenderContext, RenderState};
+
+#[cfg(feature = "async")]
+mod async_plugin;
+mod callout;
+mod code_block;
+mod details;
+mod emoji;
+mod fn_plugin;
+mod footnote;
+mod heading;
+#[cfg(feature = "syntax-highlight")]
+mod highlight;
+mod iframe;
+mod image;
+mod linkify;
+pub mod marks;
+mod math;
+mod media;
+mod mention;
+mod ordered_list;
+pub(crate) mod starter_kit;
+mod table;
+mod table_of_contents;
+mod task_list;
 mod text;
 
-pub trait Plugin {
-    fn render(
+#[cfg(feature = "async")]
+pub use async_plugin::{AsyncPlugin, BoxFuture};
+pub use callout::CalloutPlugin;
+pub use code_block::CodeBlockPlugin;
+pub use details::{register_details_plugins, DetailsContentPlugin, DetailsPlugin};
+pub use emoji::{EmojiImageResolver, EmojiPlugin};
+pub use fn_plugin::FnPlugin;
+pub use footnote::{register_footnote_plugins, FootnotePlugin, FootnoteReferencePlugin};
+pub use heading::HeadingPlugin;
+pub use iframe::IframePlugin;
+pub use image::{ImagePlugin, SrcsetProvider, SrcsetWidths};
+pub use linkify::{LinkBuilder, LinkPattern};
+pub use math::MathPlugin;
+pub use media::{register_media_plugins, AudioPlugin, VideoPlugin};
+pub use mention::{MentionAttrs, MentionPlugin};
+pub use ordered_list::OrderedListPlugin;
+pub use starter_kit::{StarterKitMark, StarterKitNode};
+pub use table::{register_table_plugins, TableCellPlugin, TableHeaderPlugin, TablePlugin};
+pub(crate) use table_of_contents::table_of_contents;
+pub use table_of_contents::{TableOfContentsPlugin, TocEntry};
+pub use task_list::{register_task_list_plugins, TaskItemPlugin, TaskListPlugin};
+pub use text::TextPlugin;
+
+// Send + Sync so a renderer can be shared across threads and held across awaits
+pub trait Plugin: Send + Sync {
+    fn render(&self, node: &Value, ctx: &mut RenderContext) -> Result<String, ProseMirrorError>;
+
+    // Called for every node of this type before anything is rendered when
+    // `collect_pass` or heading anchors are on, so the render pass can see the
+    // whole document
+    fn collect(&self, _node: &Node, _state: &mut RenderState) {}
+
+    // True when a node can render differently depending on what rendered before
+    // it through `RenderState`, blocks are then neither cached nor rendered apart
+    fn uses_state(&self) -> bool {
+        false
+    }
+
+    // Runs once after the root node has rendered, for output that belongs at the
+    // end of the document like footnotes
+    fn finish(
+        &self,
+        _ctx: &mut RenderContext,
+        _writer: &mut dyn fmt::Write,
+    ) -> Result<(), ProseMirrorError> {
+        Ok(())
+    }
+
+    // Container nodes override this to stream their children straight into
+    // the writer instead of building an intermediate String
+    fn render_to(
         &self,
         node: &Value,
-        plugins: &HashMap<String, Box<dyn Plugin>>,
+        ctx: &mut RenderContext,
+        writer: &mut dyn fmt::Write,
+    ) -> Result<(), ProseMirrorError> {
+        writer.write_str(&self.render(node, ctx)?)?;
+        Ok(())
+    }
+}
+
+pub trait MarkPlugin: Send + Sync {
+    fn render(
+        &self,
+        output: String,
+        mark: &Value,
+        ctx: &mut RenderContext,
     ) -> Result<String, ProseMirrorError>;
 }
 
-pub struct Tag {
-    name: &'static str,
+pub struct Tag<'a> {
+    // Empty when an override drops the element, only the content is rendered then
+    name: &'a str,
     is_self_closing: bool,
+    options: Option<&'a RenderOptions>,
 }
 
-impl Tag {
+impl<'a> Tag<'a> {
     pub fn new(name: &'static str, is_self_closing: bool) -> Self {
         Self {
             name,
             is_self_closing,
+            options: None,
         }
     }
 
-    fn create_attrs(attrs: &Map<String, Value>) -> String {
-        println!("{:?}", attrs);
-        let mut attr_strs = vec![];
+    // A tag that follows the renderer's options rather than the defaults
+    pub fn with_options(name: &'a str, is_self_closing: bool, options: &'a RenderOptions) -> Self {
+        Self {
+            name,
+            is_self_closing,
+            options: Some(options),
+        }
+    }
 
-        for (key, value) in attrs.iter() {
-            let value_str = match value {
-                Value::Null => "".to_string(),
-                Value::String(s) => s.clone(),
-                _ => value.to_string(),
-            };
-            attr_strs.push(format!("{}=\"{}\"", key, value_str));
+    fn escapes(&self) -> bool {
+        self.options.map(|o| o.escape_html).unwrap_or(true)
+    }
+
+    fn renders_null(&self, attr: &str) -> bool {
+        self.options
+            .map(|o| o.attr_policy.renders_null(attr))
+            .unwrap_or(false)
+    }
+
+    fn serialization(&self) -> SerializationStyle {
+        self.options.map(|o| o.serialization).unwrap_or_default()
+    }
+
+    fn is_boolean(&self, attr: &str) -> bool {
+        match self.options {
+            Some(options) => options.boolean_attrs.iter().any(|boolean| boolean == attr),
+            None => DEFAULT_BOOLEAN_ATTRS.contains(&attr),
         }
+    }
 
-        attr_strs.join(" ")
+    // `true`, `"true"` and `"checked"` for `checked` all mean present
+    fn is_truthy(attr: &str, value: &Value) -> bool {
+        match value {
+            Value::Bool(value) => *value,
+            Value::String(value) => value == "true" || value.eq_ignore_ascii_case(attr),
+            _ => false,
+        }
+    }
+
+    fn ordered<'m>(&self, attrs: &'m Map<String, Value>) -> Vec<(&'m String, &'m Value)> {
+        let mut ordered: Vec<_> = attrs.iter().collect();
+        let order = self.options.map(|o| &o.attr_order);
+        match order {
+            Some(AttrOrder::Source) => {}
+            Some(AttrOrder::Explicit(orders)) if orders.contains_key(self.name) => {
+                let order = &orders[self.name];
+                let position = |key: &str| {
+                    order
+                        .iter()
+                        .position(|attr| attr == key)
+                        .unwrap_or(order.len())
+                };
+                ordered.sort_by(|(a, _), (b, _)| position(a).cmp(&position(b)).then(a.cmp(b)));
+            }
+            _ => ordered.sort_by_key(|(key, _)| *key),
+        }
+        ordered
+    }
+
+    // Each attr with the space before it, written straight into the output
+    fn write_attrs(&self, attrs: &Map<String, Value>, writer: &mut dyn fmt::Write) -> fmt::Result {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(tag = self.name, ?attrs, "writing attrs");
+        let renamed;
+        let attrs = match self.options.map(|o| o.unknown_attrs) {
+            Some(UnknownAttrStrategy::DataPrefix) => {
+                renamed = attrs
+                    .iter()
+                    .map(|(key, value)| {
+                        let key = data_attr_name(key).unwrap_or_else(|| key.clone());
+                        (key, value.clone())
+                    })
+                    .collect();
+                &renamed
+            }
+            _ => attrs,
+        };
+        for (key, value) in self.ordered(attrs) {
+            if !is_valid_attr_name(key) || (value.is_null() && !self.renders_null(key)) {
+                continue;
+            }
+            if self.is_boolean(key) {
+                if Self::is_truthy(key, value) {
+                    writer.write_char(' ')?;
+                    writer.write_str(key)?;
+                }
+                continue;
+            }
+            let coerced;
+            let value_str = match (value, self.options) {
+                (Value::Null, _) => "",
+                (Value::String(s), _) => s.as_str(),
+                (_, Some(options)) => match options.attr_values.coerce(key, value) {
+                    Some(value) => {
+                        coerced = value;
+                        coerced.as_str()
+                    }
+                    None => continue,
+                },
+                (_, None) => {
+                    coerced = value.to_string();
+                    coerced.as_str()
+                }
+            };
+            writer.write_char(' ')?;
+            writer.write_str(key)?;
+            writer.write_str("=\"")?;
+            if self.escapes() {
+                write_escaped(writer, value_str)?;
+            } else {
+                writer.write_str(value_str)?;
+            }
+            writer.write_char('"')?;
+        }
+        Ok(())
     }
 
     pub fn render(&self, output: String, node: &Value) -> String {
-        return format!(
-            "{}{}{}",
-            self.render_opening(node.get("attrs").and_then(|attrs| attrs.as_object())),
+        self.render_with_attrs(
             output,
-            self.render_closing()
-        );
+            node.get("attrs").and_then(|attrs| attrs.as_object()),
+        )
+    }
+
+    pub fn render_with_attrs(&self, output: String, attrs: Option<&Map<String, Value>>) -> String {
+        let attrs_len = attrs.map(|attrs| attrs.len() * 24).unwrap_or(0);
+        let mut html = String::with_capacity(output.len() + self.name.len() * 2 + 5 + attrs_len);
+        // Writing into a String can't fail
+        let _ = self.write_opening(attrs, &mut html);
+        html.push_str(&output);
+        let _ = self.write_closing(&mut html);
+        html
     }
 
     pub fn render_opening(&self, attrs: Option<&Map<String, Value>>) -> String {
-        if self.is_self_closing {
-            format!(
-                "<{} {} />",
-                self.name,
-                attrs.map(Tag::create_attrs).unwrap_or_default()
-            )
-        } else {
-            format!(
-                "<{}{}>",
-                self.name,
-                attrs
-                    .map(Tag::create_attrs)
-                    .map(|s| push_front(s, " "))
-                    .unwrap_or_default()
-            )
+        let mut html = String::new();
+        let _ = self.write_opening(attrs, &mut html);
+        html
+    }
+
+    pub fn write_opening(
+        &self,
+        attrs: Option<&Map<String, Value>>,
+        writer: &mut dyn fmt::Write,
+    ) -> fmt::Result {
+        if self.name.is_empty() {
+            return Ok(());
+        }
+        writer.write_char('<')?;
+        writer.write_str(self.name)?;
+        if let Some(attrs) = attrs {
+            self.write_attrs(attrs, writer)?;
+        }
+        match (self.is_self_closing, self.serialization()) {
+            (true, SerializationStyle::Xhtml) => writer.write_str(" />"),
+            _ => writer.write_char('>'),
         }
     }
 
-    pub fn render_closing(&self) -> String {
-        if self.is_self_closing {
-            String::new()
-        } else {
-            format!("</{}>", self.name)
+    pub fn write_closing(&self, writer: &mut dyn fmt::Write) -> fmt::Result {
+        if !self.is_self_closing && !self.name.is_empty() {
+            writer.write_str("</")?;
+            writer.write_str(self.name)?;
+            writer.write_char('>')?;
         }
+        Ok(())
+    }
+
+    pub fn render_closing(&self) -> String {
+        let mut html = String::new();
+        let _ = self.write_closing(&mut html);
+        html
     }
 }
 
 macro_rules! define_tag_plugin {
     ($struct_name:ident, $type_name:expr, $tag_name:expr, $is_self_closing:expr) => {
+        #[derive(Default)]
         pub struct $struct_name;
 
         impl Plugin for $struct_name {
             fn render(
                 &self,
                 node: &Value,
-                plugins: &HashMap<String, Box<dyn Plugin>>,
+                ctx: &mut RenderContext,
             ) -> Result<std::string::String, ProseMirrorError> {
                 let mut output = String::new();
-                if let Some(content) = node.get("content") {
-                    for child_node in content.as_array().unwrap() {
-                        if let Some(child_node_type) = child_node.get("type") {
-                            if let Some(plugin) = plugins.get(child_node_type.as_str().unwrap()) {
-                                output.push_str(&plugin.render(child_node, plugins)?);
-                            }
-                        }
-                    }
-                }
+                self.render_to(node, ctx, &mut output)?;
+                Ok(output)
+            }
 
-                let tag = self.get_tag();
-                Ok(tag.render(output, node))
+            fn render_to(
+                &self,
+                node: &Value,
+                ctx: &mut RenderContext,
+                writer: &mut dyn fmt::Write,
+            ) -> Result<(), ProseMirrorError> {
+                let attrs = ctx.node_attrs($type_name, node);
+
+                let tag = self.get_tag(ctx);
+                tag.write_opening(attrs.as_ref(), writer)?;
+                ctx.render_content_to(node, writer)?;
+                tag.write_closing(writer)?;
+                Ok(())
             }
         }
 
         impl $struct_name {
-            fn get_tag(&self) -> Tag {
-                Tag::new($tag_name, $is_self_closing)
+            fn get_tag<'a>(&self, ctx: &RenderContext<'a>) -> Tag<'a> {
+                ctx.tag_for($type_name, $tag_name, $is_self_closing)
             }
 
-            pub fn new() -> Box<dyn Plugin> {
-                Box::new(Self)
+            pub fn new() -> Self {
+                Self
             }
 
             pub fn type_name() -> &'static str {
@@ -122,7 +343,7 @@ macro_rules! define_tag_plugin {
             }
 
             pub fn register(prosemirror: &mut ProseMirror) {
-                prosemirror.add_plugin($type_name, $struct_name::new());
+                prosemirror.add_plugin($type_name, Box::new($struct_name::new()));
             }
         }
     };
@@ -130,23 +351,116 @@ macro_rules! define_tag_plugin {
 
 define_tag_plugin!(DocPlugin, "doc", "div", false);
 define_tag_plugin!(ParagraphPlugin, "paragraph", "p", false);
-define_tag_plugin!(ImagePlugin, "image", "img", true);
+define_tag_plugin!(BulletListPlugin, "bulletList", "ul", false);
+define_tag_plugin!(ListItemPlugin, "listItem", "li", false);
+define_tag_plugin!(BlockquotePlugin, "blockquote", "blockquote", false);
+define_tag_plugin!(HorizontalRulePlugin, "horizontalRule", "hr", true);
+define_tag_plugin!(HardBreakPlugin, "hardBreak", "br", true);
+define_tag_plugin!(TableRowPlugin, "tableRow", "tr", false);
+define_tag_plugin!(DetailsSummaryPlugin, "detailsSummary", "summary", false);
+
+// The root `doc` as bare children, the way Tiptap's `generateHTML` returns it
+#[derive(Default)]
+pub struct FragmentDocPlugin;
+
+impl Plugin for FragmentDocPlugin {
+    fn render(&self, node: &Value, ctx: &mut RenderContext) -> Result<String, ProseMirrorError> {
+        ctx.render_content(node)
+    }
+
+    fn render_to(
+        &self,
+        node: &Value,
+        ctx: &mut RenderContext,
+        writer: &mut dyn fmt::Write,
+    ) -> Result<(), ProseMirrorError> {
+        ctx.render_content_to(node, writer)
+    }
+}
+
+impl FragmentDocPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn type_name() -> &'static str {
+        "doc"
+    }
+
+    pub fn register(prosemirror: &mut ProseMirror) {
+        prosemirror.add_plugin("doc", Box::new(FragmentDocPlugin::new()));
+    }
+}
+
+pub fn register_defaults(prosemirror: &mut ProseMirror) {
+    DocPlugin::register(prosemirror);
+    ParagraphPlugin::register(prosemirror);
+    TextPlugin::register(prosemirror);
+    ImagePlugin::register(prosemirror);
+    CodeBlockPlugin::register(prosemirror);
+    HeadingPlugin::register(prosemirror);
+    BulletListPlugin::register(prosemirror);
+    OrderedListPlugin::register(prosemirror);
+    ListItemPlugin::register(prosemirror);
+    BlockquotePlugin::register(prosemirror);
+    HorizontalRulePlugin::register(prosemirror);
+    HardBreakPlugin::register(prosemirror);
+
+    marks::register_defaults(prosemirror);
+}
+
+type RegisterSet = fn(&mut ProseMirror);
+
+// Named groups of plugins, for picking them in configuration instead of code.
+// `all` registers every one of them in this order.
+pub const PLUGIN_SETS: [(&str, RegisterSet); 12] = [
+    ("starter-kit", register_defaults),
+    ("tables", register_table_plugins),
+    ("task-lists", register_task_list_plugins),
+    ("details", register_details_plugins),
+    ("media", register_media_plugins),
+    ("footnotes", register_footnote_plugins),
+    ("math", MathPlugin::register),
+    ("callouts", CalloutPlugin::register),
+    ("mentions", MentionPlugin::register),
+    ("emoji", EmojiPlugin::register),
+    ("iframes", IframePlugin::register),
+    ("formatting", marks::register_formatting_marks),
+];
+
+// False when there's no set with that name
+pub fn register_plugin_set(prosemirror: &mut ProseMirror, name: &str) -> bool {
+    if name == "all" {
+        for (_, register) in PLUGIN_SETS {
+            register(prosemirror);
+        }
+        return true;
+    }
+    match PLUGIN_SETS.iter().find(|(set, _)| *set == name) {
+        Some((_, register)) => {
+            register(prosemirror);
+            true
+        }
+        None => false,
+    }
+}
 
 #[cfg(test)]
 mod tests {
-    use crate::plugins::text::TextPlugin;
-
     use super::*;
+    use crate::options::{AttrCoercion, AttrOrder, ObjectAttrs, SerializationStyle};
+    use crate::{AttrPolicy, TextAlignOutput};
     use serde_json::json;
 
     #[test]
     fn it_throws_error_on_unknown_type() {
         let content = json!({"type":"doc","content":[]});
-        let prose_mirror = ProseMirror::new(content);
+        let prose_mirror = ProseMirror::new();
 
         assert_eq!(
-            prose_mirror.render().unwrap_err(),
+            prose_mirror.render(&content).unwrap_err(),
             ProseMirrorError::TypeNotFound {
+                path: String::new(),
                 type_name: Some("doc".to_owned())
             }
         );
@@ -155,14 +469,14 @@ mod tests {
     #[test]
     fn it_renders_paragraph_plugin() {
         let content = json!({"type":"doc","content":[{"type":"paragraph","content":[{"text":"This is a comment on the Leafs thread","type":"text"}]}]});
-        let mut prose_mirror = ProseMirror::new(content);
+        let mut prose_mirror = ProseMirror::new();
 
         DocPlugin::register(&mut prose_mirror);
         ParagraphPlugin::register(&mut prose_mirror);
         TextPlugin::register(&mut prose_mirror);
 
         assert_eq!(
-            prose_mirror.render().unwrap(),
+            prose_mirror.render(&content).unwrap(),
             "<div><p>This is a comment on the Leafs thread</p></div>".to_string()
         );
     }
@@ -170,18 +484,223 @@ mod tests {
     #[test]
     fn it_renders_paragraph_plugin_with_attrs() {
         let content = json!({"type":"doc","content":[{"type":"paragraph","attrs": {"class": "test"}, "content":[{"text":"This is a comment on the Leafs thread","type":"text"}]}]});
-        let mut prose_mirror = ProseMirror::new(content);
+        let mut prose_mirror = ProseMirror::new();
 
         DocPlugin::register(&mut prose_mirror);
         ParagraphPlugin::register(&mut prose_mirror);
         TextPlugin::register(&mut prose_mirror);
 
         assert_eq!(
-            prose_mirror.render().unwrap(),
+            prose_mirror.render(&content).unwrap(),
             "<div><p class=\"test\">This is a comment on the Leafs thread</p></div>".to_string()
         );
     }
 
+    #[test]
+    fn it_renders_multiple_documents_with_one_renderer() {
+        let mut prose_mirror = ProseMirror::new();
+
+        DocPlugin::register(&mut prose_mirror);
+        ParagraphPlugin::register(&mut prose_mirror);
+        TextPlugin::register(&mut prose_mirror);
+
+        let first = json!({"type":"doc","content":[{"type":"paragraph","content":[{"text":"First","type":"text"}]}]});
+        let second = json!({"type":"doc","content":[{"type":"paragraph","content":[{"text":"Second","type":"text"}]}]});
+
+        assert_eq!(
+            prose_mirror.render(&first).unwrap(),
+            "<div><p>First</p></div>".to_string()
+        );
+        assert_eq!(
+            prose_mirror.render(&second).unwrap(),
+            "<div><p>Second</p></div>".to_string()
+        );
+    }
+
+    #[test]
+    fn it_renders_with_default_plugins() {
+        let content = json!({"type":"doc","content":[{"type":"paragraph","content":[{"text":"Defaults","type":"text"}]}]});
+        let prose_mirror = ProseMirror::with_default_plugins();
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div><p>Defaults</p></div>".to_string()
+        );
+    }
+
+    #[test]
+    fn it_renders_blockquote_and_horizontal_rule() {
+        let content = json!({"type":"doc","content":[
+            {"type":"blockquote","content":[{"type":"paragraph","content":[{"text":"Quoted","type":"text"}]}]},
+            {"type":"horizontalRule"}
+        ]});
+        let prose_mirror = ProseMirror::with_default_plugins();
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div><blockquote><p>Quoted</p></blockquote><hr /></div>".to_string()
+        );
+    }
+
+    #[test]
+    fn it_renders_hard_break() {
+        let content = json!({"type":"paragraph","content":[
+            {"text":"Go","type":"text"},
+            {"type":"hardBreak"},
+            {"text":"Leafs","type":"text"}
+        ]});
+        let prose_mirror = ProseMirror::with_default_plugins();
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<p>Go<br />Leafs</p>".to_string()
+        );
+    }
+
+    #[test]
+    fn it_escapes_text_and_attrs() {
+        let content = json!({"type":"paragraph","attrs":{"title":"\"><script>"},"content":[{"text":"<script>alert('Habs & Sens')</script>","type":"text"}]});
+        let prose_mirror = ProseMirror::with_default_plugins();
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<p title=\"&quot;&gt;&lt;script&gt;\">&lt;script&gt;alert(&#39;Habs &amp; Sens&#39;)&lt;/script&gt;</p>".to_string()
+        );
+    }
+
+    #[test]
+    fn it_skips_escaping_for_trusted_content() {
+        let content =
+            json!({"type":"paragraph","content":[{"text":"<em>trusted</em>","type":"text"}]});
+        let prose_mirror = ProseMirror::builder()
+            .with_defaults()
+            .escape_html(false)
+            .build();
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<p><em>trusted</em></p>".to_string()
+        );
+    }
+
+    #[test]
+    fn it_keeps_empty_attrs_per_node_type() {
+        let content = json!({"type":"image","attrs":{"alt":null,"src":"/leafs.png","title":null}});
+        let prose_mirror = ProseMirror::builder()
+            .with_defaults()
+            .attr_policy("image", AttrPolicy::keep_empty(&["alt"]))
+            .build();
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<img alt=\"\" decoding=\"async\" loading=\"lazy\" src=\"/leafs.png\" />".to_string()
+        );
+    }
+
+    #[test]
+    fn it_reports_malformed_nodes_instead_of_panicking() {
+        let prose_mirror = ProseMirror::with_default_plugins();
+        let cases = [
+            (
+                json!({"type":"doc","content":[{"type":7}]}),
+                "/content/0",
+                "`type` must be a string",
+            ),
+            (
+                json!({"type":"doc","content":"oops"}),
+                "",
+                "`content` must be an array",
+            ),
+            (
+                json!({"type":"doc","content":[{"type":"paragraph","content":[{"type":"text","text":5}]}]}),
+                "/content/0/content/0",
+                "`text` must be a string",
+            ),
+            (
+                json!({"type":"doc","content":[{"type":"paragraph","content":[{"type":"text","text":"x","marks":{}}]}]}),
+                "/content/0/content/0",
+                "`marks` must be an array",
+            ),
+        ];
+
+        for (content, path, reason) in cases {
+            assert_eq!(
+                prose_mirror.render(&content).unwrap_err(),
+                ProseMirrorError::InvalidNodeShape {
+                    path: path.to_owned(),
+                    reason: reason.to_owned()
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn it_reports_path_of_failing_node() {
+        let content = json!({"type":"doc","content":[
+            {"type":"paragraph"},
+            {"type":"blockquote","content":[
+                {"type":"paragraph","content":[
+                    {"type":"text","text":"ok"},
+                    {"type":"text","text":"bad","marks":[{"type":"bold"},{"type":"link","attrs":{"href":"javascript:alert(1)"}}]}
+                ]}
+            ]}
+        ]});
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        prose_mirror.options_mut().url_policy.action = crate::UnsafeUrlAction::Reject;
+
+        let err = prose_mirror.render(&content).unwrap_err();
+        assert_eq!(err.path(), "/content/1/content/0/content/1/marks/1");
+        assert_eq!(
+            err,
+            ProseMirrorError::UnsafeUrl {
+                path: "/content/1/content/0/content/1/marks/1".to_owned(),
+                url: "javascript:alert(1)".to_owned()
+            }
+        );
+    }
+
+    #[test]
+    fn it_renders_into_writers() {
+        let content = json!({"type":"doc","content":[
+            {"type":"heading","attrs":{"level":1},"content":[{"type":"text","text":"Leafs"}]},
+            {"type":"bulletList","content":[{"type":"listItem","content":[{"type":"paragraph","content":[{"type":"text","text":"Cup"}]}]}]}
+        ]});
+        let prose_mirror = ProseMirror::with_default_plugins();
+        let expected = "<div><h1>Leafs</h1><ul><li><p>Cup</p></li></ul></div>";
+
+        let mut output = String::new();
+        prose_mirror.render_to(&content, &mut output).unwrap();
+        assert_eq!(output, expected);
+
+        let mut bytes: Vec<u8> = vec![];
+        prose_mirror.render_to_io(&content, &mut bytes).unwrap();
+        assert_eq!(String::from_utf8(bytes).unwrap(), expected);
+    }
+
+    #[test]
+    fn it_reports_io_errors() {
+        struct Full;
+        impl std::io::Write for Full {
+            fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::other("disk full"))
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let content = json!({"type":"doc","content":[{"type":"paragraph"}]});
+        let prose_mirror = ProseMirror::with_default_plugins();
+
+        assert_eq!(
+            prose_mirror.render_to_io(&content, &mut Full).unwrap_err(),
+            ProseMirrorError::WriteFailed {
+                path: String::new(),
+                reason: "disk full".to_owned()
+            }
+        );
+    }
+
     #[test]
     fn image() {
         let content = json!({
@@ -193,10 +712,132 @@ mod tests {
           }
         });
 
-        let mut prose_mirror = ProseMirror::new(content);
+        let mut prose_mirror = ProseMirror::new();
 
         ImagePlugin::register(&mut prose_mirror);
 
-        assert_eq!(prose_mirror.render().unwrap(), "<img alt=\"PAPI SIGNS EXTENSION 😏\" src=\"https://pbs.twimg.com/media/F4PrVzTXwAAADiF?format=jpg&name=large\" title=\"\" />".to_owned());
+        assert_eq!(prose_mirror.render(&content).unwrap(), "<img alt=\"PAPI SIGNS EXTENSION 😏\" decoding=\"async\" loading=\"lazy\" src=\"https://pbs.twimg.com/media/F4PrVzTXwAAADiF?format=jpg&amp;name=large\" />".to_owned());
+    }
+
+    #[test]
+    fn it_renders_text_align() {
+        let content = json!({"type":"doc","content":[
+            {"type":"paragraph","attrs":{"textAlign":"center","style":"color: red"},"content":[{"text":"Centre","type":"text"}]},
+            {"type":"heading","attrs":{"level":2,"textAlign":"left"},"content":[{"text":"Left","type":"text"}]},
+            {"type":"paragraph","attrs":{"textAlign":"\"><script>"}}
+        ]});
+
+        assert_eq!(
+            ProseMirror::with_default_plugins()
+                .render(&content)
+                .unwrap(),
+            "<div><p style=\"color: red; text-align: center\">Centre</p><h2>Left</h2><p></p></div>"
+                .to_string()
+        );
+
+        let prose_mirror = ProseMirror::builder()
+            .with_defaults()
+            .text_align(TextAlignOutput::Class("text-".to_string()))
+            .build();
+        assert!(prose_mirror
+            .render(&content)
+            .unwrap()
+            .starts_with("<div><p class=\"text-center\" style=\"color: red\">"));
+    }
+
+    #[test]
+    fn it_renders_boolean_attrs_bare() {
+        let mut attrs = Map::new();
+        attrs.insert("checked".to_string(), json!(true));
+        attrs.insert("disabled".to_string(), json!(false));
+        attrs.insert("readonly".to_string(), json!("readonly"));
+        attrs.insert("value".to_string(), json!(true));
+        assert_eq!(
+            Tag::new("input", true).render_opening(Some(&attrs)),
+            "<input checked readonly value=\"true\" />".to_string()
+        );
+
+        let mut options = RenderOptions::new();
+        options.boolean_attrs = vec!["value".to_string()];
+        assert_eq!(
+            Tag::with_options("input", true, &options).render_opening(Some(&attrs)),
+            "<input checked=\"true\" disabled=\"false\" readonly=\"readonly\" value />".to_string()
+        );
+    }
+
+    #[test]
+    fn it_coerces_attr_values() {
+        let mut attrs = Map::new();
+        attrs.insert("colwidth".to_string(), json!([100, null, 200]));
+        attrs.insert("rowspan".to_string(), json!(2));
+        attrs.insert("meta".to_string(), json!({"a": 1}));
+        attrs.insert("data-meta".to_string(), json!({"a": 1}));
+        assert_eq!(
+            Tag::with_options("td", false, &RenderOptions::new()).render_opening(Some(&attrs)),
+            "<td colwidth=\"[100,null,200]\" data-meta=\"{&quot;a&quot;:1}\" meta=\"{&quot;a&quot;:1}\" rowspan=\"2\">"
+        );
+
+        let mut options = RenderOptions::new();
+        options.attr_values = AttrCoercion::new()
+            .with_array_separator(",")
+            .with_objects(ObjectAttrs::JsonInDataAttrs);
+        assert_eq!(
+            Tag::with_options("td", false, &options).render_opening(Some(&attrs)),
+            "<td colwidth=\"100,200\" data-meta=\"{&quot;a&quot;:1}\" rowspan=\"2\">"
+        );
+    }
+
+    #[test]
+    fn it_orders_attrs() {
+        let mut attrs = Map::new();
+        attrs.insert("src".to_string(), json!("/a.png"));
+        attrs.insert("alt".to_string(), json!("A"));
+        attrs.insert("width".to_string(), json!(10));
+
+        let mut options = RenderOptions::new();
+        assert_eq!(
+            Tag::with_options("img", true, &options).render_opening(Some(&attrs)),
+            "<img alt=\"A\" src=\"/a.png\" width=\"10\" />".to_string()
+        );
+
+        options.attr_order = AttrOrder::Explicit(
+            [(
+                "img".to_string(),
+                vec!["src".to_string(), "width".to_string()],
+            )]
+            .into_iter()
+            .collect(),
+        );
+        assert_eq!(
+            Tag::with_options("img", true, &options).render_opening(Some(&attrs)),
+            "<img src=\"/a.png\" width=\"10\" alt=\"A\" />".to_string()
+        );
+        assert_eq!(
+            Tag::with_options("video", true, &options).render_opening(Some(&attrs)),
+            "<video alt=\"A\" src=\"/a.png\" width=\"10\" />".to_string()
+        );
+    }
+
+    #[test]
+    fn it_serializes_void_elements() {
+        let content = json!({"type":"paragraph","content":[
+            {"text":"Go","type":"text"},
+            {"type":"hardBreak"},
+            {"type":"image","attrs":{"src":"/leafs.png"}}
+        ]});
+
+        let prose_mirror = ProseMirror::builder()
+            .with_defaults()
+            .serialization(SerializationStyle::Html5)
+            .build();
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<p>Go<br><img decoding=\"async\" loading=\"lazy\" src=\"/leafs.png\"></p>".to_string()
+        );
+
+        let output = ProseMirror::with_default_plugins()
+            .render(&content)
+            .unwrap();
+        assert!(output.starts_with("<p>Go<br /><img "));
     }
 }
@@ -0,0 +1,122 @@
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::error::ProseMirrorError;
+use crate::{ProseMirror, RenderContext};
+
+use super::Plugin;
+
+#[derive(Default)]
+pub struct OrderedListPlugin;
+
+impl Plugin for OrderedListPlugin {
+    fn render(&self, node: &Value, ctx: &mut RenderContext) -> Result<String, ProseMirrorError> {
+        let mut output = String::new();
+        self.render_to(node, ctx, &mut output)?;
+        Ok(output)
+    }
+
+    fn render_to(
+        &self,
+        node: &Value,
+        ctx: &mut RenderContext,
+        writer: &mut dyn fmt::Write,
+    ) -> Result<(), ProseMirrorError> {
+        let mut attrs = ctx.node_attrs("orderedList", node).unwrap_or_default();
+
+        // Like Tiptap, only emit `start` when the list doesn't begin at 1
+        if attrs.get("start").and_then(|start| start.as_f64()) == Some(1.0) {
+            attrs.remove("start");
+        }
+
+        let tag = ctx.tag_for("orderedList", "ol", false);
+        tag.write_opening(Some(&attrs), writer)?;
+        ctx.render_content_to(node, writer)?;
+        tag.write_closing(writer)?;
+        Ok(())
+    }
+}
+
+impl OrderedListPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn type_name() -> &'static str {
+        "orderedList"
+    }
+
+    pub fn register(prosemirror: &mut ProseMirror) {
+        prosemirror.add_plugin("orderedList", Box::new(OrderedListPlugin::new()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_honors_start_attr() {
+        let prose_mirror = ProseMirror::with_default_plugins();
+
+        let content = json!({"type":"orderedList","attrs":{"start":1},"content":[{"type":"listItem","content":[{"type":"paragraph","content":[{"type":"text","text":"One"}]}]}]});
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<ol><li><p>One</p></li></ol>".to_string()
+        );
+
+        let content = json!({"type":"orderedList","attrs":{"start":3},"content":[{"type":"listItem","content":[{"type":"paragraph","content":[{"type":"text","text":"Three"}]}]}]});
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<ol start=\"3\"><li><p>Three</p></li></ol>".to_string()
+        );
+    }
+
+    #[test]
+    fn it_renders_nested_lists() {
+        let prose_mirror = ProseMirror::with_default_plugins();
+        let content = json!({"type":"bulletList","content":[
+            {"type":"listItem","content":[
+                {"type":"paragraph","content":[{"type":"text","text":"Leafs"}]},
+                {"type":"orderedList","attrs":{"start":1},"content":[{"type":"listItem","content":[{"type":"paragraph","content":[{"type":"text","text":"Matthews"}]}]}, {"type":"listItem","content":[{"type":"paragraph","content":[{"type":"text","text":"Marner"}]}]}]}
+            ]},
+            {"type":"listItem","content":[{"type":"paragraph","content":[{"type":"text","text":"Habs"}]}]}
+        ]});
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<ul><li><p>Leafs</p><ol><li><p>Matthews</p></li><li><p>Marner</p></li></ol></li><li><p>Habs</p></li></ul>"
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn it_handles_empty_lists_and_odd_starts() {
+        let prose_mirror = ProseMirror::with_default_plugins();
+
+        assert_eq!(
+            prose_mirror.render(&json!({"type":"orderedList"})).unwrap(),
+            "<ol></ol>"
+        );
+        assert_eq!(
+            prose_mirror
+                .render(&json!({"type":"orderedList","attrs":{"start":1.0},"content":[]}))
+                .unwrap(),
+            "<ol></ol>"
+        );
+        assert_eq!(
+            prose_mirror
+                .render(&json!({"type":"orderedList","attrs":{"start":0}}))
+                .unwrap(),
+            "<ol start=\"0\"></ol>"
+        );
+        assert_eq!(
+            prose_mirror
+                .render(&json!({"type":"orderedList","attrs":{"start":"\"><b>"}}))
+                .unwrap(),
+            "<ol start=\"&quot;&gt;&lt;b&gt;\"></ol>"
+        );
+    }
+}
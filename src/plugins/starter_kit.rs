@@ -0,0 +1,226 @@
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::error::ProseMirrorError;
+use crate::RenderContext;
+
+use super::marks::{
+    BoldMarkPlugin, CodeMarkPlugin, ItalicMarkPlugin, LinkMarkPlugin, StrikeMarkPlugin,
+    UnderlineMarkPlugin,
+};
+use super::{
+    BlockquotePlugin, BulletListPlugin, CodeBlockPlugin, DocPlugin, HardBreakPlugin, HeadingPlugin,
+    HorizontalRulePlugin, ImagePlugin, ListItemPlugin, MarkPlugin, OrderedListPlugin,
+    ParagraphPlugin, Plugin, TextPlugin,
+};
+
+// Calls the same method on whichever plugin the variant holds
+macro_rules! dispatch {
+    ($value:expr, $enum:ident { $($variant:ident),* }, $plugin:ident => $call:expr) => {
+        match $value {
+            $($enum::$variant($plugin) => $call,)*
+        }
+    };
+}
+
+// The default node plugins as one enum, rendering a built-in node is a match and a
+// direct call instead of a HashMap lookup and a call through a vtable
+pub enum StarterKitNode {
+    Doc(DocPlugin),
+    Paragraph(ParagraphPlugin),
+    Text(TextPlugin),
+    Image(ImagePlugin),
+    CodeBlock(CodeBlockPlugin),
+    Heading(HeadingPlugin),
+    BulletList(BulletListPlugin),
+    OrderedList(OrderedListPlugin),
+    ListItem(ListItemPlugin),
+    Blockquote(BlockquotePlugin),
+    HorizontalRule(HorizontalRulePlugin),
+    HardBreak(HardBreakPlugin),
+}
+
+const NODE_TYPES: [&str; 12] = [
+    "doc",
+    "paragraph",
+    "text",
+    "image",
+    "codeBlock",
+    "heading",
+    "bulletList",
+    "orderedList",
+    "listItem",
+    "blockquote",
+    "horizontalRule",
+    "hardBreak",
+];
+
+impl StarterKitNode {
+    fn for_index(index: usize) -> Self {
+        match index {
+            0 => Self::Doc(DocPlugin::new()),
+            1 => Self::Paragraph(ParagraphPlugin::new()),
+            2 => Self::Text(TextPlugin::new()),
+            3 => Self::Image(ImagePlugin::new()),
+            4 => Self::CodeBlock(CodeBlockPlugin::new()),
+            5 => Self::Heading(HeadingPlugin::new()),
+            6 => Self::BulletList(BulletListPlugin::new()),
+            7 => Self::OrderedList(OrderedListPlugin::new()),
+            8 => Self::ListItem(ListItemPlugin::new()),
+            9 => Self::Blockquote(BlockquotePlugin::new()),
+            10 => Self::HorizontalRule(HorizontalRulePlugin::new()),
+            _ => Self::HardBreak(HardBreakPlugin::new()),
+        }
+    }
+}
+
+impl Plugin for StarterKitNode {
+    fn render(&self, node: &Value, ctx: &mut RenderContext) -> Result<String, ProseMirrorError> {
+        dispatch!(self, StarterKitNode {
+            Doc, Paragraph, Text, Image, CodeBlock, Heading, BulletList, OrderedList,
+            ListItem, Blockquote, HorizontalRule, HardBreak
+        }, plugin => plugin.render(node, ctx))
+    }
+
+    fn render_to(
+        &self,
+        node: &Value,
+        ctx: &mut RenderContext,
+        writer: &mut dyn fmt::Write,
+    ) -> Result<(), ProseMirrorError> {
+        dispatch!(self, StarterKitNode {
+            Doc, Paragraph, Text, Image, CodeBlock, Heading, BulletList, OrderedList,
+            ListItem, Blockquote, HorizontalRule, HardBreak
+        }, plugin => plugin.render_to(node, ctx, writer))
+    }
+}
+
+pub enum StarterKitMark {
+    Bold(BoldMarkPlugin),
+    Italic(ItalicMarkPlugin),
+    Strike(StrikeMarkPlugin),
+    Underline(UnderlineMarkPlugin),
+    Code(CodeMarkPlugin),
+    Link(LinkMarkPlugin),
+}
+
+const MARK_TYPES: [&str; 6] = ["bold", "italic", "strike", "underline", "code", "link"];
+
+impl StarterKitMark {
+    fn for_index(index: usize) -> Self {
+        match index {
+            0 => Self::Bold(BoldMarkPlugin::new()),
+            1 => Self::Italic(ItalicMarkPlugin::new()),
+            2 => Self::Strike(StrikeMarkPlugin::new()),
+            3 => Self::Underline(UnderlineMarkPlugin::new()),
+            4 => Self::Code(CodeMarkPlugin::new()),
+            _ => Self::Link(LinkMarkPlugin::new()),
+        }
+    }
+}
+
+impl MarkPlugin for StarterKitMark {
+    fn render(
+        &self,
+        output: String,
+        mark: &Value,
+        ctx: &mut RenderContext,
+    ) -> Result<String, ProseMirrorError> {
+        dispatch!(self, StarterKitMark {
+            Bold, Italic, Strike, Underline, Code, Link
+        }, plugin => plugin.render(output, mark, ctx))
+    }
+}
+
+// The whole set, a slot is emptied once a plugin is registered for its type the
+// regular way so that one wins
+pub(crate) struct StaticPlugins {
+    nodes: Vec<Option<StarterKitNode>>,
+    marks: Vec<Option<StarterKitMark>>,
+}
+
+impl StaticPlugins {
+    pub(crate) fn starter_kit() -> Self {
+        Self {
+            nodes: (0..NODE_TYPES.len())
+                .map(|index| Some(StarterKitNode::for_index(index)))
+                .collect(),
+            marks: (0..MARK_TYPES.len())
+                .map(|index| Some(StarterKitMark::for_index(index)))
+                .collect(),
+        }
+    }
+
+    pub(crate) fn node(&self, node_type: &str) -> Option<&StarterKitNode> {
+        let index = NODE_TYPES.iter().position(|t| *t == node_type)?;
+        self.nodes[index].as_ref()
+    }
+
+    pub(crate) fn mark(&self, mark_type: &str) -> Option<&StarterKitMark> {
+        let index = MARK_TYPES.iter().position(|t| *t == mark_type)?;
+        self.marks[index].as_ref()
+    }
+
+    pub(crate) fn remove_node(&mut self, node_type: &str) {
+        if let Some(index) = NODE_TYPES.iter().position(|t| *t == node_type) {
+            self.nodes[index] = None;
+        }
+    }
+
+    pub(crate) fn remove_mark(&mut self, mark_type: &str) {
+        if let Some(index) = MARK_TYPES.iter().position(|t| *t == mark_type) {
+            self.marks[index] = None;
+        }
+    }
+
+    pub(crate) fn node_types(&self) -> impl Iterator<Item = &'static str> + '_ {
+        NODE_TYPES
+            .iter()
+            .zip(&self.nodes)
+            .filter(|(_, plugin)| plugin.is_some())
+            .map(|(node_type, _)| *node_type)
+    }
+
+    pub(crate) fn mark_types(&self) -> impl Iterator<Item = &'static str> + '_ {
+        MARK_TYPES
+            .iter()
+            .zip(&self.marks)
+            .filter(|(_, plugin)| plugin.is_some())
+            .map(|(mark_type, _)| *mark_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ProseMirror;
+    use serde_json::json;
+
+    #[test]
+    fn it_renders_like_the_dynamic_defaults() {
+        let content = json!({"type":"doc","content":[
+            {"type":"heading","attrs":{"level":2},"content":[{"type":"text","text":"Leafs"}]},
+            {"type":"paragraph","content":[
+                {"type":"text","text":"Go","marks":[{"type":"bold"},{"type":"link","attrs":{"href":"https://nhl.com"}}]},
+                {"type":"hardBreak"}
+            ]},
+            {"type":"bulletList","content":[{"type":"listItem","content":[{"type":"paragraph"}]}]},
+            {"type":"image","attrs":{"src":"/rink.png"}}
+        ]});
+        let static_plugins = ProseMirror::with_static_plugins();
+
+        assert_eq!(
+            static_plugins.render(&content).unwrap(),
+            ProseMirror::with_default_plugins()
+                .render(&content)
+                .unwrap()
+        );
+
+        let mut overridden = ProseMirror::with_static_plugins();
+        overridden.add_fn_plugin("hardBreak", |_, _| Ok("<wbr>".to_string()));
+        assert!(overridden
+            .render(&content)
+            .unwrap()
+            .contains("Go</a></strong><wbr></p>"));
+    }
+}
@@ -0,0 +1,268 @@
+use std::fmt;
+
+use serde_json::{Map, Value};
+
+use crate::error::ProseMirrorError;
+use crate::{ProseMirror, RenderContext};
+
+use super::{Plugin, TableRowPlugin};
+
+// Browsers clamp colspan to this too, it also keeps a bogus value from allocating forever
+const MAX_COLSPAN: u64 = 1000;
+
+#[derive(Default)]
+pub struct TablePlugin;
+
+impl Plugin for TablePlugin {
+    fn render(&self, node: &Value, ctx: &mut RenderContext) -> Result<String, ProseMirrorError> {
+        let mut output = String::new();
+        self.render_to(node, ctx, &mut output)?;
+        Ok(output)
+    }
+
+    fn render_to(
+        &self,
+        node: &Value,
+        ctx: &mut RenderContext,
+        writer: &mut dyn fmt::Write,
+    ) -> Result<(), ProseMirrorError> {
+        let attrs = ctx.node_attrs("table", node);
+
+        let table = ctx.tag_for("table", "table", false);
+        let tbody = ctx.tag("tbody", false);
+        table.write_opening(attrs.as_ref(), writer)?;
+        writer.write_str(&Self::render_colgroup(node, ctx))?;
+        tbody.write_opening(None, writer)?;
+        ctx.render_content_to(node, writer)?;
+        tbody.write_closing(writer)?;
+        table.write_closing(writer)?;
+        Ok(())
+    }
+}
+
+impl TablePlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    // Column widths live on the cells of the first row, one entry per spanned column
+    fn render_colgroup(node: &Value, ctx: &RenderContext) -> String {
+        let first_row = node
+            .get("content")
+            .and_then(|rows| rows.as_array())
+            .and_then(|rows| rows.first())
+            .and_then(|row| row.get("content"))
+            .and_then(|cells| cells.as_array());
+
+        let mut widths: Vec<Option<u64>> = vec![];
+        for cell in first_row.into_iter().flatten() {
+            let attrs = cell.get("attrs");
+            let colspan = span(attrs.and_then(|attrs| attrs.get("colspan"))).unwrap_or(1);
+            let colwidth = attrs
+                .and_then(|attrs| attrs.get("colwidth"))
+                .and_then(|colwidth| colwidth.as_array());
+
+            for column in 0..colspan as usize {
+                widths.push(
+                    colwidth
+                        .and_then(|colwidth| colwidth.get(column))
+                        .and_then(|width| width.as_u64()),
+                );
+            }
+        }
+
+        if widths.iter().all(|width| width.is_none()) {
+            return String::new();
+        }
+
+        let mut cols = String::new();
+        for width in widths {
+            let mut attrs = Map::new();
+            if let Some(width) = width {
+                attrs.insert(
+                    "style".to_string(),
+                    Value::String(format!("width: {}px", width)),
+                );
+            }
+            cols.push_str(&ctx.tag("col", true).render_opening(Some(&attrs)));
+        }
+        ctx.tag("colgroup", false).render_with_attrs(cols, None)
+    }
+
+    pub fn type_name() -> &'static str {
+        "table"
+    }
+
+    pub fn register(prosemirror: &mut ProseMirror) {
+        prosemirror.add_plugin("table", Box::new(TablePlugin::new()));
+    }
+}
+
+// A colspan or rowspan above the default of 1, as a number or a numeric string
+fn span(value: Option<&Value>) -> Option<u64> {
+    let value = match value? {
+        Value::String(value) => value.trim().parse::<u64>().ok(),
+        value => value.as_u64(),
+    };
+    value
+        .filter(|value| *value > 1)
+        .map(|value| value.min(MAX_COLSPAN))
+}
+
+fn render_cell(
+    tag: &'static str,
+    type_name: &str,
+    node: &Value,
+    ctx: &mut RenderContext,
+) -> Result<String, ProseMirrorError> {
+    let output = ctx.render_content(node)?;
+    let mut attrs = ctx.node_attrs(type_name, node).unwrap_or_default();
+
+    // Widths are emitted once on the table's <colgroup>
+    attrs.remove("colwidth");
+    // 1 is the default, anything that isn't a count is left off
+    for key in ["colspan", "rowspan"] {
+        if let Some(value) = span(attrs.remove(key).as_ref()) {
+            attrs.insert(key.to_string(), Value::from(value));
+        }
+    }
+
+    Ok(ctx
+        .tag_for(type_name, tag, false)
+        .render_with_attrs(output, Some(&attrs)))
+}
+
+#[derive(Default)]
+pub struct TableCellPlugin;
+
+impl Plugin for TableCellPlugin {
+    fn render(&self, node: &Value, ctx: &mut RenderContext) -> Result<String, ProseMirrorError> {
+        render_cell("td", "tableCell", node, ctx)
+    }
+}
+
+impl TableCellPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn type_name() -> &'static str {
+        "tableCell"
+    }
+
+    pub fn register(prosemirror: &mut ProseMirror) {
+        prosemirror.add_plugin("tableCell", Box::new(TableCellPlugin::new()));
+    }
+}
+
+#[derive(Default)]
+pub struct TableHeaderPlugin;
+
+impl Plugin for TableHeaderPlugin {
+    fn render(&self, node: &Value, ctx: &mut RenderContext) -> Result<String, ProseMirrorError> {
+        render_cell("th", "tableHeader", node, ctx)
+    }
+}
+
+impl TableHeaderPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn type_name() -> &'static str {
+        "tableHeader"
+    }
+
+    pub fn register(prosemirror: &mut ProseMirror) {
+        prosemirror.add_plugin("tableHeader", Box::new(TableHeaderPlugin::new()));
+    }
+}
+
+// Tables aren't part of StarterKit, so they're registered separately from the defaults
+pub fn register_table_plugins(prosemirror: &mut ProseMirror) {
+    TablePlugin::register(prosemirror);
+    TableRowPlugin::register(prosemirror);
+    TableCellPlugin::register(prosemirror);
+    TableHeaderPlugin::register(prosemirror);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_renders_tiptap_table() {
+        let content = json!({"type":"table","content":[
+            {"type":"tableRow","content":[
+                {"type":"tableHeader","attrs":{"colspan":1,"rowspan":1,"colwidth":null},"content":[{"type":"paragraph","content":[{"type":"text","text":"Team"}]}]},
+                {"type":"tableHeader","attrs":{"colspan":1,"rowspan":1,"colwidth":null},"content":[{"type":"paragraph","content":[{"type":"text","text":"Points"}]}]}
+            ]},
+            {"type":"tableRow","content":[
+                {"type":"tableCell","attrs":{"colspan":1,"rowspan":1,"colwidth":null},"content":[{"type":"paragraph","content":[{"type":"text","text":"Leafs"}]}]},
+                {"type":"tableCell","attrs":{"colspan":1,"rowspan":1,"colwidth":null},"content":[{"type":"paragraph","content":[{"type":"text","text":"111"}]}]}
+            ]}
+        ]});
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        register_table_plugins(&mut prose_mirror);
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<table><tbody><tr><th><p>Team</p></th><th><p>Points</p></th></tr><tr><td><p>Leafs</p></td><td><p>111</p></td></tr></tbody></table>"
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn it_renders_spans_and_colgroup() {
+        let content = json!({"type":"table","content":[
+            {"type":"tableRow","content":[
+                {"type":"tableCell","attrs":{"colspan":2,"rowspan":1,"colwidth":[100,150]},"content":[{"type":"paragraph","content":[{"type":"text","text":"Wide"}]}]},
+                {"type":"tableCell","attrs":{"colspan":1,"rowspan":2,"colwidth":null},"content":[{"type":"paragraph","content":[{"type":"text","text":"Tall"}]}]}
+            ]}
+        ]});
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        register_table_plugins(&mut prose_mirror);
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<table><colgroup><col style=\"width: 100px\" /><col style=\"width: 150px\" /><col /></colgroup><tbody><tr><td colspan=\"2\"><p>Wide</p></td><td rowspan=\"2\"><p>Tall</p></td></tr></tbody></table>"
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn it_handles_empty_tables_and_bad_spans() {
+        let mut prose_mirror = ProseMirror::new();
+        register_table_plugins(&mut prose_mirror);
+
+        assert_eq!(
+            prose_mirror.render(&json!({"type":"table"})).unwrap(),
+            "<table><tbody></tbody></table>"
+        );
+
+        let content = json!({"type":"table","content":[
+            {"type":"tableRow","content":[
+                {"type":"tableCell","attrs":{"colspan":"x","rowspan":"3","colwidth":["wide",null]}},
+                {"type":"tableHeader","attrs":{"colspan":-2,"rowspan":0}},
+                {"type":"tableCell","attrs":{"colspan":1e9,"rowspan":99999}}
+            ]}
+        ]});
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<table><tbody><tr><td rowspan=\"3\"></td><th></th><td rowspan=\"1000\"></td></tr></tbody></table>"
+        );
+
+        // A colspan given as a string spans as many <col>s as it does cells
+        let content = json!({"type":"table","content":[
+            {"type":"tableRow","content":[
+                {"type":"tableCell","attrs":{"colspan":"2","colwidth":[100,50]}},
+                {"type":"tableCell","attrs":{"colspan":"x","colwidth":[80]}}
+            ]}
+        ]});
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<table><colgroup><col style=\"width: 100px\" /><col style=\"width: 50px\" /><col style=\"width: 80px\" /></colgroup><tbody><tr><td colspan=\"2\"></td><td></td></tr></tbody></table>"
+        );
+    }
+}
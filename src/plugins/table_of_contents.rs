@@ -0,0 +1,286 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::error::ProseMirrorError;
+use crate::model::Node;
+use crate::slug::HeadingAnchors;
+use crate::utils::node_text;
+use crate::{ProseMirror, RenderContext, RenderState};
+
+use super::{HeadingPlugin, Plugin};
+
+// RenderState key the collect pass gathers headings into
+pub(crate) const HEADINGS_STATE: &str = "tableOfContents";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TocEntry {
+    pub level: usize,
+    pub text: String,
+    pub slug: String,
+}
+
+// A heading as seen by the collect pass, before it has a slug
+#[derive(Serialize, Deserialize)]
+pub(crate) struct CollectedHeading {
+    level: usize,
+    text: String,
+    id: Option<String>,
+}
+
+impl CollectedHeading {
+    pub(crate) fn from_node(node: &Node) -> Self {
+        Self {
+            level: HeadingPlugin::level(node.attrs.get("level")),
+            text: node.text_content(),
+            id: node
+                .attrs
+                .get("id")
+                .and_then(|id| id.as_str())
+                .map(|id| id.to_string()),
+        }
+    }
+
+    fn from_value(node: &Value) -> Self {
+        let attrs = node.get("attrs");
+        Self {
+            level: HeadingPlugin::level(attrs.and_then(|attrs| attrs.get("level"))),
+            text: node_text(node),
+            id: attrs
+                .and_then(|attrs| attrs.get("id"))
+                .and_then(|id| id.as_str())
+                .map(|id| id.to_string()),
+        }
+    }
+}
+
+// Hands out slugs in document order, the same way `HeadingPlugin` does, so the
+// TOC links line up with the heading ids
+fn entries(headings: Vec<CollectedHeading>, anchors: &HeadingAnchors) -> Vec<TocEntry> {
+    let mut used: Map<String, Value> = headings
+        .iter()
+        .filter_map(|heading| heading.id.clone())
+        .map(|id| (id, Value::Bool(true)))
+        .collect();
+    headings
+        .into_iter()
+        .map(|heading| {
+            let slug = match heading.id {
+                Some(id) => id,
+                None => anchors.anchor(&heading.text, &mut used),
+            };
+            TocEntry {
+                level: heading.level,
+                text: heading.text,
+                slug,
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn table_of_contents(content: &Value, anchors: &HeadingAnchors) -> Vec<TocEntry> {
+    let mut headings = vec![];
+    let mut stack = vec![content];
+    while let Some(node) = stack.pop() {
+        if node.get("type").and_then(|t| t.as_str()) == Some("heading") {
+            headings.push(CollectedHeading::from_value(node));
+            continue;
+        }
+        if let Some(content) = node.get("content").and_then(|c| c.as_array()) {
+            stack.extend(content.iter().rev());
+        }
+    }
+    entries(headings, anchors)
+}
+
+// Nests each entry under the closest preceding entry with a lower level
+fn render_entries(entries: &[TocEntry], ctx: &RenderContext) -> String {
+    let mut output = String::new();
+    let mut open_levels: Vec<usize> = vec![];
+    for entry in entries {
+        match open_levels.last() {
+            Some(&level) if entry.level <= level => {
+                while open_levels.len() > 1 && entry.level <= open_levels[open_levels.len() - 2] {
+                    output.push_str("</li></ol>");
+                    open_levels.pop();
+                }
+                output.push_str("</li><li>");
+                if let Some(level) = open_levels.last_mut() {
+                    *level = entry.level;
+                }
+            }
+            _ => {
+                output.push_str("<ol><li>");
+                open_levels.push(entry.level);
+            }
+        }
+        output.push_str(&format!(
+            "<a href=\"#{}\">{}</a>",
+            ctx.escape_text(&entry.slug),
+            ctx.escape_text(&entry.text)
+        ));
+    }
+    for _ in open_levels {
+        output.push_str("</li></ol>");
+    }
+    output
+}
+
+// Tiptap's TableOfContents node. It needs the whole document, so registering it
+// turns on the collect pass.
+#[derive(Default)]
+pub struct TableOfContentsPlugin;
+
+impl Plugin for TableOfContentsPlugin {
+    fn render(&self, node: &Value, ctx: &mut RenderContext) -> Result<String, ProseMirrorError> {
+        let headings = ctx
+            .state
+            .get(HEADINGS_STATE)
+            .cloned()
+            .map(serde_json::from_value::<Vec<CollectedHeading>>)
+            .transpose()
+            .map_err(|err| ProseMirrorError::invalid_shape(&err.to_string()))?
+            .unwrap_or_default();
+        let default_anchors = HeadingAnchors::default();
+        let anchors = ctx
+            .options()
+            .heading_anchors
+            .as_ref()
+            .unwrap_or(&default_anchors);
+
+        let mut attrs = ctx.node_attrs("tableOfContents", node).unwrap_or_default();
+        attrs.insert("data-type".to_string(), Value::from("tableOfContents"));
+        let output = render_entries(&entries(headings, anchors), ctx);
+        Ok(ctx
+            .tag_for("tableOfContents", "nav", false)
+            .render_with_attrs(output, Some(&attrs)))
+    }
+
+    fn uses_state(&self) -> bool {
+        true
+    }
+}
+
+impl TableOfContentsPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn type_name() -> &'static str {
+        "tableOfContents"
+    }
+
+    pub fn register(prosemirror: &mut ProseMirror) {
+        prosemirror.add_plugin("tableOfContents", Box::new(TableOfContentsPlugin::new()));
+        let options = prosemirror.options_mut();
+        options.collect_pass = true;
+        options
+            .heading_anchors
+            .get_or_insert_with(HeadingAnchors::default);
+    }
+}
+
+pub(crate) fn collect_heading(node: &Node, state: &mut RenderState) {
+    let heading = json!(CollectedHeading::from_node(node));
+    if let Some(headings) = state
+        .get_or_insert(HEADINGS_STATE, Value::Array(vec![]))
+        .as_array_mut()
+    {
+        headings.push(heading);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_extracts_entries() {
+        let prose_mirror = ProseMirror::with_default_plugins();
+        let content = json!({"type":"doc","content":[
+            {"type":"tableOfContents"},
+            {"type":"heading","attrs":{"level":1},"content":[{"type":"text","text":"Preview"}]},
+            {"type":"heading","attrs":{"level":2},"content":[{"type":"text","text":"Lines"}]},
+            {"type":"heading","attrs":{"level":3},"content":[{"type":"text","text":"Top six"}]},
+            {"type":"heading","attrs":{"level":2},"content":[{"type":"text","text":"Goalies"}]},
+            {"type":"heading","attrs":{"level":1},"content":[{"type":"text","text":"Prediction"}]},
+            {"type":"heading","attrs":{"level":2},"content":[{"type":"text","text":"Lines"}]}
+        ]});
+
+        let slugs: Vec<(usize, String)> = prose_mirror
+            .table_of_contents(&content)
+            .into_iter()
+            .map(|entry| (entry.level, entry.slug))
+            .collect();
+        assert_eq!(
+            slugs,
+            vec![
+                (1, "preview".to_string()),
+                (2, "lines".to_string()),
+                (3, "top-six".to_string()),
+                (2, "goalies".to_string()),
+                (1, "prediction".to_string()),
+                (2, "lines-1".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_renders_a_nested_toc_inline() {
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        TableOfContentsPlugin::register(&mut prose_mirror);
+        let content = json!({"type":"doc","content":[
+            {"type":"tableOfContents"},
+            {"type":"heading","attrs":{"level":1},"content":[{"type":"text","text":"Preview"}]},
+            {"type":"heading","attrs":{"level":2},"content":[{"type":"text","text":"Lines"}]},
+            {"type":"heading","attrs":{"level":3},"content":[{"type":"text","text":"Top six"}]},
+            {"type":"heading","attrs":{"level":2},"content":[{"type":"text","text":"Goalies"}]},
+            {"type":"heading","attrs":{"level":1},"content":[{"type":"text","text":"Prediction"}]},
+            {"type":"heading","attrs":{"level":2},"content":[{"type":"text","text":"Lines"}]}
+        ]});
+
+        let output = prose_mirror.render(&content).unwrap();
+        assert!(output.starts_with(
+            "<div><nav data-type=\"tableOfContents\"><ol><li><a href=\"#preview\">Preview</a><ol><li><a href=\"#lines\">Lines</a><ol><li><a href=\"#top-six\">Top six</a></li></ol></li><li><a href=\"#goalies\">Goalies</a></li></ol></li><li><a href=\"#prediction\">Prediction</a><ol><li><a href=\"#lines-1\">Lines</a></li></ol></li></ol></nav>"
+        ));
+        assert!(output.contains("<h2 id=\"lines-1\">Lines</h2>"));
+    }
+
+    #[test]
+    fn it_nests_skipped_levels_one_deep() {
+        let prose_mirror = ProseMirror::with_default_plugins();
+        let entries = prose_mirror.table_of_contents(&json!({"type":"doc","content":[
+            {"type":"heading","attrs":{"level":3},"content":[{"type":"text","text":"Deep"}]},
+            {"type":"heading","attrs":{"level":1},"content":[{"type":"text","text":"Top"}]},
+            {"type":"heading","attrs":{"level":4},"content":[{"type":"text","text":"Deeper"}]}
+        ]}));
+
+        assert_eq!(
+            render_entries(&entries, &RenderContext::new(&prose_mirror)),
+            "<ol><li><a href=\"#deep\">Deep</a></li><li><a href=\"#top\">Top</a><ol><li><a href=\"#deeper\">Deeper</a></li></ol></li></ol>"
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn it_handles_documents_without_headings() {
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        TableOfContentsPlugin::register(&mut prose_mirror);
+
+        assert_eq!(
+            prose_mirror
+                .render(&json!({"type":"doc","content":[{"type":"tableOfContents"}]}))
+                .unwrap(),
+            "<div><nav data-type=\"tableOfContents\"></nav></div>"
+        );
+        assert_eq!(
+            prose_mirror
+                .render(&json!({"type":"doc","content":[
+                    {"type":"tableOfContents"},
+                    {"type":"heading","content":[{"type":"text","text":"<Leafs> & Habs"}]},
+                    {"type":"heading","attrs":{"level":2}}
+                ]}))
+                .unwrap(),
+            "<div><nav data-type=\"tableOfContents\"><ol><li><a href=\"#leafs-habs\">&lt;Leafs&gt; &amp; Habs</a><ol><li><a href=\"#heading\"></a></li></ol></li></ol></nav><h1 id=\"leafs-habs\">&lt;Leafs&gt; &amp; Habs</h1><h2 id=\"heading\"></h2></div>"
+        );
+    }
+}
@@ -0,0 +1,209 @@
+use std::fmt;
+
+use serde_json::{Map, Value};
+
+use crate::error::ProseMirrorError;
+use crate::{ProseMirror, RenderContext};
+
+use super::Plugin;
+
+#[derive(Default)]
+pub struct TaskListPlugin;
+
+impl Plugin for TaskListPlugin {
+    fn render(&self, node: &Value, ctx: &mut RenderContext) -> Result<String, ProseMirrorError> {
+        let mut output = String::new();
+        self.render_to(node, ctx, &mut output)?;
+        Ok(output)
+    }
+
+    fn render_to(
+        &self,
+        node: &Value,
+        ctx: &mut RenderContext,
+        writer: &mut dyn fmt::Write,
+    ) -> Result<(), ProseMirrorError> {
+        let mut attrs = ctx.node_attrs("taskList", node).unwrap_or_default();
+        attrs.insert("data-type".to_string(), Value::from("taskList"));
+
+        let tag = ctx.tag_for("taskList", "ul", false);
+        tag.write_opening(Some(&attrs), writer)?;
+        ctx.render_content_to(node, writer)?;
+        tag.write_closing(writer)?;
+        Ok(())
+    }
+}
+
+impl TaskListPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn type_name() -> &'static str {
+        "taskList"
+    }
+
+    pub fn register(prosemirror: &mut ProseMirror) {
+        prosemirror.add_plugin("taskList", Box::new(TaskListPlugin::new()));
+    }
+}
+
+#[derive(Default)]
+pub struct TaskItemPlugin {
+    // Read-only output shouldn't look like it can be ticked
+    disabled_checkboxes: bool,
+}
+
+impl Plugin for TaskItemPlugin {
+    fn render(&self, node: &Value, ctx: &mut RenderContext) -> Result<String, ProseMirrorError> {
+        let mut output = String::new();
+        self.render_to(node, ctx, &mut output)?;
+        Ok(output)
+    }
+
+    fn render_to(
+        &self,
+        node: &Value,
+        ctx: &mut RenderContext,
+        writer: &mut dyn fmt::Write,
+    ) -> Result<(), ProseMirrorError> {
+        let mut attrs = ctx.node_attrs("taskItem", node).unwrap_or_default();
+        let checked = attrs
+            .remove("checked")
+            .and_then(|checked| checked.as_bool())
+            .unwrap_or(false);
+        attrs.insert("data-checked".to_string(), Value::from(checked.to_string()));
+        attrs.insert("data-type".to_string(), Value::from("taskItem"));
+
+        let mut input_attrs = Map::new();
+        input_attrs.insert("type".to_string(), Value::from("checkbox"));
+        if checked {
+            input_attrs.insert("checked".to_string(), Value::Bool(true));
+        }
+        if self.disabled_checkboxes {
+            input_attrs.insert("disabled".to_string(), Value::Bool(true));
+        }
+
+        let mut label = ctx.tag("input", true).render_opening(Some(&input_attrs));
+        label.push_str(
+            &ctx.tag("span", false)
+                .render_with_attrs(String::new(), None),
+        );
+
+        let item = ctx.tag_for("taskItem", "li", false);
+        let content = ctx.tag("div", false);
+        item.write_opening(Some(&attrs), writer)?;
+        writer.write_str(&ctx.tag("label", false).render_with_attrs(label, None))?;
+        content.write_opening(None, writer)?;
+        ctx.render_content_to(node, writer)?;
+        content.write_closing(writer)?;
+        item.write_closing(writer)?;
+        Ok(())
+    }
+}
+
+impl TaskItemPlugin {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_disabled_checkboxes(mut self, disabled_checkboxes: bool) -> Self {
+        self.disabled_checkboxes = disabled_checkboxes;
+        self
+    }
+
+    pub fn type_name() -> &'static str {
+        "taskItem"
+    }
+
+    pub fn register(prosemirror: &mut ProseMirror) {
+        prosemirror.add_plugin("taskItem", Box::new(TaskItemPlugin::new()));
+    }
+}
+
+pub fn register_task_list_plugins(prosemirror: &mut ProseMirror) {
+    TaskListPlugin::register(prosemirror);
+    TaskItemPlugin::register(prosemirror);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TagOverrides;
+    use serde_json::json;
+
+    #[test]
+    fn it_renders_task_list() {
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        register_task_list_plugins(&mut prose_mirror);
+        let content = json!({"type":"taskList","content":[
+            {"type":"taskItem","attrs":{"checked":true},"content":[{"type":"paragraph","content":[{"type":"text","text":"Win"}]}]},
+            {"type":"taskItem","attrs":{"checked":false},"content":[{"type":"paragraph","content":[{"type":"text","text":"Cup"}]}]}
+        ]});
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<ul data-type=\"taskList\"><li data-checked=\"true\" data-type=\"taskItem\"><label><input checked type=\"checkbox\" /><span></span></label><div><p>Win</p></div></li><li data-checked=\"false\" data-type=\"taskItem\"><label><input type=\"checkbox\" /><span></span></label><div><p>Cup</p></div></li></ul>"
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn it_renders_disabled_checkboxes() {
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        TaskListPlugin::register(&mut prose_mirror);
+        prose_mirror.add_plugin(
+            "taskItem",
+            Box::new(TaskItemPlugin::new().with_disabled_checkboxes(true)),
+        );
+        let content = json!({"type":"taskList","content":[
+            {"type":"taskItem","attrs":{"checked":true},"content":[{"type":"paragraph","content":[{"type":"text","text":"Win"}]}]},
+            {"type":"taskItem","attrs":{"checked":false},"content":[{"type":"paragraph","content":[{"type":"text","text":"Cup"}]}]}
+        ]});
+
+        let output = prose_mirror.render(&content).unwrap();
+        assert!(output.contains("<input checked disabled type=\"checkbox\" />"));
+        assert!(output.contains("<input disabled type=\"checkbox\" />"));
+    }
+
+    #[test]
+    fn it_leaves_boxes_unchecked_without_a_bool() {
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        register_task_list_plugins(&mut prose_mirror);
+
+        for item in [
+            json!({"type":"taskItem"}),
+            json!({"type":"taskItem","attrs":{"checked":"true"}}),
+            json!({"type":"taskItem","attrs":{"checked":1}}),
+        ] {
+            assert_eq!(
+                prose_mirror.render(&item).unwrap(),
+                "<li data-checked=\"false\" data-type=\"taskItem\"><label><input type=\"checkbox\" /><span></span></label><div></div></li>"
+            );
+        }
+        assert_eq!(
+            prose_mirror.render(&json!({"type":"taskList"})).unwrap(),
+            "<ul data-type=\"taskList\"></ul>"
+        );
+    }
+
+    #[test]
+    fn it_follows_tag_overrides() {
+        let mut prose_mirror = ProseMirror::builder()
+            .with_defaults()
+            .tag_overrides(
+                TagOverrides::new()
+                    .with_tag("taskList", "menu")
+                    .with_tag("taskItem", "task-item"),
+            )
+            .build();
+        register_task_list_plugins(&mut prose_mirror);
+        let content =
+            json!({"type":"taskList","content":[{"type":"taskItem","attrs":{"checked":true}}]});
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<menu data-type=\"taskList\"><task-item data-checked=\"true\" data-type=\"taskItem\"><label><input checked type=\"checkbox\" /><span></span></label><div></div></task-item></menu>"
+        );
+    }
+}
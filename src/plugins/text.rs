@@ -1,33 +1,269 @@
-use std::collections::HashMap;
+use std::fmt;
 
-use serde_json::Value;
+use serde_json::{json, Value};
 
-use crate::{error::ProseMirrorError, ProseMirror};
+use crate::search::find_terms;
+use crate::utils::write_escaped;
+use crate::{error::ProseMirrorError, ProseMirror, RenderContext};
 
+use super::linkify::{find_links, LinkPattern};
 use super::Plugin;
 
-pub struct TextPlugin;
+#[derive(Default)]
+pub struct TextPlugin {
+    // Link bare URLs and email addresses, for content written before the editor
+    // had the Link extension
+    autolink: bool,
+    patterns: Vec<LinkPattern>,
+}
+
 impl Plugin for TextPlugin {
-    fn render(
+    fn render(&self, node: &Value, ctx: &mut RenderContext) -> Result<String, ProseMirrorError> {
+        let output = match node.get("text") {
+            None | Some(Value::Null) => String::new(),
+            Some(Value::String(text)) => {
+                let in_code = has_mark(node, "code");
+                let links_text = self.links_text()
+                    && !in_code
+                    && !has_mark(node, "link")
+                    && ctx.prosemirror().mark_plugin("link").is_some();
+                if links_text {
+                    self.autolink(text, ctx)?
+                } else {
+                    text_html(text, !in_code, ctx)
+                }
+            }
+            Some(_) => return Err(ProseMirrorError::invalid_shape("`text` must be a string")),
+        };
+
+        ctx.render_marks(output, node)
+    }
+
+    // Text without marks is by far the most common node, it's escaped straight
+    // into the output without a String of its own
+    fn render_to(
         &self,
         node: &Value,
-        plugins: &HashMap<String, Box<dyn Plugin>>,
-    ) -> Result<String, ProseMirrorError> {
-        let mut output = String::new();
-        if let Some(text) = node.get("text") {
-            output.push_str(text.as_str().unwrap());
+        ctx: &mut RenderContext,
+        writer: &mut dyn fmt::Write,
+    ) -> Result<(), ProseMirrorError> {
+        let unmarked = match node.get("marks") {
+            None | Some(Value::Null) => true,
+            Some(Value::Array(marks)) => marks.is_empty(),
+            Some(_) => false,
+        };
+        match node.get("text") {
+            Some(Value::String(text))
+                if unmarked && !self.links_text() && ctx.options().highlight_terms.is_empty() =>
+            {
+                if ctx.options().escape_html {
+                    write_escaped(writer, text)?;
+                } else {
+                    writer.write_str(text)?;
+                }
+            }
+            _ => writer.write_str(&self.render(node, ctx)?)?,
         }
+        Ok(())
+    }
+}
 
-        Ok(output)
+fn has_mark(node: &Value, mark_type: &str) -> bool {
+    node.get("marks")
+        .and_then(|marks| marks.as_array())
+        .is_some_and(|marks| {
+            marks
+                .iter()
+                .any(|mark| mark.get("type").and_then(|t| t.as_str()) == Some(mark_type))
+        })
+}
+
+// Escaped, with the search terms marked unless it's code
+fn text_html(text: &str, highlight: bool, ctx: &RenderContext) -> String {
+    let terms = &ctx.options().highlight_terms;
+    if !highlight || terms.is_empty() {
+        return ctx.escape_text(text);
+    }
+    let mut output = String::with_capacity(text.len());
+    let mut last = 0;
+    for (start, end) in find_terms(text, terms) {
+        output.push_str(&ctx.escape_text(&text[last..start]));
+        output.push_str("<mark class=\"search-hit\">");
+        output.push_str(&ctx.escape_text(&text[start..end]));
+        output.push_str("</mark>");
+        last = end;
     }
+    output.push_str(&ctx.escape_text(&text[last..]));
+    output
 }
 
 impl TextPlugin {
-    pub fn new() -> Box<dyn Plugin> {
-        Box::new(Self)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_autolink(mut self, autolink: bool) -> Self {
+        self.autolink = autolink;
+        self
+    }
+
+    // Tried in order, before URLs
+    pub fn with_pattern(mut self, pattern: LinkPattern) -> Self {
+        self.patterns.push(pattern);
+        self
+    }
+
+    fn links_text(&self) -> bool {
+        self.autolink || !self.patterns.is_empty()
+    }
+
+    // Links go through the link mark, so they get the same sanitizing and
+    // rewriting as the ones made in the editor
+    fn autolink(&self, text: &str, ctx: &mut RenderContext) -> Result<String, ProseMirrorError> {
+        let mut output = String::with_capacity(text.len());
+        let mut last = 0;
+        for link in find_links(text, self.autolink, &self.patterns) {
+            output.push_str(&text_html(&text[last..link.start], true, ctx));
+            let mut attrs = json!({ "href": link.href });
+            if let Some(class) = link.class {
+                attrs["class"] = Value::String(class);
+            }
+            let linked = json!({"marks": [{"type": "link", "attrs": attrs}]});
+            output.push_str(
+                &ctx.render_marks(text_html(&text[link.start..link.end], true, ctx), &linked)?,
+            );
+            last = link.end;
+        }
+        output.push_str(&text_html(&text[last..], true, ctx));
+        Ok(output)
     }
 
     pub fn register(prosemirror: &mut ProseMirror) {
-        prosemirror.add_plugin("text", TextPlugin::new());
+        prosemirror.add_plugin("text", Box::new(TextPlugin::new()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::{LinkPattern, MarkPlugin, Tag};
+    use serde_json::json;
+
+    struct WrapMark(&'static str);
+
+    impl MarkPlugin for WrapMark {
+        fn render(
+            &self,
+            output: String,
+            mark: &Value,
+            _ctx: &mut RenderContext,
+        ) -> Result<String, ProseMirrorError> {
+            Ok(Tag::new(self.0, false)
+                .render_with_attrs(output, mark.get("attrs").and_then(|a| a.as_object())))
+        }
+    }
+
+    #[test]
+    fn it_wraps_marks_in_document_order() {
+        let content =
+            json!({"type":"text","text":"Leafs","marks":[{"type":"bold"},{"type":"italic"}]});
+        let mut prose_mirror = ProseMirror::new();
+        TextPlugin::register(&mut prose_mirror);
+        prose_mirror.add_mark_plugin("bold", Box::new(WrapMark("strong")));
+        prose_mirror.add_mark_plugin("italic", Box::new(WrapMark("em")));
+
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<strong><em>Leafs</em></strong>".to_string()
+        );
+    }
+
+    #[test]
+    fn it_autolinks_bare_urls_and_emails() {
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        prose_mirror.add_plugin("text", Box::new(TextPlugin::new().with_autolink(true)));
+
+        let content = json!({"type":"paragraph","content":[
+            {"type":"text","text":"See www.nhl.com/scores, (https://leafs.com/?a=1&b=2) or mail tim@leafs.ca."},
+            {"type":"text","text":"https://nhl.com","marks":[{"type":"bold"}]},
+            {"type":"text","text":" javascript:alert(1) "},
+            {"type":"text","text":"https://already.com","marks":[{"type":"code"}]}
+        ]});
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<p>See <a href=\"https://www.nhl.com/scores\">www.nhl.com/scores</a>, \
+             (<a href=\"https://leafs.com/?a=1&amp;b=2\">https://leafs.com/?a=1&amp;b=2</a>) \
+             or mail <a href=\"mailto:tim@leafs.ca\">tim@leafs.ca</a>.\
+             <strong><a href=\"https://nhl.com\">https://nhl.com</a></strong> javascript:alert(1) \
+             <code>https://already.com</code></p>"
+        );
+    }
+
+    #[test]
+    fn it_links_tags_and_handles() {
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        prose_mirror.add_plugin(
+            "text",
+            Box::new(
+                TextPlugin::new()
+                    .with_pattern(
+                        LinkPattern::new('#', |tag| Some(format!("/tags/{}", tag.to_lowercase())))
+                            .with_class("hashtag"),
+                    )
+                    .with_pattern(LinkPattern::new('@', |handle| {
+                        (handle != "nobody").then(|| format!("/u/{}", handle))
+                    })),
+            ),
+        );
+
+        let content =
+            json!({"type":"text","text":"#GoLeafsGo with @auston_34, not @nobody or #1 or a#b"});
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<a class=\"hashtag\" href=\"/tags/goleafsgo\">#GoLeafsGo</a> with \
+             <a href=\"/u/auston_34\">@auston_34</a>, not @nobody or #1 or a#b"
+        );
+    }
+
+    #[test]
+    fn it_highlights_search_terms_outside_code() {
+        let prose_mirror = ProseMirror::builder()
+            .with_defaults()
+            .highlight_terms(&["leafs".to_string(), "<cup>".to_string()])
+            .build();
+
+        let content = json!({"type":"doc","content":[
+            {"type":"paragraph","content":[
+                {"type":"text","text":"Leafs win the <cup>, Leafsy"},
+                {"type":"text","text":"leafs","marks":[{"type":"italic"}]},
+                {"type":"text","text":"leafs","marks":[{"type":"code"}]}
+            ]},
+            {"type":"codeBlock","content":[{"type":"text","text":"leafs"}]}
+        ]});
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div><p><mark class=\"search-hit\">Leafs</mark> win the \
+             <mark class=\"search-hit\">&lt;cup&gt;</mark>, Leafsy\
+             <em><mark class=\"search-hit\">leafs</mark></em><code>leafs</code></p>\
+             <pre><code>leafs</code></pre></div>"
+        );
+    }
+
+    #[test]
+    fn it_handles_unknown_marks() {
+        let content = json!({"type":"text","text":"Leafs","marks":[{"type":"sparkle"}]});
+        let mut prose_mirror = ProseMirror::new();
+        TextPlugin::register(&mut prose_mirror);
+
+        assert_eq!(prose_mirror.render(&content).unwrap(), "Leafs".to_string());
+
+        prose_mirror.options_mut().strict = true;
+        assert_eq!(
+            prose_mirror.render(&content).unwrap_err(),
+            ProseMirrorError::MarkNotFound {
+                path: "/marks/0".to_owned(),
+                mark_name: Some("sparkle".to_owned())
+            }
+        );
     }
 }
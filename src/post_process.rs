@@ -0,0 +1,49 @@
+// Runs over the finished HTML of a whole document, after every plugin is done
+pub trait PostProcessor: Send + Sync {
+    fn process(&self, html: String) -> String;
+}
+
+impl<F> PostProcessor for F
+where
+    F: Fn(String) -> String + Send + Sync,
+{
+    fn process(&self, html: String) -> String {
+        self(html)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ProseMirror;
+    use serde_json::json;
+
+    struct Nonce(&'static str);
+
+    impl PostProcessor for Nonce {
+        fn process(&self, html: String) -> String {
+            html.replace("<script>", &format!("<script nonce=\"{}\">", self.0))
+        }
+    }
+
+    #[test]
+    fn it_chains_post_processors_in_order() {
+        let mut prose_mirror = ProseMirror::builder()
+            .with_defaults()
+            .escape_html(false)
+            .build();
+        prose_mirror.add_post_processor(Box::new(Nonce("abc")));
+        prose_mirror.add_post_processor(Box::new(|html: String| html.replace(":)", "🙂")));
+        prose_mirror.add_post_processor(Box::new(|html: String| format!("{}\n", html)));
+
+        let content = json!({"type":"paragraph","content":[{"type":"text","text":"<script>go()</script> :)"}]});
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<p><script nonce=\"abc\">go()</script> 🙂</p>\n".to_string()
+        );
+
+        let mut output = String::new();
+        prose_mirror.render_to(&content, &mut output).unwrap();
+        assert_eq!(output, prose_mirror.render(&content).unwrap());
+    }
+}
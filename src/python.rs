@@ -0,0 +1,95 @@
+use pyo3::create_exception;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyModule, PyString};
+use serde_json::Value;
+
+use crate::{ProseMirror, RenderConfig};
+
+create_exception!(tiptap_to_html, RenderError, PyValueError);
+
+// Goes through the `json` module so anything `json.dumps` takes works, strings
+// are taken to already be JSON like the wasm bindings do
+fn json_value(value: Option<&Bound<'_, PyAny>>) -> PyResult<Value> {
+    let Some(value) = value.filter(|value| !value.is_none()) else {
+        return Ok(Value::Null);
+    };
+    let json: String = match value.downcast::<PyString>() {
+        Ok(json) => json.to_str()?.to_string(),
+        Err(_) => PyModule::import(value.py(), "json")?
+            .call_method1("dumps", (value,))?
+            .extract()?,
+    };
+    serde_json::from_str(&json).map_err(|err| PyValueError::new_err(err.to_string()))
+}
+
+fn renderer(options: Option<&Bound<'_, PyAny>>) -> PyResult<ProseMirror> {
+    RenderConfig::from_json(&json_value(options)?)
+        .and_then(|config| config.build())
+        .map_err(|err| RenderError::new_err(err.to_string()))
+}
+
+// `options` is a `RenderConfig` dict, rendering errors raise `RenderError`
+#[pyfunction]
+#[pyo3(signature = (doc, options = None))]
+fn render(doc: &Bound<'_, PyAny>, options: Option<&Bound<'_, PyAny>>) -> PyResult<String> {
+    Renderer::new(options)?.render(doc)
+}
+
+// Keeps the configured renderer around, for rendering many documents
+#[pyclass(frozen)]
+struct Renderer {
+    prosemirror: ProseMirror,
+}
+
+#[pymethods]
+impl Renderer {
+    #[new]
+    #[pyo3(signature = (options = None))]
+    fn new(options: Option<&Bound<'_, PyAny>>) -> PyResult<Self> {
+        Ok(Self {
+            prosemirror: renderer(options)?,
+        })
+    }
+
+    fn render(&self, doc: &Bound<'_, PyAny>) -> PyResult<String> {
+        let content = json_value(Some(doc))?;
+        doc.py()
+            .allow_threads(|| self.prosemirror.render(&content))
+            .map_err(|err| RenderError::new_err(err.to_string()))
+    }
+}
+
+#[pymodule]
+fn tiptap_to_html(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_function(wrap_pyfunction!(render, module)?)?;
+    module.add_class::<Renderer>()?;
+    module.add("RenderError", module.py().get_type::<RenderError>())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::types::PyDict;
+
+    #[test]
+    fn it_renders_python_dicts() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let doc = py
+                .eval(
+                    c"{'type': 'doc', 'content': [{'type': 'paragraph'}]}",
+                    None,
+                    None,
+                )
+                .unwrap();
+            assert_eq!(render(&doc, None).unwrap(), "<div><p></p></div>");
+
+            let options = PyDict::new(py);
+            options.set_item("plugins", vec!["sparkles"]).unwrap();
+            let err = render(&doc, Some(options.as_any())).unwrap_err();
+            assert!(err.is_instance_of::<RenderError>(py));
+        });
+    }
+}
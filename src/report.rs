@@ -0,0 +1,76 @@
+use crate::error::ProseMirrorError;
+
+pub struct RenderReport {
+    pub html: String,
+    pub errors: Vec<ProseMirrorError>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::ProseMirrorError;
+    use crate::{ProseMirror, UnsafeUrlAction};
+    use serde_json::json;
+
+    #[test]
+    fn it_collects_all_errors() {
+        let content = json!({"type":"doc","content":[
+            {"type":"paragraph","content":[{"type":"text","text":"Go Leafs","marks":[{"type":"sparkle"}]}]},
+            {"type":"youtube"},
+            {"type":"paragraph","content":[{"type":"text","text":7}]},
+            {"type":"image","attrs":{"src":"javascript:alert(1)"}}
+        ]});
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        prose_mirror.options_mut().url_policy.action = UnsafeUrlAction::Reject;
+
+        let report = prose_mirror.render_with_report(&content);
+
+        assert_eq!(report.html, "<div><p>Go Leafs</p><p></p></div>".to_string());
+        assert_eq!(
+            report.errors,
+            vec![
+                ProseMirrorError::MarkNotFound {
+                    path: "/content/0/content/0/marks/0".to_owned(),
+                    mark_name: Some("sparkle".to_owned())
+                },
+                ProseMirrorError::TypeNotFound {
+                    path: "/content/1".to_owned(),
+                    type_name: Some("youtube".to_owned())
+                },
+                ProseMirrorError::InvalidNodeShape {
+                    path: "/content/2/content/0".to_owned(),
+                    reason: "`text` must be a string".to_owned()
+                },
+                ProseMirrorError::UnsafeUrl {
+                    path: "/content/3".to_owned(),
+                    url: "javascript:alert(1)".to_owned()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn it_reports_root_errors() {
+        let report = ProseMirror::new().render_with_report(&json!({"type":"doc"}));
+
+        assert_eq!(report.html, String::new());
+        assert_eq!(
+            report.errors,
+            vec![ProseMirrorError::TypeNotFound {
+                path: String::new(),
+                type_name: Some("doc".to_owned())
+            }]
+        );
+    }
+
+    #[test]
+    fn it_leaves_plain_render_unaffected() {
+        let content = json!({"type":"doc","content":[{"type":"youtube"}]});
+        let prose_mirror = ProseMirror::with_default_plugins();
+
+        prose_mirror.render_with_report(&content);
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div></div>".to_string()
+        );
+    }
+}
@@ -0,0 +1,151 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnsafeUrlAction {
+    // Drop the attribute and keep rendering
+    Strip,
+    // Abort the render with `ProseMirrorError::UnsafeUrl`
+    Reject,
+}
+
+#[derive(Debug, Clone)]
+pub struct UrlPolicy {
+    pub blocked_schemes: Vec<String>,
+    pub allow_data_urls: bool,
+    pub action: UnsafeUrlAction,
+}
+
+impl Default for UrlPolicy {
+    fn default() -> Self {
+        Self {
+            blocked_schemes: vec!["javascript".to_string(), "vbscript".to_string()],
+            allow_data_urls: false,
+            action: UnsafeUrlAction::Strip,
+        }
+    }
+}
+
+impl UrlPolicy {
+    pub fn is_allowed(&self, url: &str) -> bool {
+        match url_scheme(url) {
+            Some(scheme) if scheme == "data" => self.allow_data_urls,
+            Some(scheme) => !self
+                .blocked_schemes
+                .iter()
+                .any(|blocked| blocked.eq_ignore_ascii_case(&scheme)),
+            None => true,
+        }
+    }
+}
+
+// Browsers ignore leading whitespace/control characters and embedded tabs or
+// newlines, so `" java\tscript:"` still runs as javascript
+fn url_scheme(url: &str) -> Option<String> {
+    let normalized: String = url
+        .trim_start_matches(|c: char| c.is_whitespace() || c.is_control())
+        .chars()
+        .filter(|c| !matches!(c, '\t' | '\n' | '\r'))
+        .collect();
+
+    let end = normalized.find([':', '/', '?', '#'])?;
+    if !normalized[end..].starts_with(':') {
+        return None;
+    }
+    Some(normalized[..end].to_ascii_lowercase())
+}
+
+// For values interpolated into a `style` attr: colors, lengths and font names
+// only, nothing that could close the declaration or load a resource
+pub fn is_safe_css_value(value: &str) -> bool {
+    let lowercase = value.to_ascii_lowercase();
+    !value.trim().is_empty()
+        && !lowercase.contains("url(")
+        && !lowercase.contains("expression(")
+        && value.chars().all(|c| {
+            c.is_ascii_alphanumeric() || matches!(c, '#' | '(' | ')' | ',' | '.' | '%' | ' ' | '-')
+        })
+}
+
+// Removes elements with everything inside them, for tags that must never reach
+// the output even when they come in through unescaped HTML
+pub(crate) fn strip_elements(html: String, tags: &[&str]) -> String {
+    let lowercase = html.to_ascii_lowercase();
+    let mut output = String::with_capacity(html.len());
+    let mut position = 0;
+    while let Some((start, tag)) = tags
+        .iter()
+        .filter_map(|tag| {
+            let start = lowercase[position..].find(&format!("<{}", tag))? + position;
+            Some((start, *tag))
+        })
+        .min()
+    {
+        output.push_str(&html[position..start]);
+        let closing = format!("</{}>", tag);
+        // `<embed>` never has a closing tag, unclosed elements run to the end
+        let end = match lowercase[start..].find(&closing) {
+            Some(end) if tag != "embed" => start + end + closing.len(),
+            _ => lowercase[start..]
+                .find('>')
+                .map(|end| start + end + 1)
+                .unwrap_or(html.len()),
+        };
+        position = end;
+    }
+    output.push_str(&html[position..]);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_checks_css_values() {
+        assert!(is_safe_css_value("#00205b"));
+        assert!(is_safe_css_value("rgba(0, 32, 91, 0.5)"));
+        assert!(!is_safe_css_value("red; background: url(x)"));
+        assert!(!is_safe_css_value("url(https://evil.example)"));
+        assert!(!is_safe_css_value("\"><script>"));
+        assert!(!is_safe_css_value(" "));
+    }
+
+    #[test]
+    fn it_blocks_script_schemes() {
+        let policy = UrlPolicy::default();
+
+        assert!(!policy.is_allowed("javascript:alert(1)"));
+        assert!(!policy.is_allowed("  JavaScript:alert(1)"));
+        assert!(!policy.is_allowed("java\tscript:alert(1)"));
+        assert!(!policy.is_allowed("vbscript:msgbox"));
+    }
+
+    #[test]
+    fn it_allows_regular_urls() {
+        let policy = UrlPolicy::default();
+
+        assert!(policy.is_allowed("https://nhl.com/leafs"));
+        assert!(policy.is_allowed("mailto:leafs@nhl.com"));
+        assert!(policy.is_allowed("/relative/path:with-colon"));
+        assert!(policy.is_allowed("#section"));
+    }
+
+    #[test]
+    fn it_makes_data_urls_configurable() {
+        let mut policy = UrlPolicy::default();
+        assert!(!policy.is_allowed("data:image/png;base64,AAAA"));
+
+        policy.allow_data_urls = true;
+        assert!(policy.is_allowed("data:image/png;base64,AAAA"));
+    }
+
+    #[test]
+    fn it_strips_scripts_and_iframes() {
+        assert_eq!(
+            strip_elements(
+                "<p>a<SCRIPT>alert(1)</script>b<iframe src=\"x\"></iframe><embed src=\"y\">c</p>"
+                    .to_string(),
+                &["embed", "iframe", "script"]
+            ),
+            "<p>abc</p>"
+        );
+    }
+}
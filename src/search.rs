@@ -0,0 +1,79 @@
+// Case-insensitive whole-word matches of search terms in text, for
+// `RenderOptions::highlight_terms`. Where two terms match at the same spot the
+// longer one wins, and matches never overlap.
+pub(crate) fn find_terms(text: &str, terms: &[String]) -> Vec<(usize, usize)> {
+    let mut matches = Vec::new();
+    let mut index = 0;
+    while index < text.len() {
+        let rest = &text[index..];
+        let at_boundary = text[..index]
+            .chars()
+            .next_back()
+            .is_none_or(|prev| !is_word_char(prev));
+        let len = at_boundary
+            .then(|| {
+                terms
+                    .iter()
+                    .filter_map(|term| match_len(rest, term.trim()))
+                    .max()
+            })
+            .flatten();
+        match len {
+            Some(len) => {
+                matches.push((index, index + len));
+                index += len;
+            }
+            None => index += rest.chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    matches
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+// The length of `term` at the start of `text`, if it ends on a word boundary
+fn match_len(text: &str, term: &str) -> Option<usize> {
+    if term.is_empty() {
+        return None;
+    }
+    let mut text_chars = text.char_indices();
+    for term_char in term.chars() {
+        let (_, text_char) = text_chars.next()?;
+        if !text_char.to_lowercase().eq(term_char.to_lowercase()) {
+            return None;
+        }
+    }
+    let len = text_chars.next().map_or(text.len(), |(index, _)| index);
+    let ends_word = text[len..]
+        .chars()
+        .next()
+        .is_none_or(|next| !is_word_char(next));
+    ends_word.then_some(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_matches_whole_words_ignoring_case() {
+        let terms = vec![
+            "leafs".to_string(),
+            "Maple Leafs".to_string(),
+            "".to_string(),
+        ];
+        let text = "MAPLE LEAFS, leafs! Leafsy _leafs ÉLAN";
+        let matched: Vec<&str> = find_terms(text, &terms)
+            .into_iter()
+            .map(|(start, end)| &text[start..end])
+            .collect();
+
+        assert_eq!(matched, vec!["MAPLE LEAFS", "leafs"]);
+        assert_eq!(
+            find_terms("élan", &["ÉLAN".to_string()]),
+            vec![(0, "élan".len())]
+        );
+    }
+}
@@ -0,0 +1,83 @@
+use serde_json::{Map, Value};
+
+pub type Slugifier = Box<dyn Fn(&str) -> String + Send + Sync>;
+
+// "Hello, World!" -> "hello-world", keeping non-ASCII letters as they are
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if (c.is_whitespace() || c == '-' || c == '_') && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_matches('-').to_string()
+}
+
+// Generates `id`s for headings so sections can be linked to
+pub struct HeadingAnchors {
+    slugify: Slugifier,
+}
+
+impl Default for HeadingAnchors {
+    fn default() -> Self {
+        Self {
+            slugify: Box::new(slugify),
+        }
+    }
+}
+
+impl HeadingAnchors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_slugifier<F>(slugify: F) -> Self
+    where
+        F: Fn(&str) -> String + Send + Sync + 'static,
+    {
+        Self {
+            slugify: Box::new(slugify),
+        }
+    }
+
+    // A slug for `text` that isn't in `used` yet: `title`, `title-1`, `title-2`...
+    pub fn anchor(&self, text: &str, used: &mut Map<String, Value>) -> String {
+        let mut slug = (self.slugify)(text);
+        if slug.is_empty() {
+            slug = "heading".to_string();
+        }
+        let mut anchor = slug.clone();
+        let mut suffix = 0;
+        while used.contains_key(&anchor) {
+            suffix += 1;
+            anchor = format!("{}-{}", slug, suffix);
+        }
+        used.insert(anchor.clone(), Value::Bool(true));
+        anchor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_slugifies_text() {
+        assert_eq!(slugify("Hello, World!"), "hello-world");
+        assert_eq!(slugify("  Leafs -- vs_Habs  "), "leafs-vs-habs");
+        assert_eq!(slugify("Équipe à domicile"), "équipe-à-domicile");
+    }
+
+    #[test]
+    fn it_dedupes_anchors() {
+        let anchors = HeadingAnchors::new();
+        let mut used = Map::new();
+        used.insert("intro-1".to_string(), Value::Bool(true));
+
+        assert_eq!(anchors.anchor("Intro", &mut used), "intro");
+        assert_eq!(anchors.anchor("Intro", &mut used), "intro-2");
+        assert_eq!(anchors.anchor("!!!", &mut used), "heading");
+    }
+}
@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TypeStats {
+    pub count: usize,
+    // Time spent in the type's plugin, not counting the children it rendered
+    pub time: Duration,
+}
+
+// What one `render_with_stats` call did, for finding the plugins that are slow
+#[derive(Debug, Clone, Default)]
+pub struct RenderStats {
+    pub nodes: usize,
+    pub node_types: HashMap<String, TypeStats>,
+    pub mark_types: HashMap<String, TypeStats>,
+    pub output_bytes: usize,
+    // Only top-level blocks go through the cache
+    pub cache_hits: usize,
+    pub cache_misses: usize,
+    pub total_time: Duration,
+    // Time taken by the children of every node that's still rendering
+    nested: Vec<Duration>,
+}
+
+impl RenderStats {
+    // The node types by the time spent in them, slowest first
+    pub fn slowest_node_types(&self) -> Vec<(&str, TypeStats)> {
+        let mut types: Vec<_> = self
+            .node_types
+            .iter()
+            .map(|(node_type, stats)| (node_type.as_str(), *stats))
+            .collect();
+        types.sort_by(|(a, a_stats), (b, b_stats)| b_stats.time.cmp(&a_stats.time).then(a.cmp(b)));
+        types
+    }
+
+    pub(crate) fn enter(&mut self) -> Instant {
+        self.nested.push(Duration::ZERO);
+        Instant::now()
+    }
+
+    pub(crate) fn exit_node(&mut self, node_type: &str, start: Instant) {
+        let time = self.exit(start);
+        self.nodes += 1;
+        Self::add(&mut self.node_types, node_type, time);
+    }
+
+    pub(crate) fn exit_mark(&mut self, mark_type: &str, start: Instant) {
+        let time = self.exit(start);
+        Self::add(&mut self.mark_types, mark_type, time);
+    }
+
+    // The time of this plugin alone, its total is handed up to the parent
+    fn exit(&mut self, start: Instant) -> Duration {
+        let elapsed = start.elapsed();
+        let children = self.nested.pop().unwrap_or_default();
+        if let Some(parent) = self.nested.last_mut() {
+            *parent += elapsed;
+        }
+        elapsed.saturating_sub(children)
+    }
+
+    fn add(types: &mut HashMap<String, TypeStats>, name: &str, time: Duration) {
+        let stats = types.entry(name.to_string()).or_default();
+        stats.count += 1;
+        stats.time += time;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{MemoryCache, ProseMirror};
+    use serde_json::json;
+
+    #[test]
+    fn it_counts_what_was_rendered() {
+        let content = json!({"type":"doc","content":[
+            {"type":"paragraph","content":[{"type":"text","text":"Go","marks":[{"type":"bold"}]}]},
+            {"type":"paragraph","content":[{"type":"text","text":"Go","marks":[{"type":"bold"}]}]}
+        ]});
+        let prose_mirror = ProseMirror::builder()
+            .with_defaults()
+            .with_cache(Box::new(MemoryCache::new(8)))
+            .build();
+
+        let (html, stats) = prose_mirror.render_with_stats(&content).unwrap();
+
+        assert_eq!(html, prose_mirror.render(&content).unwrap());
+        assert_eq!(stats.output_bytes, html.len());
+        assert_eq!(stats.nodes, 3);
+        assert_eq!(stats.node_types["paragraph"].count, 1);
+        assert_eq!(stats.node_types["text"].count, 1);
+        assert_eq!(stats.mark_types["bold"].count, 1);
+        assert_eq!((stats.cache_hits, stats.cache_misses), (1, 1));
+        assert!(stats.total_time >= stats.node_types["doc"].time);
+        assert_eq!(stats.slowest_node_types().len(), 3);
+    }
+}
@@ -0,0 +1,267 @@
+use std::{fmt, io};
+
+use serde::de::{self, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use serde_json::{json, Map, Value};
+
+use crate::error::ProseMirrorError;
+use crate::limits;
+use crate::{utils, ProseMirror, RenderContext};
+
+// Stands in for the root's children when rendering it on its own, whatever
+// comes before it is the opening half of the root and whatever follows the rest
+const MARKER: &str = "\u{E000}tiptap-to-html\u{E000}";
+
+struct Stream<'r, 'a> {
+    ctx: &'r mut RenderContext<'a>,
+    writer: &'r mut dyn fmt::Write,
+    // Shared by every child so the node count covers the whole document
+    limits: limits::TreeLimits,
+    // serde errors can't carry ours, so a failed render is kept here
+    error: Option<ProseMirrorError>,
+}
+
+// The HTML around the root's children, `None` when the root's plugin doesn't
+// render its content where a marker would show up
+pub(crate) fn split_root(
+    prosemirror: &ProseMirror,
+    root: &Map<String, Value>,
+) -> Option<(String, String)> {
+    root.get("type")
+        .and_then(|t| t.as_str())
+        .and_then(|t| prosemirror.plugin(t))?;
+    let mut shell = root.clone();
+    shell.insert(
+        "content".to_string(),
+        json!([{"type": "text", "text": MARKER}]),
+    );
+    let mut html = String::new();
+    RenderContext::new(prosemirror)
+        .render_node_to(&Value::Object(shell), true, &mut html)
+        .ok()?;
+    let (prefix, suffix) = html.split_once(MARKER)?;
+    Some((prefix.to_string(), suffix.to_string()))
+}
+
+impl Stream<'_, '_> {
+    fn fail<E: de::Error>(&mut self, err: ProseMirrorError) -> E {
+        self.error = Some(err);
+        E::custom("render failed")
+    }
+}
+
+struct Root<'s, 'r, 'a>(&'s mut Stream<'r, 'a>);
+
+impl<'de> DeserializeSeed<'de> for Root<'_, '_, '_> {
+    type Value = ();
+
+    fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for Root<'_, '_, '_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a ProseMirror node")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        let stream = self.0;
+        let mut root = Map::new();
+        let mut streamed = false;
+        while let Some(key) = map.next_key::<String>()? {
+            // Only possible once `type` has been seen, otherwise the children
+            // have to be kept until it shows up
+            if key == "content" && !streamed {
+                if let Some((prefix, suffix)) = split_root(stream.ctx.prosemirror(), &root) {
+                    let root_type = root["type"].as_str().unwrap_or_default().to_string();
+                    if let Err(err) = stream.limits.enter("", 0) {
+                        return Err(stream.fail(err));
+                    }
+                    if let Err(err) = stream.writer.write_str(&prefix) {
+                        return Err(stream.fail(err.into()));
+                    }
+                    map.next_value_seed(Children {
+                        stream: &mut *stream,
+                        root_type: &root_type,
+                    })?;
+                    if root_type == "doc" {
+                        if let Err(err) = stream.ctx.finish_document(stream.writer) {
+                            return Err(stream.fail(err));
+                        }
+                    }
+                    if let Err(err) = stream.writer.write_str(&suffix) {
+                        return Err(stream.fail(err.into()));
+                    }
+                    streamed = true;
+                    continue;
+                }
+            }
+            let value: Value = map.next_value()?;
+            root.insert(key, value);
+        }
+
+        let result = if streamed {
+            stream.ctx.finish_document(stream.writer)
+        } else {
+            let root = Value::Object(root);
+            stream
+                .limits
+                .check(&root, "", 0)
+                .and_then(|_| stream.ctx.render_document(&root, stream.writer))
+        };
+        result.map_err(|err| stream.fail(err))
+    }
+}
+
+struct Children<'s, 'r, 'a> {
+    stream: &'s mut Stream<'r, 'a>,
+    root_type: &'s str,
+}
+
+impl<'de> DeserializeSeed<'de> for Children<'_, '_, '_> {
+    type Value = ();
+
+    fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for Children<'_, '_, '_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an array of nodes")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        let stream = self.stream;
+        let mut index = 0;
+        // Only one child is held in memory at a time
+        while let Some(child) = seq.next_element::<Value>()? {
+            let segment = format!("/content/{}", index);
+            let result = stream.limits.check(&child, &segment, 1).and_then(|_| {
+                stream
+                    .ctx
+                    .render_streamed_child(self.root_type, index, &child, stream.writer)
+            });
+            if let Err(err) = result {
+                return Err(stream.fail(err));
+            }
+            index += 1;
+        }
+        Ok(())
+    }
+}
+
+// Renders JSON straight from `reader` into `writer`, deserializing the root's
+// children one at a time so a large document is never held in memory whole.
+// Anything that needs the whole document (transforms, the collect pass,
+// formatting, post-processors) falls back to parsing it up front.
+pub(crate) fn render_reader<R: io::Read, W: io::Write>(
+    prosemirror: &ProseMirror,
+    reader: R,
+    writer: &mut W,
+) -> Result<(), ProseMirrorError> {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!("render_reader").entered();
+    let options = prosemirror.options();
+    if !prosemirror.transforms.is_empty()
+        || !prosemirror.post_processors.is_empty()
+        || prosemirror.runs_collect_pass()
+        || options.indent.is_some()
+        || options.minify
+    {
+        let content: Value = serde_json::from_reader(reader)
+            .map_err(|err| ProseMirrorError::invalid_shape(&err.to_string()))?;
+        return prosemirror.render_to_io(&content, writer);
+    }
+
+    let mut adapter = utils::IoWriter::new(writer);
+    let result = limits::write_limited(&mut adapter, options.max_output_bytes, |writer| {
+        let mut ctx = RenderContext::new(prosemirror);
+        let mut stream = Stream {
+            ctx: &mut ctx,
+            writer,
+            limits: prosemirror.tree_limits(),
+            error: None,
+        };
+        let mut deserializer = serde_json::Deserializer::from_reader(reader);
+        let result = Root(&mut stream)
+            .deserialize(&mut deserializer)
+            .and_then(|_| deserializer.end());
+        match (result, stream.error.take()) {
+            (_, Some(err)) => Err(err),
+            (Err(err), None) => Err(ProseMirrorError::invalid_shape(&err.to_string())),
+            (Ok(()), None) => Ok(()),
+        }
+    });
+    adapter.into_result(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Limit;
+
+    #[test]
+    fn it_renders_json_as_it_reads_it() {
+        let prose_mirror = ProseMirror::with_default_plugins();
+        let json = r#"{"type":"doc","content":[
+            {"type":"heading","attrs":{"level":2},"content":[{"type":"text","text":"Leafs"}]},
+            {"type":"paragraph","content":[{"type":"text","text":"Go","marks":[{"type":"bold"}]}]}
+        ]}"#;
+        let mut output = vec![];
+        render_reader(&prose_mirror, json.as_bytes(), &mut output).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            prose_mirror
+                .render(&serde_json::from_str(json).unwrap())
+                .unwrap()
+        );
+
+        // `content` before `type` can't be streamed but still renders
+        let mut output = vec![];
+        render_reader(
+            &prose_mirror,
+            r#"{"content":[{"type":"paragraph"}],"type":"doc"}"#.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "<div><p></p></div>");
+    }
+
+    #[test]
+    fn it_reports_errors_while_streaming() {
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        prose_mirror.options_mut().max_depth = Some(2);
+        let json = r#"{"type":"doc","content":[{"type":"paragraph"},{"type":"blockquote","content":[{"type":"paragraph","content":[{"type":"text","text":"x"}]}]}]}"#;
+
+        assert_eq!(
+            render_reader(&prose_mirror, json.as_bytes(), &mut vec![]).unwrap_err(),
+            ProseMirrorError::LimitExceeded {
+                path: "/content/1/content/0/content/0".to_string(),
+                limit: Limit::Depth,
+                max: 2
+            }
+        );
+
+        prose_mirror.options_mut().max_nodes = Some(3);
+        assert_eq!(
+            render_reader(&prose_mirror, json.as_bytes(), &mut vec![])
+                .unwrap_err()
+                .path(),
+            "/content/1/content/0"
+        );
+        assert!(matches!(
+            render_reader(
+                &prose_mirror,
+                r#"{"type":"doc","content":[{"#.as_bytes(),
+                &mut vec![]
+            ),
+            Err(ProseMirrorError::InvalidNodeShape { .. })
+        ));
+    }
+}
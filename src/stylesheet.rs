@@ -0,0 +1,174 @@
+use std::fmt::Write;
+
+use crate::utils;
+
+// Rendered HTML with its inline `style` attributes moved into a stylesheet, for
+// a Content-Security-Policy without `style-src 'unsafe-inline'`. Each distinct
+// style becomes a class named after a hash of it, so stylesheets from several
+// documents can go on the same page.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct StyledHtml {
+    pub html: String,
+    pub stylesheet: String,
+}
+
+impl StyledHtml {
+    // Moves each `style="..."` on the elements of `html` into a class
+    pub fn extract(html: &str) -> Self {
+        let mut output = String::with_capacity(html.len());
+        let mut rules: Vec<(String, String)> = Vec::new();
+        let mut rest = html;
+        while let Some(start) = rest.find('<') {
+            output.push_str(&rest[..start]);
+            rest = &rest[start..];
+            let Some(end) = tag_end(rest) else {
+                break;
+            };
+            let tag = &rest[..end];
+            match extract_style(tag) {
+                Some((tag, class, declarations)) => {
+                    output.push_str(&tag);
+                    if !rules.iter().any(|(existing, _)| *existing == class) {
+                        rules.push((class, declarations));
+                    }
+                }
+                None => output.push_str(tag),
+            }
+            rest = &rest[end..];
+        }
+        output.push_str(rest);
+
+        let mut stylesheet = String::new();
+        for (class, declarations) in rules {
+            let _ = writeln!(stylesheet, ".{} {{ {} }}", class, declarations);
+        }
+        Self {
+            html: output,
+            stylesheet,
+        }
+    }
+
+    // The stylesheet as a <style> element, with the nonce the policy allows
+    pub fn style_element(&self, nonce: Option<&str>) -> String {
+        match nonce {
+            Some(nonce) => format!(
+                "<style nonce=\"{}\">{}</style>",
+                utils::escape_html(nonce),
+                self.stylesheet
+            ),
+            None => format!("<style>{}</style>", self.stylesheet),
+        }
+    }
+
+    // The `style-src` source allowing exactly this stylesheet in a <style> element
+    #[cfg(feature = "csp-hash")]
+    pub fn csp_hash(&self) -> String {
+        use base64::Engine;
+        use sha2::{Digest, Sha256};
+
+        let digest = Sha256::digest(self.stylesheet.as_bytes());
+        format!(
+            "'sha256-{}'",
+            base64::engine::general_purpose::STANDARD.encode(digest)
+        )
+    }
+}
+
+// The index just past the `>` closing the tag at the start of `html`, skipping
+// over quoted attribute values
+fn tag_end(html: &str) -> Option<usize> {
+    let mut quote = None;
+    for (index, byte) in html.bytes().enumerate() {
+        match (quote, byte) {
+            (None, b'"' | b'\'') => quote = Some(byte),
+            (Some(open), _) if open == byte => quote = None,
+            (None, b'>') => return Some(index + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+// The tag rewritten with a class in place of its style, the class and the style
+fn extract_style(tag: &str) -> Option<(String, String, String)> {
+    if tag.starts_with("</") || tag.starts_with("<!") {
+        return None;
+    }
+    let start = tag.find(" style=\"")?;
+    let value_start = start + " style=\"".len();
+    let value_end = value_start + tag[value_start..].find('"')?;
+    let declarations = css_text(&utils::unescape_html(&tag[value_start..value_end]));
+    let class = format!("s-{:08x}", fnv1a(declarations.as_bytes()));
+
+    let mut rewritten = format!("{}{}", &tag[..start], &tag[value_end + 1..]);
+    match rewritten.find(" class=\"") {
+        Some(class_start) => {
+            let insert_at = class_start + " class=\"".len();
+            let class_end = insert_at + rewritten[insert_at..].find('"')?;
+            rewritten.insert_str(class_end, &format!(" {}", class));
+        }
+        None => {
+            let name_end = rewritten
+                .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+                .unwrap_or(rewritten.len());
+            rewritten.insert_str(name_end, &format!(" class=\"{}\"", class));
+        }
+    }
+    Some((rewritten, class, declarations))
+}
+
+// Nothing in a style attribute may end the <style> element it moves into
+fn css_text(declarations: &str) -> String {
+    declarations
+        .trim()
+        .trim_end_matches(';')
+        .replace('<', "\\3c ")
+}
+
+fn fnv1a(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c9dc5, |hash, byte| {
+        (hash ^ *byte as u32).wrapping_mul(0x01000193)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ProseMirror;
+    use serde_json::json;
+
+    #[test]
+    fn it_moves_inline_styles_into_a_stylesheet() {
+        let prose_mirror = ProseMirror::with_default_plugins();
+        let content = json!({"type":"doc","content":[
+            {"type":"paragraph","attrs":{"textAlign":"center","class":"lead"},"content":[{"type":"text","text":"style=\"x\""}]},
+            {"type":"heading","attrs":{"level":2,"textAlign":"center"}}
+        ]});
+        let styled = prose_mirror.render_with_stylesheet(&content).unwrap();
+
+        let class = format!("s-{:08x}", fnv1a(b"text-align: center"));
+        assert_eq!(
+            styled.html,
+            format!(
+                "<div><p class=\"lead {0}\">style=&quot;x&quot;</p><h2 class=\"{0}\"></h2></div>",
+                class
+            )
+        );
+        assert_eq!(
+            styled.stylesheet,
+            format!(".{} {{ text-align: center }}\n", class)
+        );
+        assert!(styled
+            .style_element(Some("r4nd"))
+            .starts_with("<style nonce=\"r4nd\">."));
+    }
+
+    #[cfg(feature = "csp-hash")]
+    #[test]
+    fn it_hashes_the_stylesheet_for_the_policy() {
+        assert_eq!(
+            StyledHtml::default().csp_hash(),
+            "'sha256-47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU='"
+        );
+    }
+}
@@ -0,0 +1,144 @@
+// `tiptap_html` filters for template engines, so `{{ post.body | tiptap_html }}`
+// renders a stored document. Strings are taken to be JSON, as the bindings do.
+use serde_json::Value;
+
+use crate::ProseMirror;
+
+fn render(prosemirror: &ProseMirror, value: &Value) -> Result<String, String> {
+    let parsed;
+    let content = match value {
+        Value::String(json) => {
+            parsed = serde_json::from_str(json).map_err(|err| err.to_string())?;
+            &parsed
+        }
+        _ => value,
+    };
+    prosemirror.render(content).map_err(|err| err.to_string())
+}
+
+#[cfg(feature = "tera")]
+pub use self::tera_filter::TiptapHtmlFilter;
+
+#[cfg(feature = "tera")]
+mod tera_filter {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use serde_json::Value;
+    use tera::{Filter, Tera};
+
+    use crate::ProseMirror;
+
+    // Shares one renderer between every template of a `Tera` instance
+    pub struct TiptapHtmlFilter {
+        prosemirror: Arc<ProseMirror>,
+    }
+
+    impl TiptapHtmlFilter {
+        pub fn new(prosemirror: Arc<ProseMirror>) -> Self {
+            Self { prosemirror }
+        }
+
+        pub fn register(self, tera: &mut Tera) {
+            tera.register_filter("tiptap_html", self);
+        }
+    }
+
+    impl Filter for TiptapHtmlFilter {
+        fn filter(&self, value: &Value, _args: &HashMap<String, Value>) -> tera::Result<Value> {
+            super::render(&self.prosemirror, value)
+                .map(Value::String)
+                .map_err(tera::Error::msg)
+        }
+
+        // The renderer escapes text itself
+        fn is_safe(&self) -> bool {
+            true
+        }
+    }
+}
+
+#[cfg(feature = "askama")]
+pub use self::askama_filter::{set_askama_renderer, tiptap_html};
+
+#[cfg(feature = "askama")]
+mod askama_filter {
+    use std::sync::OnceLock;
+
+    use askama::filters::Safe;
+    use askama::Values;
+    use serde_json::Value;
+
+    use crate::ProseMirror;
+
+    // Askama filters are plain functions, so the renderer has to be global. It's
+    // the default plugins unless set before the first render.
+    static RENDERER: OnceLock<ProseMirror> = OnceLock::new();
+
+    // False if a renderer is already in use
+    pub fn set_askama_renderer(prosemirror: ProseMirror) -> bool {
+        RENDERER.set(prosemirror).is_ok()
+    }
+
+    // Bring it into scope with `mod filters { pub use tiptap_to_html::templates::tiptap_html; }`
+    #[askama::filter_fn]
+    pub fn tiptap_html(value: &Value, _: &dyn Values) -> askama::Result<Safe<String>> {
+        let prosemirror = RENDERER.get_or_init(ProseMirror::with_default_plugins);
+        super::render(prosemirror, value)
+            .map(Safe)
+            .map_err(|err| askama::Error::Custom(err.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    #[cfg(feature = "tera")]
+    #[test]
+    fn it_renders_in_tera_templates() {
+        use std::sync::Arc;
+
+        use super::TiptapHtmlFilter;
+        use crate::ProseMirror;
+
+        let mut tera = tera::Tera::default();
+        TiptapHtmlFilter::new(Arc::new(ProseMirror::with_default_plugins())).register(&mut tera);
+        tera.add_raw_template("post.html", "<article>{{ body | tiptap_html }}</article>")
+            .unwrap();
+        let mut context = tera::Context::new();
+        context.insert(
+            "body",
+            &json!({"type":"doc","content":[{"type":"paragraph","content":[{"type":"text","text":"<hi>"}]}]}),
+        );
+
+        assert_eq!(
+            tera.render("post.html", &context).unwrap(),
+            "<article><div><p>&lt;hi&gt;</p></div></article>"
+        );
+    }
+
+    #[cfg(feature = "askama")]
+    #[test]
+    fn it_renders_in_askama_templates() {
+        use askama::Template;
+
+        mod filters {
+            pub use crate::templates::tiptap_html;
+        }
+
+        #[derive(Template)]
+        #[template(source = "<article>{{ body|tiptap_html }}</article>", ext = "html")]
+        struct Post {
+            body: serde_json::Value,
+        }
+
+        let post = Post {
+            body: json!(r#"{"type":"doc","content":[{"type":"paragraph"}]}"#),
+        };
+        assert_eq!(
+            post.render().unwrap(),
+            "<article><div><p></p></div></article>"
+        );
+    }
+}
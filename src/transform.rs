@@ -0,0 +1,117 @@
+use serde_json::Value;
+
+// Rewrites the document JSON before anything is rendered
+pub trait Transform: Send + Sync {
+    fn transform(&self, doc: &mut Value);
+}
+
+impl<F> Transform for F
+where
+    F: Fn(&mut Value) + Send + Sync,
+{
+    fn transform(&self, doc: &mut Value) {
+        self(doc)
+    }
+}
+
+// Calls `f` on every node, parents before their children
+pub fn walk_nodes_mut(node: &mut Value, f: &mut dyn FnMut(&mut Value)) {
+    f(node);
+    if let Some(Value::Array(content)) = node.get_mut("content") {
+        for child in content {
+            walk_nodes_mut(child, f);
+        }
+    }
+}
+
+// For documents saved before a node type was renamed
+pub struct RenameNodeType {
+    from: String,
+    to: String,
+}
+
+impl RenameNodeType {
+    pub fn new(from: &str, to: &str) -> Self {
+        Self {
+            from: from.to_string(),
+            to: to.to_string(),
+        }
+    }
+}
+
+impl Transform for RenameNodeType {
+    fn transform(&self, doc: &mut Value) {
+        walk_nodes_mut(doc, &mut |node| {
+            if node.get("type").and_then(|t| t.as_str()) == Some(self.from.as_str()) {
+                node["type"] = Value::String(self.to.clone());
+            }
+        });
+    }
+}
+
+// Drops paragraphs with no content, the leftovers of pressing enter a few times
+#[derive(Default)]
+pub struct StripEmptyParagraphs;
+
+impl Transform for StripEmptyParagraphs {
+    fn transform(&self, doc: &mut Value) {
+        walk_nodes_mut(doc, &mut |node| {
+            if let Some(Value::Array(content)) = node.get_mut("content") {
+                content.retain(|child| !is_empty_paragraph(child));
+            }
+        });
+    }
+}
+
+fn is_empty_paragraph(node: &Value) -> bool {
+    node.get("type").and_then(|t| t.as_str()) == Some("paragraph")
+        && node
+            .get("content")
+            .and_then(|content| content.as_array())
+            .map(|content| content.is_empty())
+            .unwrap_or(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ProseMirror;
+    use serde_json::json;
+
+    #[test]
+    fn it_transforms_before_rendering() {
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        prose_mirror.add_transform(Box::new(StripEmptyParagraphs));
+        prose_mirror.add_transform(Box::new(RenameNodeType::new("quote", "blockquote")));
+
+        let content = json!({"type":"doc","content":[
+            {"type":"paragraph"},
+            {"type":"quote","content":[
+                {"type":"paragraph","content":[{"type":"text","text":"Leafs in 4"}]},
+                {"type":"paragraph","content":[]}
+            ]}
+        ]});
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<div><blockquote><p>Leafs in 4</p></blockquote></div>".to_string()
+        );
+    }
+
+    #[test]
+    fn it_runs_closures_as_transforms() {
+        let mut prose_mirror = ProseMirror::with_default_plugins();
+        prose_mirror.add_transform(Box::new(|doc: &mut Value| {
+            walk_nodes_mut(doc, &mut |node| {
+                if let Some(Value::String(text)) = node.get_mut("text") {
+                    *text = text.to_uppercase();
+                }
+            })
+        }));
+
+        let content = json!({"type":"paragraph","content":[{"type":"text","text":"go leafs go"}]});
+        assert_eq!(
+            prose_mirror.render(&content).unwrap(),
+            "<p>GO LEAFS GO</p>".to_string()
+        );
+    }
+}
@@ -1,4 +1,164 @@
-pub fn push_front(mut s: String, prefix: &str) -> String {
-    s.insert_str(0, prefix);
-    s
+use std::{fmt, io};
+
+use serde_json::{Map, Value};
+
+use crate::error::ProseMirrorError;
+
+pub fn escape_html(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len() + s.len() / 8);
+    // Writing into a String can't fail
+    let _ = write_escaped(&mut escaped, s);
+    escaped
+}
+
+// Copies the runs between special characters in one go instead of char by char
+pub fn write_escaped(writer: &mut dyn fmt::Write, s: &str) -> fmt::Result {
+    let mut start = 0;
+    for (index, byte) in s.bytes().enumerate() {
+        let entity = match byte {
+            b'&' => "&amp;",
+            b'<' => "&lt;",
+            b'>' => "&gt;",
+            b'"' => "&quot;",
+            b'\'' => "&#39;",
+            _ => continue,
+        };
+        writer.write_str(&s[start..index])?;
+        writer.write_str(entity)?;
+        start = index + 1;
+    }
+    writer.write_str(&s[start..])
+}
+
+// The reverse of `escape_html`, for reading back attribute values it wrote
+pub fn unescape_html(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+// A rough guess at the size of the HTML a document renders to, so the output
+// buffer rarely has to grow: its text plus a few tags' worth per node
+pub fn estimated_len(node: &Value) -> usize {
+    let mut len = 0;
+    let mut stack = vec![node];
+    while let Some(node) = stack.pop() {
+        len += 16;
+        if let Some(text) = node.get("text").and_then(|t| t.as_str()) {
+            len += text.len() + text.len() / 8;
+        }
+        if let Some(marks) = node.get("marks").and_then(|m| m.as_array()) {
+            len += marks.len() * 16;
+        }
+        if let Some(content) = node.get("content").and_then(|c| c.as_array()) {
+            stack.extend(content);
+        }
+    }
+    len
+}
+
+// Same semantics as Tiptap's `mergeAttributes`: `class` and `style` values are
+// combined, everything else in `extra` overrides `attrs`
+pub fn merge_attributes(attrs: &mut Map<String, Value>, extra: &Map<String, Value>) {
+    for (key, value) in extra {
+        let merged = match (key.as_str(), attrs.get(key), value) {
+            ("class", Some(Value::String(existing)), Value::String(added)) => {
+                let mut classes: Vec<&str> = existing.split_whitespace().collect();
+                for class in added.split_whitespace() {
+                    if !classes.contains(&class) {
+                        classes.push(class);
+                    }
+                }
+                Value::String(classes.join(" "))
+            }
+            ("style", Some(Value::String(existing)), Value::String(added)) => {
+                let styles: Vec<&str> = existing
+                    .split(';')
+                    .chain(added.split(';'))
+                    .map(|style| style.trim())
+                    .filter(|style| !style.is_empty())
+                    .collect();
+                Value::String(styles.join("; "))
+            }
+            _ => value.clone(),
+        };
+        attrs.insert(key.clone(), merged);
+    }
+}
+
+// The text of a node and all of its descendants, ignoring marks
+pub fn node_text(node: &Value) -> String {
+    let mut text = String::new();
+    let mut stack = vec![node];
+    while let Some(node) = stack.pop() {
+        if let Some(t) = node.get("text").and_then(|t| t.as_str()) {
+            text.push_str(t);
+        }
+        if let Some(content) = node.get("content").and_then(|c| c.as_array()) {
+            stack.extend(content.iter().rev());
+        }
+    }
+    text
+}
+
+// Lets the `fmt::Write` based renderer stream into an `io::Write` sink while
+// keeping hold of the underlying io error
+pub struct IoWriter<'a, W: io::Write> {
+    inner: &'a mut W,
+    error: Option<io::Error>,
+}
+
+impl<'a, W: io::Write> IoWriter<'a, W> {
+    pub fn new(inner: &'a mut W) -> Self {
+        Self { inner, error: None }
+    }
+
+    pub fn into_result(self, result: Result<(), ProseMirrorError>) -> Result<(), ProseMirrorError> {
+        match (result, self.error) {
+            (Err(err), Some(io_error)) => Err(err.with_reason(&io_error.to_string())),
+            (result, _) => result,
+        }
+    }
+}
+
+impl<W: io::Write> fmt::Write for IoWriter<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|err| {
+            self.error = Some(err);
+            fmt::Error
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_escapes_html() {
+        assert_eq!(
+            escape_html("<a href=\"x\">Leafs & 'Habs'</a>"),
+            "&lt;a href=&quot;x&quot;&gt;Leafs &amp; &#39;Habs&#39;&lt;/a&gt;"
+        );
+        assert_eq!(escape_html("Go Leafs"), "Go Leafs");
+    }
+
+    #[test]
+    fn it_merges_class_and_style() {
+        let mut attrs = json!({"class":"prose-p","style":"color: red;","id":"a"})
+            .as_object()
+            .unwrap()
+            .clone();
+        let extra = json!({"class":"lead prose-p","style":"font-size: 2em","id":"b"});
+
+        merge_attributes(&mut attrs, extra.as_object().unwrap());
+
+        assert_eq!(
+            Value::Object(attrs),
+            json!({"class":"prose-p lead","style":"color: red; font-size: 2em","id":"b"})
+        );
+    }
 }
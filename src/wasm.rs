@@ -0,0 +1,55 @@
+use js_sys::JSON;
+use serde_json::Value;
+use wasm_bindgen::prelude::*;
+
+use crate::{ProseMirror, RenderConfig};
+
+// Round-trips through `JSON.stringify` so numbers and missing values come out
+// exactly as a JSON document stored by the editor would. Strings are taken to
+// already be JSON.
+fn json_value(value: &JsValue) -> Result<Value, JsError> {
+    if value.is_undefined() || value.is_null() {
+        return Ok(Value::Null);
+    }
+    let json = match value.as_string() {
+        Some(json) => json,
+        None => JSON::stringify(value)
+            .map_err(|_| JsError::new("value can't be converted to JSON"))?
+            .into(),
+    };
+    serde_json::from_str(&json).map_err(|err| JsError::new(&err.to_string()))
+}
+
+fn renderer(options: &JsValue) -> Result<ProseMirror, JsError> {
+    RenderConfig::from_json(&json_value(options)?)
+        .and_then(|config| config.build())
+        .map_err(|err| JsError::new(&err.to_string()))
+}
+
+// `options` is a `RenderConfig` object, rendering errors are thrown
+#[wasm_bindgen]
+pub fn render(json: JsValue, options: JsValue) -> Result<String, JsError> {
+    Renderer::new(options)?.render(json)
+}
+
+// Keeps the configured renderer around, for rendering many documents
+#[wasm_bindgen]
+pub struct Renderer {
+    prosemirror: ProseMirror,
+}
+
+#[wasm_bindgen]
+impl Renderer {
+    #[wasm_bindgen(constructor)]
+    pub fn new(options: JsValue) -> Result<Renderer, JsError> {
+        Ok(Renderer {
+            prosemirror: renderer(&options)?,
+        })
+    }
+
+    pub fn render(&self, json: JsValue) -> Result<String, JsError> {
+        self.prosemirror
+            .render(&json_value(&json)?)
+            .map_err(|err| JsError::new(&err.to_string()))
+    }
+}